@@ -58,6 +58,10 @@ pub enum Command {
 
         #[clap(long)]
         referral_fee_percent: u8,
+
+        /// Receiver of protocol fee, default to treasury
+        #[clap(long, default_value_t = Pubkey::default())]
+        protocol_fee_receiver: Pubkey,
     },
     /// Update config
     UpdateConfig {
@@ -108,6 +112,10 @@ pub enum Command {
 
         #[clap(long)]
         funding_amount: u64,
+
+        /// Time the reward period starts, defaults to the funding time
+        #[clap(long)]
+        reward_start_time: Option<u64>,
     },
 
     // update reward duration
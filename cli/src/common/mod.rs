@@ -1 +1,2 @@
 pub mod pda;
+pub mod reward;
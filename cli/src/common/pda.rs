@@ -2,8 +2,8 @@ use anchor_lang::prelude::Pubkey;
 use std::{cmp::max, cmp::min};
 
 use cp_amm::constants::seeds::{
-    CONFIG_PREFIX, POOL_AUTHORITY_PREFIX, POOL_PREFIX, REWARD_VAULT_PREFIX, TOKEN_BADGE_PREFIX,
-    TOKEN_VAULT_PREFIX,
+    CONFIG_PREFIX, POOL_AUTHORITY_PREFIX, POOL_PREFIX, REWARD_EXTENSION_PREFIX,
+    REWARD_VAULT_PREFIX, TOKEN_BADGE_PREFIX, TOKEN_VAULT_PREFIX,
 };
 use cp_amm::ID;
 
@@ -59,3 +59,7 @@ pub fn derive_reward_vault_pda(index: u8, pool: Pubkey) -> Pubkey {
     )
     .0
 }
+
+pub fn derive_reward_extension_pda(pool: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[REWARD_EXTENSION_PREFIX, pool.as_ref()], &ID).0
+}
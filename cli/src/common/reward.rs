@@ -0,0 +1,36 @@
+use std::ops::Deref;
+
+use anchor_client::{solana_sdk::signer::Signer, Program};
+use anchor_lang::prelude::Pubkey;
+use anyhow::*;
+use cp_amm::constants::NUM_REWARDS;
+use cp_amm::state::{Pool, RewardExtension, RewardInfo};
+
+use crate::common::pda::derive_reward_extension_pda;
+
+/// Reward extension of the pool, if it has one
+pub fn get_reward_extension(pool: Pubkey, pool_state: &Pool) -> Option<Pubkey> {
+    pool_state
+        .has_reward_extension()
+        .then(|| derive_reward_extension_pda(pool))
+}
+
+/// Reward indexes from NUM_REWARDS onwards are stored in the reward extension
+pub fn get_reward_info<C: Deref<Target = impl Signer> + Clone>(
+    program: &Program<C>,
+    pool: Pubkey,
+    pool_state: &Pool,
+    reward_index: usize,
+) -> Result<RewardInfo> {
+    if reward_index < NUM_REWARDS {
+        return Ok(pool_state.reward_infos[reward_index]);
+    }
+    let reward_extension =
+        get_reward_extension(pool, pool_state).context("Pool has no reward extension")?;
+    let reward_extension_state = program.account::<RewardExtension>(reward_extension)?;
+    reward_extension_state
+        .reward_infos
+        .get(reward_index - NUM_REWARDS)
+        .copied()
+        .context("Invalid reward index")
+}
@@ -19,6 +19,7 @@ pub struct CreateConfigParams {
     pub pool_creator_authority: Pubkey,
     pub activation_type: u8,
     pub collect_fee_mode: u8,
+    pub protocol_fee_receiver: Pubkey,
 }
 
 pub fn create_config<C: Deref<Target = impl Signer> + Clone>(
@@ -36,6 +37,7 @@ pub fn create_config<C: Deref<Target = impl Signer> + Clone>(
         sqrt_min_price,
         sqrt_max_price,
         collect_fee_mode,
+        protocol_fee_receiver,
     } = params;
 
     loop {
@@ -63,6 +65,7 @@ pub fn create_config<C: Deref<Target = impl Signer> + Clone>(
                 sqrt_max_price,
                 collect_fee_mode,
                 index,
+                protocol_fee_receiver,
             };
 
             let ix = instruction::CreateConfig { config_parameters };
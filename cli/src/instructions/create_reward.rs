@@ -8,11 +8,12 @@ use anchor_spl::token;
 use anyhow::*;
 use cp_amm::accounts;
 use cp_amm::instruction;
-use cp_amm::state::Pool;
+use cp_amm::state::{Pool, RewardExtension};
 
 use crate::common::pda::{
     derive_event_authority_pda, derive_pool_authority, derive_reward_vault_pda,
 };
+use crate::common::reward::get_reward_extension;
 
 pub struct InitializeRewardParams {
     pub pool: Pubkey,
@@ -33,12 +34,21 @@ pub fn create_reward<C: Deref<Target = impl Signer> + Clone>(
     } = params;
     let pool_authority = derive_pool_authority();
     let pool_state = program.account::<Pool>(pool).unwrap();
-    let reward_infos = pool_state.reward_infos;
+    let reward_extension = get_reward_extension(pool, &pool_state);
 
-    if reward_infos[0].initialized() && reward_infos[1].initialized() {
-        return Ok(());
+    // take the first uninitialized reward, the reward extension slots follow the pool ones
+    let mut reward_infos = pool_state.reward_infos.to_vec();
+    if let Some(reward_extension) = reward_extension {
+        let reward_extension_state = program.account::<RewardExtension>(reward_extension)?;
+        reward_infos.extend(reward_extension_state.reward_infos);
     }
-    let reward_index = if reward_infos[0].initialized() { 1 } else { 0 };
+    let Some(reward_index) = reward_infos
+        .iter()
+        .position(|reward_info| !reward_info.initialized())
+    else {
+        return Ok(());
+    };
+    let reward_index = u8::try_from(reward_index)?;
 
     let reward_vault = derive_reward_vault_pda(reward_index, pool);
     let event_authority = derive_event_authority_pda();
@@ -53,6 +63,7 @@ pub fn create_reward<C: Deref<Target = impl Signer> + Clone>(
         token_program: token::ID,
         event_authority,
         program: cp_amm::ID,
+        reward_extension,
     };
 
     let ix = instruction::InitializeReward {
@@ -12,12 +12,14 @@ use cp_amm::instruction;
 use cp_amm::state::Pool;
 
 use crate::common::pda::derive_event_authority_pda;
+use crate::common::reward::{get_reward_extension, get_reward_info};
 
 pub struct FundRewardParams {
     pub pool: Pubkey,
     pub reward_index: u8,
     pub funding_amount: u64,
     pub carry_forward: bool,
+    pub reward_start_time: Option<u64>,
 }
 
 pub fn funding_reward<C: Deref<Target = impl Signer> + Clone>(
@@ -31,10 +33,12 @@ pub fn funding_reward<C: Deref<Target = impl Signer> + Clone>(
         reward_index,
         funding_amount,
         carry_forward,
+        reward_start_time,
     } = params;
     let pool_state = program.account::<Pool>(pool).unwrap();
-    let reward_mint = pool_state.reward_infos[reward_index as usize].mint;
-    let reward_vault = pool_state.reward_infos[reward_index as usize].vault;
+    let reward_info = get_reward_info(program, pool, &pool_state, reward_index.into())?;
+    let reward_mint = reward_info.mint;
+    let reward_vault = reward_info.vault;
     let event_authority = derive_event_authority_pda();
     let funder_token_account = get_associated_token_address(&program.payer(), &reward_mint);
 
@@ -47,12 +51,14 @@ pub fn funding_reward<C: Deref<Target = impl Signer> + Clone>(
         token_program: token::ID,
         event_authority,
         program: cp_amm::ID,
+        reward_extension: get_reward_extension(pool, &pool_state),
     };
 
     let ix = instruction::FundReward {
         reward_index,
         amount: funding_amount,
         carry_forward,
+        reward_start_time,
     };
 
     let mut request_builder = program.request();
@@ -3,14 +3,11 @@ use std::ops::Deref;
 use anchor_client::solana_client::rpc_config::RpcSendTransactionConfig;
 use anchor_client::solana_sdk::instruction::Instruction;
 use anchor_client::{solana_sdk::pubkey::Pubkey, solana_sdk::signer::Signer, Program};
-use anchor_lang::InstructionData;
-use anchor_lang::ToAccountMetas;
 use anyhow::*;
 
-use cp_amm::instruction;
-use cp_amm::params::fee_parameters::{BaseFeeParameters, PoolFeeParameters};
-use cp_amm::state::Config;
-use cp_amm::{accounts, ConfigParameters};
+use cp_amm::params::fee_parameters::{BaseFeeParameters, ExtraFeeParameters, PoolFeeParameters};
+use cp_amm::state::{fee::FeeSchedulerMode, Config};
+use cp_amm::{accounts, instruction};
 
 use crate::common::pda::derive_event_authority_pda;
 
@@ -37,72 +34,47 @@ pub fn update_config<C: Deref<Target = impl Signer> + Clone>(
         referral_fee_percent,
     } = params;
 
-    let config_state = program.account::<Config>(config).unwrap();
+    let config_state = program.account::<Config>(config)?;
+    let current_pool_fees = config_state.pool_fees.to_pool_fee_parameters();
+    let current_extra_fees = config_state.pool_fees.to_extra_fee_parameters();
 
-    let event_authority = derive_event_authority_pda();
-    let mut request_builder = program.request();
-    // 1 close old config
-    let close_config_data = (instruction::CloseConfig {}).data();
-    let close_config_accounts = (accounts::CloseConfigCtx {
-        config,
-        admin: program.payer(),
-        rent_receiver: program.payer(),
-        event_authority,
-        program: cp_amm::ID,
-    })
-    .to_account_metas(None);
-
-    let close_config_ix = Instruction {
-        data: close_config_data,
-        accounts: close_config_accounts,
-        program_id: cp_amm::ID,
-    };
-
-    // 2. create config
+    // keep the settings that can't be passed from command line
+    let is_fee_curve_mode = base_fee.fee_scheduler_mode == u8::from(FeeSchedulerMode::FeeCurve);
     let pool_fees = PoolFeeParameters {
         base_fee,
         protocol_fee_percent,
         partner_fee_percent,
         referral_fee_percent,
-        dynamic_fee: None,
+        ..current_pool_fees
     };
-    let config_parameters = ConfigParameters {
-        pool_fees,
-        vault_config_key: config_state.vault_config_key,
-        pool_creator_authority: config_state.pool_creator_authority,
-        activation_type: config_state.activation_type,
-        sqrt_min_price: config_state.sqrt_min_price,
-        sqrt_max_price: config_state.sqrt_max_price,
-        collect_fee_mode: config_state.collect_fee_mode,
-        index: config_state.index,
+    let extra_fees = ExtraFeeParameters {
+        fee_curve: current_extra_fees.fee_curve.filter(|_| is_fee_curve_mode),
+        ..current_extra_fees
     };
 
-    let create_config_data = (instruction::CreateConfig { config_parameters }).data();
-
-    let create_config_accounts = (accounts::CreateConfigCtx {
+    let event_authority = derive_event_authority_pda();
+    let accounts = accounts::UpdateConfigCtx {
         config,
         admin: program.payer(),
-        system_program: anchor_client::solana_sdk::system_program::ID,
         event_authority,
         program: cp_amm::ID,
-    })
-    .to_account_metas(None);
+    };
 
-    let create_config_ix = Instruction {
-        data: create_config_data,
-        accounts: create_config_accounts,
-        program_id: cp_amm::ID,
+    let ix = instruction::UpdateConfig {
+        pool_fees,
+        extra_fees,
     };
 
-    //
+    let mut request_builder = program.request();
+
     if let Some(compute_unit_price) = compute_unit_price {
         request_builder = request_builder.instruction(compute_unit_price);
     }
 
-    request_builder = request_builder.instruction(close_config_ix);
-    request_builder = request_builder.instruction(create_config_ix);
-
-    let signature = request_builder.send_with_spinner_and_config(transaction_config);
+    let signature = request_builder
+        .accounts(accounts)
+        .args(ix)
+        .send_with_spinner_and_config(transaction_config);
 
     println!("Update config {config} Signature: {signature:#?}");
 
@@ -12,6 +12,7 @@ use cp_amm::instruction;
 use cp_amm::state::Pool;
 
 use crate::common::pda::{derive_event_authority_pda, derive_pool_authority};
+use crate::common::reward::{get_reward_extension, get_reward_info};
 
 pub struct WithdrawIneligibleRewardParams {
     pub pool: Pubkey,
@@ -27,7 +28,7 @@ pub fn withdraw_ineligible_reward<C: Deref<Target = impl Signer> + Clone>(
     let WithdrawIneligibleRewardParams { pool, reward_index } = params;
 
     let pool_state = program.account::<Pool>(pool).unwrap();
-    let reward_info = pool_state.reward_infos[reward_index as usize];
+    let reward_info = get_reward_info(program, pool, &pool_state, reward_index.into())?;
 
     let reward_vault = reward_info.vault;
     let reward_mint = reward_info.mint;
@@ -46,6 +47,7 @@ pub fn withdraw_ineligible_reward<C: Deref<Target = impl Signer> + Clone>(
         event_authority,
         token_program: token::ID,
         program: cp_amm::ID,
+        reward_extension: get_reward_extension(pool, &pool_state),
     };
 
     let ix = instruction::WithdrawIneligibleReward { reward_index };
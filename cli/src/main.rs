@@ -85,6 +85,7 @@ fn main() -> Result<()> {
             protocol_fee_percent,
             partner_fee_percent,
             referral_fee_percent,
+            protocol_fee_receiver,
         } => {
             let pool_fee = PoolFeeParameters {
                 base_fee: BaseFeeParameters {
@@ -105,6 +106,7 @@ fn main() -> Result<()> {
                 activation_type,
                 collect_fee_mode,
                 pool_fees: pool_fee,
+                protocol_fee_receiver,
             };
             create_config(params, &program, transaction_config, compute_unit_price_ix)?;
         }
@@ -155,12 +157,14 @@ fn main() -> Result<()> {
             reward_index,
             carry_forward,
             funding_amount,
+            reward_start_time,
         } => {
             let params = FundRewardParams {
                 pool,
                 reward_index,
                 funding_amount,
                 carry_forward,
+                reward_start_time,
             };
             funding_reward(params, &program, transaction_config, compute_unit_price_ix)?;
         }
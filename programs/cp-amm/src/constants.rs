@@ -19,6 +19,9 @@ pub const BIN_STEP_BPS_DEFAULT: u16 = 1;
 //  bin_step << 64 / BASIS_POINT_MAX
 pub const BIN_STEP_BPS_U128_DEFAULT: u128 = 1844674407370955;
 
+// bin steps in bps supported by dynamic fee
+pub const ALLOWED_BIN_STEPS: [u16; 5] = [1, 5, 10, 25, 100];
+
 static_assertions::const_assert_eq!(LIQUIDITY_SCALE + REWARD_RATE_SCALE, TOTAL_REWARD_SCALE);
 
 pub const BASIS_POINT_MAX: u64 = 10_000;
@@ -30,11 +33,31 @@ pub const U24_MAX: u32 = 0xffffff;
 // Number of rewards supported by pool
 pub const NUM_REWARDS: usize = 2;
 
+// Number of rewards supported by pool reward extension
+pub const NUM_EXTENSION_REWARDS: usize = 2;
+
+// Number of fee curve points after the cliff fee
+pub const MAX_FEE_CURVE_POINTS: usize = 2;
+
+// Number of observations kept by pool oracle
+pub const NUM_ORACLE_OBSERVATIONS: usize = 64;
+
+// Minimum seconds between two oracle observations, swaps within it update the latest observation
+pub const ORACLE_OBSERVATION_INTERVAL: u64 = 60;
+
 // Minimum reward duration
 pub const MIN_REWARD_DURATION: u64 = 1;
 
 pub const MAX_REWARD_DURATION: u64 = 31536000; // 1 year = 365 * 24 * 3600
 
+pub const MAX_REWARD_START_DELAY: u64 = 31536000; // 1 year = 365 * 24 * 3600
+
+// Reward index the pool partner or creator can initialize, the others are left to admin
+pub const CREATOR_REWARD_INDEX: usize = 1;
+
+// Minimum reward duration of the creator reward
+pub const MIN_CREATOR_REWARD_DURATION: u64 = 86400; // 1 day
+
 pub mod activation {
     #[cfg(not(feature = "local"))]
     pub const SLOT_BUFFER: u64 = 9000; // 1 slot = 400 mls => 1 hour
@@ -114,6 +137,10 @@ pub mod seeds {
     pub const TOKEN_BADGE_PREFIX: &[u8] = b"token_badge";
     pub const REWARD_VAULT_PREFIX: &[u8] = b"reward_vault";
     pub const CLAIM_FEE_OPERATOR_PREFIX: &[u8] = b"cf_operator";
+    pub const REFERRAL_ACCOUNT_PREFIX: &[u8] = b"referral";
+    pub const ORACLE_PREFIX: &[u8] = b"oracle";
+    pub const REWARD_EXTENSION_PREFIX: &[u8] = b"reward_extension";
+    pub const POSITION_REWARD_EXTENSION_PREFIX: &[u8] = b"position_reward_extension";
 }
 
 pub mod treasury {
@@ -106,6 +106,42 @@ pub fn get_delta_amount_b_unsigned_unchecked(
     }
 }
 
+/// Gets the liquidity for given amount_a and price range, rounding down
+///
+/// # Formula
+///
+/// * `L = Δa * √P_upper * √P_lower / (√P_upper - √P_lower)`
+pub fn get_liquidity_delta_from_amount_a(
+    lower_sqrt_price: u128,
+    upper_sqrt_price: u128,
+    amount_a: u64,
+) -> Result<U256> {
+    let numerator_1 = U256::from(amount_a).safe_mul(U256::from(lower_sqrt_price))?;
+    let numerator_2 = U256::from(upper_sqrt_price);
+    let denominator = U256::from(upper_sqrt_price.safe_sub(lower_sqrt_price)?);
+
+    let result = mul_div_u256(numerator_1, numerator_2, denominator, Rounding::Down)
+        .ok_or(PoolError::MathOverflow)?;
+    Ok(result)
+}
+
+/// Gets the liquidity for given amount_b and price range, rounding down
+///
+/// # Formula
+///
+/// * `L = Δb / (√P_upper - √P_lower)`
+pub fn get_liquidity_delta_from_amount_b(
+    lower_sqrt_price: u128,
+    upper_sqrt_price: u128,
+    amount_b: u64,
+) -> Result<U256> {
+    let denominator = U256::from(upper_sqrt_price.safe_sub(lower_sqrt_price)?);
+    let result = U256::from(amount_b)
+        .safe_shl((RESOLUTION as usize) * 2)?
+        .safe_div(denominator)?;
+    Ok(result)
+}
+
 /// Gets the next sqrt price given an input amount of token_a or token_b
 /// Throws if price or liquidity are 0, or if the next price is out of bounds
 pub fn get_next_sqrt_price_from_input(
@@ -197,3 +233,87 @@ pub fn get_next_sqrt_price_from_amount_b_rounding_down(
     let result = U256::from(sqrt_price).safe_add(quotient)?;
     Ok(result.try_into().map_err(|_| PoolError::TypeCastFailed)?)
 }
+
+/// Gets the next sqrt price given an output amount of token_a or token_b
+/// Throws if price or liquidity are 0, or if the output drains the available liquidity
+pub fn get_next_sqrt_price_from_output(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount_out: u64,
+    a_for_b: bool,
+) -> Result<u128> {
+    assert!(sqrt_price > 0);
+    assert!(liquidity > 0);
+
+    // round to make sure that we pass the target price
+    if a_for_b {
+        get_next_sqrt_price_from_output_amount_b_rounding_down(sqrt_price, liquidity, amount_out)
+    } else {
+        get_next_sqrt_price_from_output_amount_a_rounding_up(sqrt_price, liquidity, amount_out)
+    }
+}
+
+/// Gets the next sqrt price √P' given an output delta of token_a
+///
+/// Always round up, so the price moves a bit further than needed and the exact output is met
+///
+/// # Formula
+///
+/// * `√P' = √P * L / (L - Δx * √P)`
+///
+/// # Proof
+///
+/// For constant L,
+///
+///  L = x * √P
+///  x' = x - Δx
+///  x' * √P' = x * √P
+///  √P' = (x * √P) / (x - Δx)
+///  x = L/√P
+///  √P' = √P * L / (L - Δx * √P)
+///
+pub fn get_next_sqrt_price_from_output_amount_a_rounding_up(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount: u64,
+) -> Result<u128> {
+    if amount == 0 {
+        return Ok(sqrt_price);
+    }
+    let sqrt_price = U256::from(sqrt_price);
+    let liquidity = U256::from(liquidity);
+
+    let product = U256::from(amount).safe_mul(sqrt_price)?;
+    // the pool doesn't hold enough token_a
+    require!(liquidity > product, PoolError::PriceRangeViolation);
+
+    let denominator = liquidity.safe_sub(product)?;
+    let result = mul_div_u256(liquidity, sqrt_price, denominator, Rounding::Up)
+        .ok_or(PoolError::MathOverflow)?;
+    Ok(result.try_into().map_err(|_| PoolError::TypeCastFailed)?)
+}
+
+/// Gets the next sqrt price √P' given an output delta of token_b
+///
+/// Always round down, so the price moves a bit further than needed and the exact output is met
+///
+/// # Formula
+///
+/// * `√P' = √P - Δy / L`
+///
+pub fn get_next_sqrt_price_from_output_amount_b_rounding_down(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount: u64,
+) -> Result<u128> {
+    let quotient = U256::from(amount)
+        .safe_shl((RESOLUTION * 2) as usize)?
+        .div_ceil(U256::from(liquidity));
+
+    let sqrt_price = U256::from(sqrt_price);
+    // the pool doesn't hold enough token_b
+    require!(sqrt_price > quotient, PoolError::PriceRangeViolation);
+
+    let result = sqrt_price.safe_sub(quotient)?;
+    Ok(result.try_into().map_err(|_| PoolError::TypeCastFailed)?)
+}
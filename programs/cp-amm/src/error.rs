@@ -121,4 +121,40 @@ pub enum PoolError {
 
     #[msg("Fee inverse is incorrect")]
     FeeInverseIsIncorrect,
+
+    #[msg("Invalid sqrt price limit")]
+    InvalidSqrtPriceLimit,
+
+    #[msg("Invalid split position parameters")]
+    InvalidSplitPositionParameters,
+
+    #[msg("Position has vested liquidity")]
+    PositionHasVestedLiquidity,
+
+    #[msg("Invalid fee rate limiter")]
+    InvalidFeeRateLimiter,
+
+    #[msg("Invalid pool fees authority")]
+    InvalidPoolFeesAuthority,
+
+    #[msg("Invalid referral account")]
+    InvalidReferralAccount,
+
+    #[msg("Invalid oracle account")]
+    InvalidOracleAccount,
+
+    #[msg("Invalid oracle window")]
+    InvalidOracleWindow,
+
+    #[msg("Invalid position account")]
+    InvalidPositionAccount,
+
+    #[msg("Invalid reward extension")]
+    InvalidRewardExtension,
+
+    #[msg("Invalid reward creator")]
+    InvalidRewardCreator,
+
+    #[msg("Invalid reward start time")]
+    InvalidRewardStartTime,
 }
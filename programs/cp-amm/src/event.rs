@@ -2,10 +2,29 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    params::fee_parameters::PoolFeeParameters, state::SwapResult, AddLiquidityParameters,
-    RemoveLiquidityParameters, SwapParameters,
+    params::fee_parameters::{ExtraFeeParameters, PoolFeeParameters},
+    state::{SplitAmountInfo, SwapResult},
+    AddLiquidityParameters, RemoveLiquidityParameters, SplitPositionParameters,
+    SwapExactOutParameters, SwapParameters,
 };
 
+/// Update config
+#[event]
+pub struct EvtUpdateConfig {
+    /// Config pubkey
+    pub config: Pubkey,
+    /// admin pk
+    pub admin: Pubkey,
+    /// Pool fees before the update
+    pub old_pool_fees: PoolFeeParameters,
+    /// Pool fees after the update
+    pub new_pool_fees: PoolFeeParameters,
+    /// Extra fees before the update
+    pub old_extra_fees: ExtraFeeParameters,
+    /// Extra fees after the update
+    pub new_extra_fees: ExtraFeeParameters,
+}
+
 /// Close config
 #[event]
 pub struct EvtCloseConfig {
@@ -27,6 +46,7 @@ pub struct EvtCreateConfig {
     pub collect_fee_mode: u8,
     pub index: u64,
     pub config: Pubkey,
+    pub protocol_fee_receiver: Pubkey,
 }
 
 /// Create token badge
@@ -119,6 +139,29 @@ pub struct EvtSwap {
     pub has_referral: bool,
     pub params: SwapParameters,
     pub swap_result: SwapResult,
+    /// Input amount the pool swapped, transfer fee excluded. Only the consumed part on a partial fill
+    pub actual_amount_in: u64,
+    pub current_timestamp: u64,
+}
+
+/// Emitted after EvtSwap by swap_with_price_limit
+#[event]
+pub struct EvtSwapWithPriceLimit {
+    pub pool: Pubkey,
+    pub sqrt_price_limit: u128,
+    /// Input amount transferred from the user, transfer fee included. Less than params.amount_in on partial fill
+    pub consumed_amount_in: u64,
+    /// Input amount the pool swapped, transfer fee excluded. Same as EvtSwap::actual_amount_in
+    pub actual_amount_in: u64,
+}
+
+#[event]
+pub struct EvtSwapExactOut {
+    pub pool: Pubkey,
+    pub trade_direction: u8,
+    pub has_referral: bool,
+    pub params: SwapExactOutParameters,
+    pub swap_result: SwapResult,
     pub actual_amount_in: u64,
     pub current_timestamp: u64,
 }
@@ -143,9 +186,31 @@ pub struct EvtPermanentLockPosition {
     pub total_permanent_locked_liquidity: u128,
 }
 
+#[event]
+pub struct EvtSplitPosition {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub position: Pubkey,
+    pub new_position: Pubkey,
+    pub params: SplitPositionParameters,
+    pub split_amount: SplitAmountInfo,
+}
+
+#[event]
+pub struct EvtMergePositions {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub position: Pubkey,
+    pub source_position: Pubkey,
+    pub source_position_nft_mint: Pubkey,
+}
+
 #[event]
 pub struct EvtClaimProtocolFee {
     pub pool: Pubkey,
+    pub max_amount_a: u64,
+    pub max_amount_b: u64,
     pub token_a_amount: u64,
     pub token_b_amount: u64,
 }
@@ -157,12 +222,65 @@ pub struct EvtClaimPartnerFee {
     pub token_b_amount: u64,
 }
 
+#[event]
+pub struct EvtCreateReferralAccount {
+    pub referral_account: Pubkey,
+    pub referrer: Pubkey,
+    pub token_mint: Pubkey,
+}
+
+#[event]
+pub struct EvtClaimReferralFee {
+    pub pool: Pubkey,
+    pub referral_account: Pubkey,
+    pub referrer: Pubkey,
+    pub token_mint: Pubkey,
+    pub max_amount: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EvtInitializeOracle {
+    pub pool: Pubkey,
+    pub oracle: Pubkey,
+}
+
+#[event]
+pub struct EvtInitializeRewardExtension {
+    pub pool: Pubkey,
+    pub reward_extension: Pubkey,
+}
+
+#[event]
+pub struct EvtCreatePositionRewardExtension {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub position_reward_extension: Pubkey,
+}
+
 #[event]
 pub struct EvtSetPoolStatus {
     pub pool: Pubkey,
     pub status: u8,
 }
 
+#[event]
+pub struct EvtUpdateProtocolFeeReceiver {
+    pub pool: Pubkey,
+    pub old_protocol_fee_receiver: Pubkey,
+    pub new_protocol_fee_receiver: Pubkey,
+}
+
+#[event]
+pub struct EvtUpdatePoolFees {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub old_pool_fees: PoolFeeParameters,
+    pub new_pool_fees: PoolFeeParameters,
+    pub old_extra_fees: ExtraFeeParameters,
+    pub new_extra_fees: ExtraFeeParameters,
+}
+
 // Initialize reward
 #[event]
 pub struct EvtInitializeReward {
@@ -192,6 +310,8 @@ pub struct EvtFundReward {
     pub amount: u64,
     // Amount excluded transfer fee
     pub transfer_fee_excluded_amount_in: u64,
+    // Time the reward period starts
+    pub reward_start_time: u64,
 }
 
 #[event]
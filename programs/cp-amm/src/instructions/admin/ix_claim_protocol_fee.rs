@@ -5,7 +5,7 @@ use crate::{
     constants::seeds::POOL_AUTHORITY_PREFIX,
     state::{ClaimFeeOperator, Pool},
     token::transfer_from_pool,
-    treasury, EvtClaimProtocolFee,
+    EvtClaimProtocolFee,
 };
 
 /// Accounts for withdraw protocol fees
@@ -33,23 +33,23 @@ pub struct ClaimProtocolFeesCtx<'info> {
     /// The mint of token b
     pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// The treasury token a account
+    /// The protocol fee receiver token a account, token a is not claimed when it's missing
     #[account(
         mut,
-        associated_token::authority = treasury::ID,
+        associated_token::authority = pool.load()?.get_protocol_fee_receiver(),
         associated_token::mint = token_a_mint,
         associated_token::token_program = token_a_program,
     )]
-    pub token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_a_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    /// The treasury token b account
+    /// The protocol fee receiver token b account, token b is not claimed when it's missing
     #[account(
         mut,
-        associated_token::authority = treasury::ID,
+        associated_token::authority = pool.load()?.get_protocol_fee_receiver(),
         associated_token::mint = token_b_mint,
         associated_token::token_program = token_b_program,
     )]
-    pub token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_b_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     /// Claim fee operator
     #[account(has_one = operator)]
@@ -66,33 +66,55 @@ pub struct ClaimProtocolFeesCtx<'info> {
 }
 
 /// Withdraw protocol fees. Permissionless.
-pub fn handle_claim_protocol_fee(ctx: Context<ClaimProtocolFeesCtx>) -> Result<()> {
+pub fn handle_claim_protocol_fee(
+    ctx: Context<ClaimProtocolFeesCtx>,
+    max_amount_a: u64,
+    max_amount_b: u64,
+) -> Result<()> {
     let mut pool = ctx.accounts.pool.load_mut()?;
 
-    let (token_a_amount, token_b_amount) = pool.claim_protocol_fee();
-
-    transfer_from_pool(
-        ctx.accounts.pool_authority.to_account_info(),
-        &ctx.accounts.token_a_mint,
-        &ctx.accounts.token_a_vault,
-        &ctx.accounts.token_a_account,
-        &ctx.accounts.token_a_program,
-        token_a_amount,
-        ctx.bumps.pool_authority,
-    )?;
-
-    transfer_from_pool(
-        ctx.accounts.pool_authority.to_account_info(),
-        &ctx.accounts.token_b_mint,
-        &ctx.accounts.token_b_vault,
-        &ctx.accounts.token_b_account,
-        &ctx.accounts.token_b_program,
-        token_b_amount,
-        ctx.bumps.pool_authority,
-    )?;
+    // skip the side without receiver token account
+    let claimable_max_amount_a = if ctx.accounts.token_a_account.is_some() {
+        max_amount_a
+    } else {
+        0
+    };
+    let claimable_max_amount_b = if ctx.accounts.token_b_account.is_some() {
+        max_amount_b
+    } else {
+        0
+    };
+    let (token_a_amount, token_b_amount) =
+        pool.claim_protocol_fee(claimable_max_amount_a, claimable_max_amount_b)?;
+
+    if let Some(token_a_account) = &ctx.accounts.token_a_account {
+        transfer_from_pool(
+            ctx.accounts.pool_authority.to_account_info(),
+            &ctx.accounts.token_a_mint,
+            &ctx.accounts.token_a_vault,
+            token_a_account,
+            &ctx.accounts.token_a_program,
+            token_a_amount,
+            ctx.bumps.pool_authority,
+        )?;
+    }
+
+    if let Some(token_b_account) = &ctx.accounts.token_b_account {
+        transfer_from_pool(
+            ctx.accounts.pool_authority.to_account_info(),
+            &ctx.accounts.token_b_mint,
+            &ctx.accounts.token_b_vault,
+            token_b_account,
+            &ctx.accounts.token_b_program,
+            token_b_amount,
+            ctx.bumps.pool_authority,
+        )?;
+    }
 
     emit_cpi!(EvtClaimProtocolFee {
         pool: ctx.accounts.pool.key(),
+        max_amount_a,
+        max_amount_b,
         token_a_amount,
         token_b_amount
     });
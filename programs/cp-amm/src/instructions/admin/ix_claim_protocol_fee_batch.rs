@@ -0,0 +1,131 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::seeds::POOL_AUTHORITY_PREFIX,
+    state::{ClaimFeeOperator, Pool},
+    token::transfer_from_pool,
+    EvtClaimProtocolFee, PoolError,
+};
+
+/// Accounts for withdraw protocol fees of many pools. Accounts of each pool are passed through remaining accounts
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimProtocolFeesBatchCtx<'info> {
+    /// CHECK: pool authority
+    #[account(seeds = [POOL_AUTHORITY_PREFIX], bump)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// Claim fee operator
+    #[account(has_one = operator)]
+    pub claim_fee_operator: AccountLoader<'info, ClaimFeeOperator>,
+
+    /// Operator
+    pub operator: Signer<'info>,
+}
+
+/// Accounts of a pool in remaining accounts, same constraints as ClaimProtocolFeesCtx
+#[derive(Accounts)]
+pub struct ClaimProtocolFeeRemainingAccount<'info> {
+    #[account(mut, has_one = token_a_vault, has_one = token_b_vault, has_one = token_a_mint, has_one = token_b_mint)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// The vault token account for input token
+    #[account(mut, token::token_program = token_a_program, token::mint = token_a_mint)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for output token
+    #[account(mut, token::token_program = token_b_program, token::mint = token_b_mint)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token a
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token b
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The protocol fee receiver token a account
+    #[account(
+        mut,
+        associated_token::authority = pool.load()?.get_protocol_fee_receiver(),
+        associated_token::mint = token_a_mint,
+        associated_token::token_program = token_a_program,
+    )]
+    pub token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The protocol fee receiver token b account
+    #[account(
+        mut,
+        associated_token::authority = pool.load()?.get_protocol_fee_receiver(),
+        associated_token::mint = token_b_mint,
+        associated_token::token_program = token_b_program,
+    )]
+    pub token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token a program
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token b program
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+/// Withdraw protocol fees of many pools. Pools without protocol fee are skipped.
+pub fn handle_claim_protocol_fee_batch<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ClaimProtocolFeesBatchCtx<'info>>,
+) -> Result<()> {
+    let mut remaining_accounts = ctx.remaining_accounts;
+    require!(!remaining_accounts.is_empty(), PoolError::InvalidInput);
+
+    while !remaining_accounts.is_empty() {
+        let accounts = ClaimProtocolFeeRemainingAccount::try_accounts(
+            &crate::ID,
+            &mut remaining_accounts,
+            &[],
+            &mut ClaimProtocolFeeRemainingAccountBumps {},
+            &mut BTreeSet::new(),
+        )?;
+
+        let mut pool = accounts.pool.load_mut()?;
+        if pool.protocol_a_fee == 0 && pool.protocol_b_fee == 0 {
+            continue;
+        }
+
+        let (token_a_amount, token_b_amount) = pool.claim_protocol_fee(u64::MAX, u64::MAX)?;
+
+        if token_a_amount > 0 {
+            transfer_from_pool(
+                ctx.accounts.pool_authority.to_account_info(),
+                &accounts.token_a_mint,
+                &accounts.token_a_vault,
+                &accounts.token_a_account,
+                &accounts.token_a_program,
+                token_a_amount,
+                ctx.bumps.pool_authority,
+            )?;
+        }
+
+        if token_b_amount > 0 {
+            transfer_from_pool(
+                ctx.accounts.pool_authority.to_account_info(),
+                &accounts.token_b_mint,
+                &accounts.token_b_vault,
+                &accounts.token_b_account,
+                &accounts.token_b_program,
+                token_b_amount,
+                ctx.bumps.pool_authority,
+            )?;
+        }
+
+        emit_cpi!(EvtClaimProtocolFee {
+            pool: accounts.pool.key(),
+            max_amount_a: u64::MAX,
+            max_amount_b: u64::MAX,
+            token_a_amount,
+            token_b_amount
+        });
+    }
+
+    Ok(())
+}
@@ -7,7 +7,7 @@ use crate::{
     event,
     params::{
         activation::ActivationParams,
-        fee_parameters::{PartnerInfo, PoolFeeParameters},
+        fee_parameters::{ExtraFeeParameters, PartnerInfo, PoolFeeParameters},
     },
     state::{CollectFeeMode, Config},
     PoolError,
@@ -23,6 +23,8 @@ pub struct ConfigParameters {
     pub activation_type: u8,
     pub collect_fee_mode: u8,
     pub index: u64,
+    /// Receiver of protocol fee, Pubkey::default means treasury
+    pub protocol_fee_receiver: Pubkey,
 }
 
 #[event_cpi]
@@ -47,6 +49,7 @@ pub struct CreateConfigCtx<'info> {
 pub fn handle_create_config(
     ctx: Context<CreateConfigCtx>,
     config_parameters: ConfigParameters,
+    extra_fees: ExtraFeeParameters,
 ) -> Result<()> {
     let ConfigParameters {
         pool_fees,
@@ -57,6 +60,7 @@ pub fn handle_create_config(
         sqrt_max_price,
         collect_fee_mode,
         index,
+        protocol_fee_receiver,
     } = config_parameters;
 
     require!(
@@ -76,7 +80,9 @@ pub fn handle_create_config(
     );
 
     // validate fee
-    pool_fees.validate()?;
+    pool_fees.validate(&extra_fees)?;
+    pool_fees.validate_collect_fee_mode(collect_fee_mode)?;
+    extra_fees.validate_fee_curve_duration(activation_type)?;
 
     let has_alpha_vault = vault_config_key.ne(&Pubkey::default());
 
@@ -102,13 +108,14 @@ pub fn handle_create_config(
     let mut config = ctx.accounts.config.load_init()?;
     config.init(
         index,
-        &pool_fees,
+        pool_fees.to_pool_fees_config(&extra_fees),
         vault_config_key,
         pool_creator_authority,
         activation_type,
         sqrt_min_price,
         sqrt_max_price,
         collect_fee_mode.into(),
+        protocol_fee_receiver,
     );
 
     emit_cpi!(event::EvtCreateConfig {
@@ -121,6 +128,7 @@ pub fn handle_create_config(
         sqrt_min_price,
         sqrt_max_price,
         index,
+        protocol_fee_receiver,
     });
 
     Ok(())
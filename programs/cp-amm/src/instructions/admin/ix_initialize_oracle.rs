@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin,
+    constants::seeds::ORACLE_PREFIX,
+    state::{Oracle, Pool},
+    EvtInitializeOracle, PoolError,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeOracleCtx<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [
+            ORACLE_PREFIX,
+            pool.key().as_ref(),
+        ],
+        bump,
+        space = 8 + Oracle::INIT_SPACE
+    )]
+    pub oracle: AccountLoader<'info, Oracle>,
+
+    #[account(
+        mut,
+        constraint = assert_eq_admin(admin.key()) @ PoolError::InvalidAdmin,
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_initialize_oracle(ctx: Context<InitializeOracleCtx>) -> Result<()> {
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let mut oracle = ctx.accounts.oracle.load_init()?;
+
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    oracle.initialize(ctx.accounts.pool.key(), current_timestamp);
+    pool.oracle_flag = 1;
+
+    emit_cpi!(EvtInitializeOracle {
+        pool: ctx.accounts.pool.key(),
+        oracle: ctx.accounts.oracle.key(),
+    });
+
+    Ok(())
+}
@@ -5,11 +5,11 @@ use crate::{
     assert_eq_admin,
     constants::{
         seeds::{POOL_AUTHORITY_PREFIX, REWARD_VAULT_PREFIX},
-        MAX_REWARD_DURATION, MIN_REWARD_DURATION, NUM_REWARDS,
+        CREATOR_REWARD_INDEX, MAX_REWARD_DURATION, MIN_REWARD_DURATION,
     },
     error::PoolError,
     event::EvtInitializeReward,
-    state::Pool,
+    state::{get_reward_info, get_reward_info_mut, load_reward_extension, Pool, RewardExtension},
     token::{get_token_program_flags, is_supported_mint, is_token_badge_initialized},
 };
 
@@ -45,20 +45,30 @@ pub struct InitializeRewardCtx<'info> {
     pub token_program: Interface<'info, TokenInterface>,
 
     pub system_program: Program<'info, System>,
+
+    /// Reward extension of the pool, required for reward indexes from NUM_REWARDS onwards
+    #[account(mut)]
+    pub reward_extension: Option<AccountLoader<'info, RewardExtension>>,
 }
 
 impl<'info> InitializeRewardCtx<'info> {
     fn validate(&self, reward_index: usize, reward_duration: u64) -> Result<()> {
         let pool = self.pool.load()?;
-
-        require!(reward_index < NUM_REWARDS, PoolError::InvalidRewardIndex);
+        let reward_extension =
+            load_reward_extension(&pool, self.pool.key(), self.reward_extension.as_ref())?;
 
         require!(
             reward_duration >= MIN_REWARD_DURATION && reward_duration <= MAX_REWARD_DURATION,
             PoolError::InvalidRewardDuration
         );
 
-        let reward_info = &pool.reward_infos[reward_index];
+        // the creator reward index is reserved for the pool partner or creator, so admin can't take it from them
+        require!(
+            reward_index != CREATOR_REWARD_INDEX || !pool.has_reward_creator(),
+            PoolError::InvalidRewardIndex
+        );
+
+        let reward_info = get_reward_info(&pool, reward_extension.as_deref(), reward_index)?;
         require!(!reward_info.initialized(), PoolError::RewardInitialized);
 
         Ok(())
@@ -90,7 +100,12 @@ pub fn handle_initialize_reward<'c: 'info, 'info>(
     ctx.accounts.validate(index, reward_duration)?;
 
     let mut pool = ctx.accounts.pool.load_mut()?;
-    let reward_info = &mut pool.reward_infos[index];
+    let mut reward_extension = load_reward_extension(
+        &pool,
+        ctx.accounts.pool.key(),
+        ctx.accounts.reward_extension.as_ref(),
+    )?;
+    let reward_info = get_reward_info_mut(&mut pool, reward_extension.as_deref_mut(), index)?;
 
     reward_info.init_reward(
         ctx.accounts.reward_mint.key(),
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin,
+    constants::seeds::REWARD_EXTENSION_PREFIX,
+    state::{Pool, RewardExtension},
+    EvtInitializeRewardExtension, PoolError,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeRewardExtensionCtx<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [
+            REWARD_EXTENSION_PREFIX,
+            pool.key().as_ref(),
+        ],
+        bump,
+        space = 8 + RewardExtension::INIT_SPACE
+    )]
+    pub reward_extension: AccountLoader<'info, RewardExtension>,
+
+    #[account(
+        mut,
+        constraint = assert_eq_admin(admin.key()) @ PoolError::InvalidAdmin,
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_initialize_reward_extension(
+    ctx: Context<InitializeRewardExtensionCtx>,
+) -> Result<()> {
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let mut reward_extension = ctx.accounts.reward_extension.load_init()?;
+
+    reward_extension.initialize(ctx.accounts.pool.key());
+    pool.reward_extension_flag = 1;
+
+    emit_cpi!(EvtInitializeRewardExtension {
+        pool: ctx.accounts.pool.key(),
+        reward_extension: ctx.accounts.reward_extension.key(),
+    });
+
+    Ok(())
+}
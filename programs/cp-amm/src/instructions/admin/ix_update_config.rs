@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, event,
+    params::fee_parameters::{ExtraFeeParameters, PartnerInfo, PoolFeeParameters},
+    state::Config,
+    PoolError,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateConfigCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(constraint = assert_eq_admin(admin.key()) @ PoolError::InvalidAdmin)]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_update_config(
+    ctx: Context<UpdateConfigCtx>,
+    pool_fees: PoolFeeParameters,
+    extra_fees: ExtraFeeParameters,
+) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+
+    // validate fee
+    pool_fees.validate(&extra_fees)?;
+    pool_fees.validate_collect_fee_mode(config.collect_fee_mode)?;
+    extra_fees.validate_fee_curve_duration(config.activation_type)?;
+
+    let partner_info = PartnerInfo {
+        partner_authority: config.pool_creator_authority,
+        fee_percent: pool_fees.partner_fee_percent,
+        ..Default::default()
+    };
+    partner_info.validate()?;
+
+    let old_pool_fees = config.pool_fees.to_pool_fee_parameters();
+    let old_extra_fees = config.pool_fees.to_extra_fee_parameters();
+    config.update_pool_fees(&pool_fees, &extra_fees);
+
+    emit_cpi!(event::EvtUpdateConfig {
+        config: ctx.accounts.config.key(),
+        admin: ctx.accounts.admin.key(),
+        old_pool_fees,
+        new_pool_fees: pool_fees,
+        old_extra_fees,
+        new_extra_fees: extra_fees,
+    });
+
+    Ok(())
+}
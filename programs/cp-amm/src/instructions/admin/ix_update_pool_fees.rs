@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin,
+    params::fee_parameters::{ExtraFeeParameters, PoolFeeParameters},
+    state::{Pool, PoolType},
+    EvtUpdatePoolFees, PoolError,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdatePoolFeesCtx<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// Admin, or partner of a pool created from config
+    pub authority: Signer<'info>,
+}
+
+impl UpdatePoolFeesCtx<'_> {
+    fn validate(
+        &self,
+        pool_fees: &PoolFeeParameters,
+        extra_fees: &ExtraFeeParameters,
+    ) -> Result<()> {
+        let pool = self.pool.load()?;
+        let authority = self.authority.key();
+
+        let pool_type =
+            PoolType::try_from(pool.pool_type).map_err(|_| PoolError::TypeCastFailed)?;
+        let is_admin = assert_eq_admin(authority);
+        let is_partner = pool_type == PoolType::Permissionless
+            && pool.partner.ne(&Pubkey::default())
+            && pool.partner.eq(&authority);
+        require!(is_admin || is_partner, PoolError::InvalidPoolFeesAuthority);
+
+        // fee split decides the protocol revenue, so only admin can change it
+        if !is_admin {
+            require!(
+                pool_fees.protocol_fee_percent == pool.pool_fees.protocol_fee_percent
+                    && pool_fees.partner_fee_percent == pool.pool_fees.partner_fee_percent
+                    && pool_fees.referral_fee_percent == pool.pool_fees.referral_fee_percent,
+                PoolError::InvalidPoolFeesAuthority
+            );
+        }
+
+        pool_fees.validate(extra_fees)?;
+        pool_fees.validate_collect_fee_mode(pool.collect_fee_mode)?;
+        extra_fees.validate_fee_curve_duration(pool.activation_type)?;
+
+        match pool_type {
+            PoolType::Customizable => pool_fees.validate_for_customizable_pool()?,
+            PoolType::Permissionless => {
+                if pool.partner.eq(&Pubkey::default()) {
+                    require!(pool_fees.partner_fee_percent == 0, PoolError::InvalidFee);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn handle_update_pool_fees(
+    ctx: Context<UpdatePoolFeesCtx>,
+    pool_fees: PoolFeeParameters,
+    extra_fees: ExtraFeeParameters,
+) -> Result<()> {
+    ctx.accounts.validate(&pool_fees, &extra_fees)?;
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let old_pool_fees = pool.pool_fees.to_pool_fee_parameters();
+    let old_extra_fees = pool.pool_fees.to_extra_fee_parameters();
+
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    pool.update_pool_fees(
+        pool_fees.to_pool_fees_struct(&extra_fees),
+        current_timestamp,
+    );
+
+    emit_cpi!(EvtUpdatePoolFees {
+        pool: ctx.accounts.pool.key(),
+        authority: ctx.accounts.authority.key(),
+        old_pool_fees,
+        new_pool_fees: pool_fees,
+        old_extra_fees,
+        new_extra_fees: extra_fees,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{assert_eq_admin, event, state::Pool, PoolError};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateProtocolFeeReceiverCtx<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(constraint = assert_eq_admin(admin.key()) @ PoolError::InvalidAdmin)]
+    pub admin: Signer<'info>,
+}
+
+/// Route protocol fee of the pool to a new receiver. Pubkey::default routes it back to treasury
+pub fn handle_update_protocol_fee_receiver(
+    ctx: Context<UpdateProtocolFeeReceiverCtx>,
+    protocol_fee_receiver: Pubkey,
+) -> Result<()> {
+    let mut pool = ctx.accounts.pool.load_mut()?;
+
+    let old_protocol_fee_receiver = pool.protocol_fee_receiver;
+    require!(
+        old_protocol_fee_receiver.ne(&protocol_fee_receiver),
+        PoolError::InvalidParameters
+    );
+    pool.protocol_fee_receiver = protocol_fee_receiver;
+
+    emit_cpi!(event::EvtUpdateProtocolFeeReceiver {
+        pool: ctx.accounts.pool.key(),
+        old_protocol_fee_receiver,
+        new_protocol_fee_receiver: protocol_fee_receiver,
+    });
+
+    Ok(())
+}
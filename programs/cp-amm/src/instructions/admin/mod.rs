@@ -1,5 +1,7 @@
 pub mod ix_create_config;
 pub use ix_create_config::*;
+pub mod ix_update_config;
+pub use ix_update_config::*;
 pub mod ix_close_config;
 pub use ix_close_config::*;
 pub mod auth;
@@ -8,6 +10,8 @@ pub mod ix_create_token_badge;
 pub use ix_create_token_badge::*;
 pub mod ix_claim_protocol_fee;
 pub use ix_claim_protocol_fee::*;
+pub mod ix_claim_protocol_fee_batch;
+pub use ix_claim_protocol_fee_batch::*;
 pub mod ix_set_pool_status;
 pub use ix_set_pool_status::*;
 pub mod ix_create_claim_protocol_fee_operator;
@@ -20,3 +24,11 @@ pub mod ix_update_reward_funder;
 pub use ix_update_reward_funder::*;
 pub mod ix_update_reward_duration;
 pub use ix_update_reward_duration::*;
+pub mod ix_update_pool_fees;
+pub use ix_update_pool_fees::*;
+pub mod ix_update_protocol_fee_receiver;
+pub use ix_update_protocol_fee_receiver::*;
+pub mod ix_initialize_oracle;
+pub use ix_initialize_oracle::*;
+pub mod ix_initialize_reward_extension;
+pub use ix_initialize_reward_extension::*;
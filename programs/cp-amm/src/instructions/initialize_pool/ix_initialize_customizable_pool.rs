@@ -16,7 +16,10 @@ use crate::{
     },
     create_position_nft,
     curve::get_initialize_amounts,
-    params::{activation::ActivationParams, fee_parameters::PoolFeeParameters},
+    params::{
+        activation::ActivationParams,
+        fee_parameters::{ExtraFeeParameters, PoolFeeParameters},
+    },
     state::{CollectFeeMode, Pool, PoolType, Position},
     token::{
         calculate_transfer_fee_included_amount, get_token_program_flags, is_supported_mint,
@@ -50,7 +53,7 @@ pub struct InitializeCustomizablePoolParameters {
 }
 
 impl InitializeCustomizablePoolParameters {
-    pub fn validate(&self) -> Result<()> {
+    pub fn validate(&self, extra_fees: &ExtraFeeParameters) -> Result<()> {
         require!(
             self.sqrt_min_price >= MIN_SQRT_PRICE && self.sqrt_max_price <= MAX_SQRT_PRICE,
             PoolError::InvalidPriceRange
@@ -68,12 +71,15 @@ impl InitializeCustomizablePoolParameters {
         require!(self.liquidity > 0, PoolError::InvalidMinimumLiquidity);
 
         // validate fee
-        self.pool_fees.validate()?;
+        self.pool_fees.validate(extra_fees)?;
         // more validation for protocol fee and partner fee
         self.pool_fees.validate_for_customizable_pool()?;
 
         CollectFeeMode::try_from(self.collect_fee_mode)
             .map_err(|_| PoolError::InvalidCollectFeeMode)?;
+        self.pool_fees
+            .validate_collect_fee_mode(self.collect_fee_mode)?;
+        extra_fees.validate_fee_curve_duration(self.activation_type)?;
 
         // validate activation
         let activation_params = ActivationParams {
@@ -89,7 +95,7 @@ impl InitializeCustomizablePoolParameters {
 #[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeCustomizablePoolCtx<'info> {
-    /// CHECK: Pool creator
+    /// CHECK: Pool creator, recorded in the pool only if it signs
     pub creator: UncheckedAccount<'info>,
 
     /// position_nft_mint
@@ -225,8 +231,9 @@ pub struct InitializeCustomizablePoolCtx<'info> {
 pub fn handle_initialize_customizable_pool<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, InitializeCustomizablePoolCtx<'info>>,
     params: InitializeCustomizablePoolParameters,
+    extra_fees: ExtraFeeParameters,
 ) -> Result<()> {
-    params.validate()?;
+    params.validate(&extra_fees)?;
     if !is_supported_mint(&ctx.accounts.token_a_mint)? {
         require!(
             is_token_badge_initialized(
@@ -288,14 +295,22 @@ pub fn handle_initialize_customizable_pool<'c: 'info, 'info>(
         has_alpha_vault,
     );
     let pool_type: u8 = PoolType::Customizable.into();
+    // the creator is only recorded when it signs, as it can then initialize the creator reward
+    let creator = if ctx.accounts.creator.is_signer {
+        ctx.accounts.creator.key()
+    } else {
+        Pubkey::default()
+    };
     pool.initialize(
-        pool_fees.to_pool_fees_struct(),
+        pool_fees.to_pool_fees_struct(&extra_fees),
         ctx.accounts.token_a_mint.key(),
         ctx.accounts.token_b_mint.key(),
         ctx.accounts.token_a_vault.key(),
         ctx.accounts.token_b_vault.key(),
         alpha_vault,
         Pubkey::default(),
+        creator,
+        Pubkey::default(),
         sqrt_min_price,
         sqrt_max_price,
         sqrt_price,
@@ -44,7 +44,7 @@ pub struct InitializePoolParameters {
 #[event_cpi]
 #[derive(Accounts)]
 pub struct InitializePoolCtx<'info> {
-    /// CHECK: Pool creator
+    /// CHECK: Pool creator, recorded in the pool only if it signs
     pub creator: UncheckedAccount<'info>,
 
     /// position_nft_mint
@@ -260,6 +260,12 @@ pub fn handle_initialize_pool<'c: 'info, 'info>(
     let pool_type: u8 = PoolType::Permissionless.into();
 
     let alpha_vault = config.get_whitelisted_alpha_vault(ctx.accounts.pool.key());
+    // the creator is only recorded when it signs, as it can then initialize the creator reward
+    let creator = if ctx.accounts.creator.is_signer {
+        ctx.accounts.creator.key()
+    } else {
+        Pubkey::default()
+    };
     pool.initialize(
         config.pool_fees.to_pool_fees_struct(),
         ctx.accounts.token_a_mint.key(),
@@ -268,6 +274,8 @@ pub fn handle_initialize_pool<'c: 'info, 'info>(
         ctx.accounts.token_b_vault.key(),
         alpha_vault,
         config.pool_creator_authority,
+        creator,
+        config.protocol_fee_receiver,
         config.sqrt_min_price,
         config.sqrt_max_price,
         sqrt_price,
@@ -3,7 +3,11 @@ use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::{
     get_pool_access_validator,
-    state::{ModifyLiquidityResult, Pool, Position},
+    state::{
+        load_position_reward_extension, load_reward_extension, update_position_reward_extension,
+        update_reward_extension_liquidity, ModifyLiquidityResult, Pool, Position,
+        PositionRewardExtension, RewardExtension,
+    },
     token::{calculate_transfer_fee_included_amount, transfer_from_user},
     u128x128_math::Rounding,
     EvtAddLiquidity, PoolError,
@@ -69,6 +73,14 @@ pub struct AddLiquidityCtx<'info> {
 
     /// Token b program
     pub token_b_program: Interface<'info, TokenInterface>,
+
+    /// Reward extension of the pool, required when the position has a position reward extension
+    #[account(mut)]
+    pub reward_extension: Option<AccountLoader<'info, RewardExtension>>,
+
+    /// Reward extension of the position, required once the position has one
+    #[account(mut)]
+    pub position_reward_extension: Option<AccountLoader<'info, PositionRewardExtension>>,
 }
 
 pub fn handle_add_liquidity(
@@ -94,10 +106,26 @@ pub fn handle_add_liquidity(
     let mut pool = ctx.accounts.pool.load_mut()?;
 
     let mut position = ctx.accounts.position.load_mut()?;
+    let mut reward_extension = load_reward_extension(
+        &pool,
+        ctx.accounts.pool.key(),
+        ctx.accounts.reward_extension.as_ref(),
+    )?;
+    let mut position_reward_extension = load_position_reward_extension(
+        &position,
+        ctx.accounts.position.key(),
+        ctx.accounts.position_reward_extension.as_ref(),
+    )?;
 
     // update current pool reward & postion reward before any logic
     let current_time = Clock::get()?.unix_timestamp as u64;
     position.update_rewards(&mut pool, current_time)?;
+    update_position_reward_extension(
+        &position,
+        reward_extension.as_deref_mut(),
+        position_reward_extension.as_deref_mut(),
+        current_time,
+    )?;
 
     let ModifyLiquidityResult {
         token_a_amount,
@@ -109,7 +137,13 @@ pub fn handle_add_liquidity(
         PoolError::AmountIsZero
     );
 
+    let liquidity_before = position.get_total_liquidity()?;
     pool.apply_add_liquidity(&mut position, liquidity_delta)?;
+    update_reward_extension_liquidity(
+        &position,
+        reward_extension.as_deref_mut(),
+        liquidity_before,
+    )?;
 
     let total_amount_a =
         calculate_transfer_fee_included_amount(&ctx.accounts.token_a_mint, token_a_amount)?.amount;
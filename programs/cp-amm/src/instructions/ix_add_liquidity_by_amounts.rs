@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    handle_add_liquidity, token::calculate_transfer_fee_excluded_amount, AddLiquidityCtx,
+    AddLiquidityParameters, PoolError,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AddLiquidityByAmountsParameters {
+    /// maximum token a amount
+    pub max_amount_token_a: u64,
+    /// maximum token b amount
+    pub max_amount_token_b: u64,
+    /// minimum liquidity delta to be added
+    pub minimum_liquidity_delta: u128,
+}
+
+pub fn handle_add_liquidity_by_amounts(
+    ctx: Context<AddLiquidityCtx>,
+    params: AddLiquidityByAmountsParameters,
+) -> Result<()> {
+    let AddLiquidityByAmountsParameters {
+        max_amount_token_a,
+        max_amount_token_b,
+        minimum_liquidity_delta,
+    } = params;
+
+    // the vaults only receive the transfer fee excluded amounts
+    let token_a_amount =
+        calculate_transfer_fee_excluded_amount(&ctx.accounts.token_a_mint, max_amount_token_a)?
+            .amount;
+    let token_b_amount =
+        calculate_transfer_fee_excluded_amount(&ctx.accounts.token_b_mint, max_amount_token_b)?
+            .amount;
+
+    let liquidity_delta = ctx
+        .accounts
+        .pool
+        .load()?
+        .get_liquidity_delta_from_amounts(token_a_amount, token_b_amount)?;
+
+    require!(
+        liquidity_delta >= minimum_liquidity_delta,
+        PoolError::ExceededSlippage
+    );
+
+    handle_add_liquidity(
+        ctx,
+        AddLiquidityParameters {
+            liquidity_delta,
+            token_a_amount_threshold: max_amount_token_a,
+            token_b_amount_threshold: max_amount_token_b,
+        },
+    )
+}
@@ -0,0 +1,320 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use std::cell::RefMut;
+use std::collections::BTreeSet;
+
+use crate::{
+    constants::{seeds::POOL_AUTHORITY_PREFIX, NUM_EXTENSION_REWARDS, NUM_REWARDS},
+    safe_math::SafeMath,
+    state::{
+        get_extension_reward_index, get_reward_info, load_position_reward_extension,
+        load_reward_extension, update_position_reward_extension, Pool, Position,
+        PositionRewardExtension, RewardExtension, RewardInfo,
+    },
+    token::transfer_from_pool,
+    EvtClaimPositionFee, EvtClaimReward, PoolError,
+};
+
+/// Claim rewards of many positions in the same pool. Remaining accounts are, in order:
+/// - ClaimAllFeeRemainingAccount, only if fee is claimed
+/// - ClaimAllRewardRemainingAccount for each initialized reward by reward index, the reward
+///   extension indexes included
+/// - ClaimAllPositionRemainingAccount for each position, followed by its
+///   ClaimAllPositionRewardExtensionRemainingAccount if the position has a position reward extension
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimAllRewardsCtx<'info> {
+    /// CHECK: pool authority
+    #[account(seeds = [POOL_AUTHORITY_PREFIX], bump)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// owner of positions
+    pub owner: Signer<'info>,
+
+    /// Reward extension of the pool, required to claim the reward indexes from NUM_REWARDS onwards
+    /// and for positions having a position reward extension
+    #[account(mut)]
+    pub reward_extension: Option<AccountLoader<'info, RewardExtension>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAllFeeRemainingAccount<'info> {
+    /// The user token a account
+    #[account(mut)]
+    pub token_a_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The user token b account
+    #[account(mut)]
+    pub token_b_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for token a
+    #[account(mut, token::token_program = token_a_program, token::mint = token_a_mint)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for token b
+    #[account(mut, token::token_program = token_b_program, token::mint = token_b_mint)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token a
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token b
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token a program
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token b program
+    pub token_b_program: Interface<'info, TokenInterface>,
+}
+
+impl ClaimAllFeeRemainingAccount<'_> {
+    fn validate(&self, pool: &Pool) -> Result<()> {
+        require!(
+            self.token_a_vault.key() == pool.token_a_vault
+                && self.token_b_vault.key() == pool.token_b_vault,
+            PoolError::InvalidInput
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClaimAllRewardRemainingAccount<'info> {
+    /// The vault token account for reward token
+    #[account(mut, token::token_program = token_program, token::mint = reward_mint)]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Reward mint
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl ClaimAllRewardRemainingAccount<'_> {
+    fn validate(&self, reward_info: &RewardInfo) -> Result<()> {
+        require!(
+            reward_info.vault.eq(&self.reward_vault.key()),
+            PoolError::InvalidRewardVault
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClaimAllPositionRemainingAccount<'info> {
+    #[account(mut)]
+    pub position: AccountLoader<'info, Position>,
+
+    /// The token account for nft
+    #[account(
+            constraint = position_nft_account.mint == position.load()?.nft_mint,
+            constraint = position_nft_account.amount == 1,
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+impl ClaimAllPositionRemainingAccount<'_> {
+    fn load_and_validate(&self, pool: Pubkey, owner: Pubkey) -> Result<RefMut<'_, Position>> {
+        let position = self.position.load_mut()?;
+        require!(
+            position.pool == pool && self.position_nft_account.owner == owner,
+            PoolError::InvalidPositionAccount
+        );
+        Ok(position)
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClaimAllPositionRewardExtensionRemainingAccount<'info> {
+    /// Reward extension of the position
+    #[account(mut)]
+    pub position_reward_extension: AccountLoader<'info, PositionRewardExtension>,
+}
+
+pub fn handle_claim_all_rewards<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ClaimAllRewardsCtx<'info>>,
+    claim_fee: bool,
+) -> Result<()> {
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let mut pool = ctx.accounts.pool.load_mut()?;
+
+    let fee_accounts = if claim_fee {
+        let fee_accounts = ClaimAllFeeRemainingAccount::try_accounts(
+            &crate::ID,
+            &mut remaining_accounts,
+            &[],
+            &mut ClaimAllFeeRemainingAccountBumps {},
+            &mut BTreeSet::new(),
+        )?;
+        fee_accounts.validate(&pool)?;
+        Some(fee_accounts)
+    } else {
+        None
+    };
+
+    let mut reward_extension = load_reward_extension(
+        &pool,
+        ctx.accounts.pool.key(),
+        ctx.accounts.reward_extension.as_ref(),
+    )?;
+    let num_rewards = if reward_extension.is_some() {
+        NUM_REWARDS + NUM_EXTENSION_REWARDS
+    } else {
+        NUM_REWARDS
+    };
+
+    let mut reward_accounts = Vec::with_capacity(num_rewards);
+    for reward_index in 0..num_rewards {
+        let reward_info = get_reward_info(&pool, reward_extension.as_deref(), reward_index)?;
+        if !reward_info.initialized() {
+            continue;
+        }
+        let reward_accounts_of_index = ClaimAllRewardRemainingAccount::try_accounts(
+            &crate::ID,
+            &mut remaining_accounts,
+            &[],
+            &mut ClaimAllRewardRemainingAccountBumps {},
+            &mut BTreeSet::new(),
+        )?;
+        reward_accounts_of_index.validate(reward_info)?;
+        reward_accounts.push((reward_index, reward_accounts_of_index));
+    }
+
+    require!(!remaining_accounts.is_empty(), PoolError::InvalidInput);
+
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    let mut total_rewards = [0u64; NUM_REWARDS + NUM_EXTENSION_REWARDS];
+    let mut total_fee_a = 0u64;
+    let mut total_fee_b = 0u64;
+
+    while !remaining_accounts.is_empty() {
+        let position_accounts = ClaimAllPositionRemainingAccount::try_accounts(
+            &crate::ID,
+            &mut remaining_accounts,
+            &[],
+            &mut ClaimAllPositionRemainingAccountBumps {},
+            &mut BTreeSet::new(),
+        )?;
+        let mut position = position_accounts
+            .load_and_validate(ctx.accounts.pool.key(), ctx.accounts.owner.key())?;
+
+        let position_reward_extension_accounts = if position.has_reward_extension() {
+            Some(
+                ClaimAllPositionRewardExtensionRemainingAccount::try_accounts(
+                    &crate::ID,
+                    &mut remaining_accounts,
+                    &[],
+                    &mut ClaimAllPositionRewardExtensionRemainingAccountBumps {},
+                    &mut BTreeSet::new(),
+                )?,
+            )
+        } else {
+            None
+        };
+        let mut position_reward_extension = load_position_reward_extension(
+            &position,
+            position_accounts.position.key(),
+            position_reward_extension_accounts
+                .as_ref()
+                .map(|accounts| &accounts.position_reward_extension),
+        )?;
+
+        // update pool reward & position reward
+        position.update_rewards(&mut pool, current_time)?;
+        update_position_reward_extension(
+            &position,
+            reward_extension.as_deref_mut(),
+            position_reward_extension.as_deref_mut(),
+            current_time,
+        )?;
+
+        for (reward_index, reward_accounts_of_index) in reward_accounts.iter() {
+            // only positions having a position reward extension earn the reward indexes from NUM_REWARDS onwards
+            let total_reward = match (
+                get_extension_reward_index(*reward_index),
+                position_reward_extension.as_deref_mut(),
+            ) {
+                (Some(extension_index), Some(position_reward_extension)) => {
+                    position_reward_extension.reward_infos[extension_index].claim_reward()
+                }
+                (Some(_), None) => 0,
+                (None, _) => position.claim_reward(*reward_index)?,
+            };
+            total_rewards[*reward_index] = total_rewards[*reward_index].safe_add(total_reward)?;
+
+            emit_cpi!(EvtClaimReward {
+                pool: ctx.accounts.pool.key(),
+                position: position_accounts.position.key(),
+                mint_reward: reward_accounts_of_index.reward_mint.key(),
+                owner: ctx.accounts.owner.key(),
+                reward_index: *reward_index as u8,
+                total_reward,
+            });
+        }
+
+        if claim_fee {
+            let (fee_a_pending, fee_b_pending) =
+                position.claim_fee(pool.fee_a_per_liquidity(), pool.fee_b_per_liquidity())?;
+            total_fee_a = total_fee_a.safe_add(fee_a_pending)?;
+            total_fee_b = total_fee_b.safe_add(fee_b_pending)?;
+
+            emit_cpi!(EvtClaimPositionFee {
+                pool: ctx.accounts.pool.key(),
+                position: position_accounts.position.key(),
+                owner: ctx.accounts.owner.key(),
+                fee_a_claimed: fee_a_pending,
+                fee_b_claimed: fee_b_pending,
+            });
+        }
+    }
+
+    for (reward_index, reward_accounts_of_index) in reward_accounts.iter() {
+        let total_reward = total_rewards[*reward_index];
+        if total_reward > 0 {
+            transfer_from_pool(
+                ctx.accounts.pool_authority.to_account_info(),
+                &reward_accounts_of_index.reward_mint,
+                &reward_accounts_of_index.reward_vault,
+                &reward_accounts_of_index.user_token_account,
+                &reward_accounts_of_index.token_program,
+                total_reward,
+                ctx.bumps.pool_authority,
+            )?;
+        }
+    }
+
+    if let Some(fee_accounts) = fee_accounts {
+        if total_fee_a > 0 {
+            transfer_from_pool(
+                ctx.accounts.pool_authority.to_account_info(),
+                &fee_accounts.token_a_mint,
+                &fee_accounts.token_a_vault,
+                &fee_accounts.token_a_account,
+                &fee_accounts.token_a_program,
+                total_fee_a,
+                ctx.bumps.pool_authority,
+            )?;
+        }
+
+        if total_fee_b > 0 {
+            transfer_from_pool(
+                ctx.accounts.pool_authority.to_account_info(),
+                &fee_accounts.token_b_mint,
+                &fee_accounts.token_b_vault,
+                &fee_accounts.token_b_account,
+                &fee_accounts.token_b_program,
+                total_fee_b,
+                ctx.bumps.pool_authority,
+            )?;
+        }
+    }
+
+    Ok(())
+}
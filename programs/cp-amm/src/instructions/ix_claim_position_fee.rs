@@ -77,14 +77,8 @@ pub fn handle_claim_position_fee(ctx: Context<ClaimPositionFeeCtx>) -> Result<()
     let mut position = ctx.accounts.position.load_mut()?;
 
     let pool = ctx.accounts.pool.load()?;
-    position.update_fee(pool.fee_a_per_liquidity(), pool.fee_b_per_liquidity())?;
-    // update metrics
-
-    let fee_a_pending = position.fee_a_pending;
-    let fee_b_pending = position.fee_b_pending;
-    position
-        .metrics
-        .accumulate_claimed_fee(fee_a_pending, fee_b_pending)?;
+    let (fee_a_pending, fee_b_pending) =
+        position.claim_fee(pool.fee_a_per_liquidity(), pool.fee_b_per_liquidity())?;
 
     if fee_a_pending > 0 {
         // send to user
@@ -111,8 +105,6 @@ pub fn handle_claim_position_fee(ctx: Context<ClaimPositionFeeCtx>) -> Result<()
         )?;
     }
 
-    position.reset_pending_fee();
-
     emit_cpi!(EvtClaimPositionFee {
         pool: ctx.accounts.pool.key(),
         position: ctx.accounts.position.key(),
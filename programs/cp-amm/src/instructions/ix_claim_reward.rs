@@ -2,10 +2,14 @@ use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::{
-    constants::{seeds::POOL_AUTHORITY_PREFIX, NUM_REWARDS},
+    constants::seeds::POOL_AUTHORITY_PREFIX,
     error::PoolError,
     event::EvtClaimReward,
-    state::{pool::Pool, position::Position},
+    state::{
+        get_extension_reward_index, get_reward_info, load_position_reward_extension,
+        load_reward_extension, pool::Pool, position::Position, update_position_reward_extension,
+        PositionRewardExtension, RewardExtension,
+    },
     token::transfer_from_pool,
 };
 
@@ -47,14 +51,24 @@ pub struct ClaimRewardCtx<'info> {
     pub owner: Signer<'info>,
 
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Reward extension of the pool, required for the reward indexes from NUM_REWARDS onwards and for positions having a
+    /// position reward extension
+    #[account(mut)]
+    pub reward_extension: Option<AccountLoader<'info, RewardExtension>>,
+
+    /// Reward extension of the position, required once the position has one
+    #[account(mut)]
+    pub position_reward_extension: Option<AccountLoader<'info, PositionRewardExtension>>,
 }
 
 impl<'info> ClaimRewardCtx<'info> {
     fn validate(&self, reward_index: usize) -> Result<()> {
         let pool = self.pool.load()?;
-        require!(reward_index < NUM_REWARDS, PoolError::InvalidRewardIndex);
+        let reward_extension =
+            load_reward_extension(&pool, self.pool.key(), self.reward_extension.as_ref())?;
 
-        let reward_info = &pool.reward_infos[reward_index];
+        let reward_info = get_reward_info(&pool, reward_extension.as_deref(), reward_index)?;
         require!(reward_info.initialized(), PoolError::RewardUninitialized);
         require!(
             reward_info.vault.eq(&self.reward_vault.key()),
@@ -79,8 +93,32 @@ pub fn handle_claim_reward(ctx: Context<ClaimRewardCtx>, reward_index: u8) -> Re
     // update pool reward & position reward
     position.update_rewards(&mut pool, current_time)?;
 
-    // get all pending reward
-    let total_reward = position.claim_reward(index)?;
+    let mut reward_extension = load_reward_extension(
+        &pool,
+        ctx.accounts.pool.key(),
+        ctx.accounts.reward_extension.as_ref(),
+    )?;
+    let mut position_reward_extension = load_position_reward_extension(
+        &position,
+        ctx.accounts.position.key(),
+        ctx.accounts.position_reward_extension.as_ref(),
+    )?;
+    update_position_reward_extension(
+        &position,
+        reward_extension.as_deref_mut(),
+        position_reward_extension.as_deref_mut(),
+        current_time,
+    )?;
+
+    // get all pending reward, only positions having a position reward extension earn the reward indexes from NUM_REWARDS onwards
+    let total_reward = match get_extension_reward_index(index) {
+        Some(extension_index) => position_reward_extension
+            .as_deref_mut()
+            .ok_or(PoolError::InvalidRewardExtension)?
+            .reward_infos[extension_index]
+            .claim_reward(),
+        None => position.claim_reward(index)?,
+    };
 
     // transfer rewards to user
     if total_reward > 0 {
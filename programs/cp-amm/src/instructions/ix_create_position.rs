@@ -167,3 +167,34 @@ pub fn create_position_nft<'info>(
 
     Ok(())
 }
+
+pub fn burn_position_nft<'info>(
+    owner: AccountInfo<'info>,
+    position_nft_mint: AccountInfo<'info>,
+    position_nft_account: AccountInfo<'info>,
+    rent_receiver: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+) -> Result<()> {
+    token_2022::burn(
+        CpiContext::new(
+            token_program.clone(),
+            token_2022::Burn {
+                mint: position_nft_mint,
+                from: position_nft_account.clone(),
+                authority: owner.clone(),
+            },
+        ),
+        1,
+    )?;
+
+    token_2022::close_account(CpiContext::new(
+        token_program,
+        token_2022::CloseAccount {
+            account: position_nft_account,
+            destination: rent_receiver,
+            authority: owner,
+        },
+    ))?;
+
+    Ok(())
+}
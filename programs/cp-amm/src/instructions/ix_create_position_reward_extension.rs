@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{
+    constants::seeds::POSITION_REWARD_EXTENSION_PREFIX,
+    state::{load_reward_extension, Pool, Position, PositionRewardExtension, RewardExtension},
+    EvtCreatePositionRewardExtension, PoolError,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreatePositionRewardExtensionCtx<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(mut, has_one = pool)]
+    pub position: AccountLoader<'info, Position>,
+
+    /// The token account for nft
+    #[account(
+            constraint = position_nft_account.mint == position.load()?.nft_mint,
+            constraint = position_nft_account.amount == 1,
+            token::authority = owner
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// owner of position
+    pub owner: Signer<'info>,
+
+    /// Reward extension of the pool
+    #[account(mut)]
+    pub reward_extension: AccountLoader<'info, RewardExtension>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            POSITION_REWARD_EXTENSION_PREFIX,
+            position.key().as_ref(),
+        ],
+        bump,
+        space = 8 + PositionRewardExtension::INIT_SPACE
+    )]
+    pub position_reward_extension: AccountLoader<'info, PositionRewardExtension>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_create_position_reward_extension(
+    ctx: Context<CreatePositionRewardExtensionCtx>,
+) -> Result<()> {
+    let pool = ctx.accounts.pool.load()?;
+    let mut position = ctx.accounts.position.load_mut()?;
+    let mut reward_extension = load_reward_extension(
+        &pool,
+        ctx.accounts.pool.key(),
+        Some(&ctx.accounts.reward_extension),
+    )?
+    .ok_or(PoolError::InvalidRewardExtension)?;
+
+    // the position earns extension rewards from now on, so its liquidity joins the liquidity earning them
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    reward_extension.update_rewards(current_time)?;
+
+    let mut position_reward_extension = ctx.accounts.position_reward_extension.load_init()?;
+    position_reward_extension.initialize(ctx.accounts.position.key());
+    position_reward_extension.reset_checkpoints(&reward_extension);
+
+    reward_extension.update_liquidity(0, position.get_total_liquidity()?)?;
+    position.reward_extension_flag = 1;
+
+    emit_cpi!(EvtCreatePositionRewardExtension {
+        pool: ctx.accounts.pool.key(),
+        position: ctx.accounts.position.key(),
+        position_reward_extension: ctx.accounts.position_reward_extension.key(),
+    });
+
+    Ok(())
+}
@@ -2,10 +2,10 @@ use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::{
-    constants::{NUM_REWARDS, REWARD_RATE_SCALE},
+    constants::REWARD_RATE_SCALE,
     event::EvtFundReward,
     math::safe_math::SafeMath,
-    state::Pool,
+    state::{get_reward_info, get_reward_info_mut, load_reward_extension, Pool, RewardExtension},
     token::{calculate_transfer_fee_excluded_amount, transfer_from_user},
     utils_math::safe_mul_shr_cast,
     PoolError,
@@ -28,15 +28,19 @@ pub struct FundRewardCtx<'info> {
     pub funder: Signer<'info>,
 
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Reward extension of the pool, required for the reward indexes from NUM_REWARDS onwards
+    #[account(mut)]
+    pub reward_extension: Option<AccountLoader<'info, RewardExtension>>,
 }
 
 impl<'info> FundRewardCtx<'info> {
     fn validate(&self, reward_index: usize) -> Result<()> {
         let pool = self.pool.load()?;
+        let reward_extension =
+            load_reward_extension(&pool, self.pool.key(), self.reward_extension.as_ref())?;
 
-        require!(reward_index < NUM_REWARDS, PoolError::InvalidRewardIndex);
-
-        let reward_info = &pool.reward_infos[reward_index];
+        let reward_info = get_reward_info(&pool, reward_extension.as_deref(), reward_index)?;
         require!(reward_info.initialized(), PoolError::RewardUninitialized);
         require!(
             reward_info.vault.eq(&self.reward_vault.key()),
@@ -56,6 +60,7 @@ pub fn handle_fund_reward(
     reward_index: u8,
     amount: u64,
     carry_forward: bool,
+    reward_start_time: Option<u64>,
 ) -> Result<()> {
     let index: usize = reward_index
         .try_into()
@@ -69,12 +74,20 @@ pub fn handle_fund_reward(
     require!(transfer_fee_excluded_amount_in > 0, PoolError::AmountIsZero);
 
     let mut pool = ctx.accounts.pool.load_mut()?;
+    let mut reward_extension = load_reward_extension(
+        &pool,
+        ctx.accounts.pool.key(),
+        ctx.accounts.reward_extension.as_ref(),
+    )?;
     let current_time = Clock::get()?.unix_timestamp;
     // 1. update pool rewards
     pool.update_rewards(current_time as u64)?;
+    if let Some(reward_extension) = reward_extension.as_deref_mut() {
+        reward_extension.update_rewards(current_time as u64)?;
+    }
 
     // 2. set new farming rate
-    let reward_info = &mut pool.reward_infos[index];
+    let reward_info = get_reward_info_mut(&mut pool, reward_extension.as_deref_mut(), index)?;
 
     let total_amount = if carry_forward {
         let carry_forward_ineligible_reward: u64 = safe_mul_shr_cast(
@@ -103,7 +116,8 @@ pub fn handle_fund_reward(
     };
 
     // Reward rate might include ineligible reward based on whether to brought forward
-    reward_info.update_rate_after_funding(current_time as u64, total_amount)?;
+    reward_info.update_rate_after_funding(current_time as u64, total_amount, reward_start_time)?;
+    let reward_start_time = reward_info.reward_start_time;
 
     // Transfer without ineligible reward because it's already in the vault
     transfer_from_user(
@@ -122,6 +136,7 @@ pub fn handle_fund_reward(
         reward_index,
         amount: total_amount,
         transfer_fee_excluded_amount_in,
+        reward_start_time,
     });
 
     Ok(())
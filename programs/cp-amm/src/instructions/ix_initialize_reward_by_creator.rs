@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::{
+        seeds::{POOL_AUTHORITY_PREFIX, REWARD_VAULT_PREFIX},
+        CREATOR_REWARD_INDEX, MAX_REWARD_DURATION, MIN_CREATOR_REWARD_DURATION,
+    },
+    error::PoolError,
+    event::EvtInitializeReward,
+    state::Pool,
+    token::{get_token_program_flags, is_supported_mint, is_token_badge_initialized},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct InitializeRewardByCreatorCtx<'info> {
+    /// CHECK: pool authority
+    #[account(seeds = [POOL_AUTHORITY_PREFIX], bump)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        init,
+        seeds = [REWARD_VAULT_PREFIX, pool.key().as_ref(), reward_index.to_le_bytes().as_ref()],
+        bump,
+        payer = creator,
+        token::mint = reward_mint,
+        token::authority = pool_authority
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// pool partner or creator
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl InitializeRewardByCreatorCtx<'_> {
+    fn validate(&self, reward_index: usize, reward_duration: u64) -> Result<()> {
+        let pool = self.pool.load()?;
+
+        require!(
+            pool.is_reward_creator(self.creator.key()),
+            PoolError::InvalidRewardCreator
+        );
+
+        // the other rewards are left to admin, so the creator can't take them
+        require!(
+            reward_index == CREATOR_REWARD_INDEX,
+            PoolError::InvalidRewardIndex
+        );
+
+        require!(
+            (MIN_CREATOR_REWARD_DURATION..=MAX_REWARD_DURATION).contains(&reward_duration),
+            PoolError::InvalidRewardDuration
+        );
+
+        let reward_info = &pool.reward_infos[reward_index];
+        require!(!reward_info.initialized(), PoolError::RewardInitialized);
+
+        Ok(())
+    }
+}
+
+pub fn handle_initialize_reward_by_creator<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, InitializeRewardByCreatorCtx<'info>>,
+    reward_index: u8,
+    reward_duration: u64,
+    funder: Pubkey,
+) -> Result<()> {
+    let index: usize = reward_index.into();
+
+    ctx.accounts.validate(index, reward_duration)?;
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+
+    // besides the pool tokens and the quote mints, the reward mint needs a token badge
+    let reward_mint = ctx.accounts.reward_mint.key();
+    if !is_supported_mint(&ctx.accounts.reward_mint)? || !pool.is_creator_reward_mint(reward_mint) {
+        require!(
+            is_token_badge_initialized(
+                reward_mint,
+                ctx.remaining_accounts
+                    .get(0)
+                    .ok_or(PoolError::InvalidTokenBadge)?
+            )?,
+            PoolError::InvalidTokenBadge
+        );
+    }
+
+    pool.reward_infos[index].init_reward(
+        reward_mint,
+        ctx.accounts.reward_vault.key(),
+        funder,
+        reward_duration,
+        get_token_program_flags(&ctx.accounts.reward_mint).into(),
+    );
+
+    emit_cpi!(EvtInitializeReward {
+        pool: ctx.accounts.pool.key(),
+        reward_mint,
+        funder,
+        reward_duration,
+        reward_index,
+    });
+
+    Ok(())
+}
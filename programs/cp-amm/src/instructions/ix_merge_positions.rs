@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::{
+    burn_position_nft, get_pool_access_validator,
+    state::{
+        load_position_reward_extension, load_reward_extension, update_position_reward_extension,
+        update_reward_extension_liquidity, Pool, Position, PositionRewardExtension,
+        RewardExtension,
+    },
+    EvtMergePositions, PoolError,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MergePositionsCtx<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// The position that receives the liquidity
+    #[account(mut, has_one = pool)]
+    pub position: AccountLoader<'info, Position>,
+
+    /// The token account for nft of position
+    #[account(
+            constraint = position_nft_account.mint == position.load()?.nft_mint,
+            constraint = position_nft_account.amount == 1,
+            token::authority = owner
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position that is merged and closed
+    #[account(
+        mut,
+        has_one = pool,
+        constraint = source_position.key() != position.key() @ PoolError::InvalidParameters,
+        close = rent_receiver
+    )]
+    pub source_position: AccountLoader<'info, Position>,
+
+    /// The nft mint of source position
+    #[account(mut, address = source_position.load()?.nft_mint)]
+    pub source_position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token account for nft of source position
+    #[account(
+            mut,
+            constraint = source_position_nft_account.mint == source_position_nft_mint.key(),
+            constraint = source_position_nft_account.amount == 1,
+            token::authority = owner
+    )]
+    pub source_position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// owner of both positions
+    pub owner: Signer<'info>,
+
+    /// CHECK: Account to receive closed account rental SOL
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    /// Program to burn the position nft
+    pub token_program: Program<'info, Token2022>,
+
+    /// Reward extension of the pool, required when the position has a position reward extension
+    #[account(mut)]
+    pub reward_extension: Option<AccountLoader<'info, RewardExtension>>,
+
+    /// Reward extension of the position, required once the position has one
+    #[account(mut)]
+    pub position_reward_extension: Option<AccountLoader<'info, PositionRewardExtension>>,
+
+    /// Reward extension of the source position, closed with it
+    #[account(mut, close = rent_receiver)]
+    pub source_position_reward_extension: Option<AccountLoader<'info, PositionRewardExtension>>,
+}
+
+pub fn handle_merge_positions(ctx: Context<MergePositionsCtx>) -> Result<()> {
+    {
+        let pool = ctx.accounts.pool.load()?;
+        let access_validator = get_pool_access_validator(&pool)?;
+        require!(
+            access_validator.can_create_position(),
+            PoolError::PoolDisabled
+        );
+    }
+
+    {
+        let mut pool = ctx.accounts.pool.load_mut()?;
+        let mut position = ctx.accounts.position.load_mut()?;
+        let mut source_position = ctx.accounts.source_position.load_mut()?;
+
+        // update current pool reward & postion reward before any logic
+        let current_time = Clock::get()?.unix_timestamp as u64;
+        position.update_rewards(&mut pool, current_time)?;
+        source_position.update_rewards(&mut pool, current_time)?;
+
+        let mut reward_extension = load_reward_extension(
+            &pool,
+            ctx.accounts.pool.key(),
+            ctx.accounts.reward_extension.as_ref(),
+        )?;
+        let mut position_reward_extension = load_position_reward_extension(
+            &position,
+            ctx.accounts.position.key(),
+            ctx.accounts.position_reward_extension.as_ref(),
+        )?;
+        let mut source_position_reward_extension = load_position_reward_extension(
+            &source_position,
+            ctx.accounts.source_position.key(),
+            ctx.accounts.source_position_reward_extension.as_ref(),
+        )?;
+        update_position_reward_extension(
+            &position,
+            reward_extension.as_deref_mut(),
+            position_reward_extension.as_deref_mut(),
+            current_time,
+        )?;
+        update_position_reward_extension(
+            &source_position,
+            reward_extension.as_deref_mut(),
+            source_position_reward_extension.as_deref_mut(),
+            current_time,
+        )?;
+
+        position.update_fee(pool.fee_a_per_liquidity(), pool.fee_b_per_liquidity())?;
+        source_position.update_fee(pool.fee_a_per_liquidity(), pool.fee_b_per_liquidity())?;
+
+        // the pending extension rewards of the source position need a position reward extension to move to
+        require!(
+            position.has_reward_extension() || !source_position.has_reward_extension(),
+            PoolError::InvalidRewardExtension
+        );
+
+        // vesting accounts are bound to the source position, so merging is rejected while it has vested liquidity
+        let liquidity_before = position.get_total_liquidity()?;
+        let source_liquidity_before = source_position.get_total_liquidity()?;
+        position.merge(&mut source_position)?;
+        if let (Some(position_reward_extension), Some(source_position_reward_extension)) = (
+            position_reward_extension.as_deref_mut(),
+            source_position_reward_extension.as_deref_mut(),
+        ) {
+            position_reward_extension.merge(source_position_reward_extension)?;
+        }
+        update_reward_extension_liquidity(
+            &source_position,
+            reward_extension.as_deref_mut(),
+            source_liquidity_before,
+        )?;
+        update_reward_extension_liquidity(
+            &position,
+            reward_extension.as_deref_mut(),
+            liquidity_before,
+        )?;
+
+        pool.metrics.rec_position()?;
+    }
+
+    burn_position_nft(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.source_position_nft_mint.to_account_info(),
+        ctx.accounts.source_position_nft_account.to_account_info(),
+        ctx.accounts.rent_receiver.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    emit_cpi!(EvtMergePositions {
+        pool: ctx.accounts.pool.key(),
+        owner: ctx.accounts.owner.key(),
+        position: ctx.accounts.position.key(),
+        source_position: ctx.accounts.source_position.key(),
+        source_position_nft_mint: ctx.accounts.source_position_nft_mint.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Oracle, Pool};
+
+#[derive(Accounts)]
+pub struct ObserveCtx<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(has_one = pool)]
+    pub oracle: AccountLoader<'info, Oracle>,
+}
+
+/// Return the time weighted average sqrt price over at least `window` seconds
+pub fn handle_observe(ctx: Context<ObserveCtx>, window: u64) -> Result<u128> {
+    let pool = ctx.accounts.pool.load()?;
+    let oracle = ctx.accounts.oracle.load()?;
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    oracle.get_twap(window, pool.sqrt_price, current_timestamp)
+}
@@ -86,7 +86,7 @@ pub fn handle_refresh_vesting<'a, 'b, 'c: 'info, 'info>(
     Ok(())
 }
 
-fn release_vesting_liquidity_to_position(
+pub fn release_vesting_liquidity_to_position(
     vesting: &mut RefMut<'_, Vesting>,
     position: &mut RefMut<'_, Position>,
     current_point: u64,
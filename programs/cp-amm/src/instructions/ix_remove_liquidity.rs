@@ -1,3 +1,4 @@
+use std::cell::RefMut;
 use std::u128;
 
 use anchor_lang::prelude::*;
@@ -6,7 +7,11 @@ use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::{
     constants::seeds::POOL_AUTHORITY_PREFIX,
     get_pool_access_validator,
-    state::{ModifyLiquidityResult, Pool, Position},
+    state::{
+        load_position_reward_extension, load_reward_extension, update_position_reward_extension,
+        update_reward_extension_liquidity, ModifyLiquidityResult, Pool, Position,
+        PositionRewardExtension, RewardExtension,
+    },
     token::transfer_from_pool,
     u128x128_math::Rounding,
     EvtRemoveLiquidity, PoolError,
@@ -76,6 +81,14 @@ pub struct RemoveLiquidityCtx<'info> {
 
     /// Token b program
     pub token_b_program: Interface<'info, TokenInterface>,
+
+    /// Reward extension of the pool, required when the position has a position reward extension
+    #[account(mut)]
+    pub reward_extension: Option<AccountLoader<'info, RewardExtension>>,
+
+    /// Reward extension of the position, required once the position has one
+    #[account(mut)]
+    pub position_reward_extension: Option<AccountLoader<'info, PositionRewardExtension>>,
 }
 
 pub fn handle_remove_liquidity(
@@ -95,26 +108,31 @@ pub fn handle_remove_liquidity(
 
     let mut pool = ctx.accounts.pool.load_mut()?;
     let mut position = ctx.accounts.position.load_mut()?;
+    let mut reward_extension = load_reward_extension(
+        &pool,
+        ctx.accounts.pool.key(),
+        ctx.accounts.reward_extension.as_ref(),
+    )?;
+    let mut position_reward_extension = load_position_reward_extension(
+        &position,
+        ctx.accounts.position.key(),
+        ctx.accounts.position_reward_extension.as_ref(),
+    )?;
 
-    let liquidity_delta = liquidity_delta.unwrap_or(position.unlocked_liquidity);
-    require!(
-        liquidity_delta <= position.unlocked_liquidity && liquidity_delta > 0,
-        PoolError::InsufficientLiquidity
-    );
-
-    // update current pool reward & postion reward before any logic
-    let current_time = Clock::get()?.unix_timestamp as u64;
-    position.update_rewards(&mut pool, current_time)?;
-
-    let ModifyLiquidityResult {
-        token_a_amount,
-        token_b_amount,
-    } = pool.get_amounts_for_modify_liquidity(liquidity_delta, Rounding::Down)?;
+    let (
+        liquidity_delta,
+        ModifyLiquidityResult {
+            token_a_amount,
+            token_b_amount,
+        },
+    ) = remove_liquidity_from_position(
+        &mut pool,
+        &mut position,
+        reward_extension.as_deref_mut(),
+        position_reward_extension.as_deref_mut(),
+        liquidity_delta,
+    )?;
 
-    require!(
-        token_a_amount > 0 || token_b_amount > 0,
-        PoolError::AmountIsZero
-    );
     // Slippage check
     require!(
         token_a_amount >= token_a_amount_threshold,
@@ -125,8 +143,6 @@ pub fn handle_remove_liquidity(
         PoolError::ExceededSlippage
     );
 
-    pool.apply_remove_liquidity(&mut position, liquidity_delta)?;
-
     // send to user
     transfer_from_pool(
         ctx.accounts.pool_authority.to_account_info(),
@@ -162,3 +178,43 @@ pub fn handle_remove_liquidity(
 
     Ok(())
 }
+
+/// Remove `liquidity_delta`, or all unlocked liquidity when it is None, from the position and
+/// return the removed liquidity with the token amounts to be paid out
+pub fn remove_liquidity_from_position(
+    pool: &mut RefMut<'_, Pool>,
+    position: &mut Position,
+    mut reward_extension: Option<&mut RewardExtension>,
+    position_reward_extension: Option<&mut PositionRewardExtension>,
+    liquidity_delta: Option<u128>,
+) -> Result<(u128, ModifyLiquidityResult)> {
+    let liquidity_delta = liquidity_delta.unwrap_or(position.unlocked_liquidity);
+    require!(
+        liquidity_delta <= position.unlocked_liquidity && liquidity_delta > 0,
+        PoolError::InsufficientLiquidity
+    );
+
+    // update current pool reward & postion reward before any logic
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    position.update_rewards(pool, current_time)?;
+    update_position_reward_extension(
+        position,
+        reward_extension.as_deref_mut(),
+        position_reward_extension,
+        current_time,
+    )?;
+
+    let modify_liquidity_result =
+        pool.get_amounts_for_modify_liquidity(liquidity_delta, Rounding::Down)?;
+
+    require!(
+        modify_liquidity_result.token_a_amount > 0 || modify_liquidity_result.token_b_amount > 0,
+        PoolError::AmountIsZero
+    );
+
+    let liquidity_before = position.get_total_liquidity()?;
+    pool.apply_remove_liquidity(position, liquidity_delta)?;
+    update_reward_extension_liquidity(position, reward_extension, liquidity_before)?;
+
+    Ok((liquidity_delta, modify_liquidity_result))
+}
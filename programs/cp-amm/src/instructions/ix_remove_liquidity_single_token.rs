@@ -0,0 +1,244 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    activation_handler::ActivationHandler,
+    constants::seeds::POOL_AUTHORITY_PREFIX,
+    get_pool_access_validator,
+    params::swap::TradeDirection,
+    remove_liquidity_from_position,
+    safe_math::SafeMath,
+    state::{
+        fee::FeeMode, load_oracle, load_position_reward_extension, load_reward_extension,
+        ModifyLiquidityResult, Oracle, Pool, Position, PositionRewardExtension, RewardExtension,
+    },
+    token::{calculate_transfer_fee_excluded_amount, transfer_from_pool},
+    EvtRemoveLiquidity, EvtSwap, PoolError, RemoveLiquidityParameters, SwapParameters,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RemoveLiquiditySingleTokenParameters {
+    /// delta liquidity
+    pub liquidity_delta: u128,
+    /// minimum amount of output token received, transfer fee excluded
+    pub minimum_amount_out: u64,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveLiquiditySingleTokenCtx<'info> {
+    /// CHECK: pool authority
+    #[account(seeds = [POOL_AUTHORITY_PREFIX], bump)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(mut, has_one = token_a_vault, has_one = token_b_vault, has_one = token_a_mint, has_one = token_b_mint)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+      mut,
+      has_one = pool,
+    )]
+    pub position: AccountLoader<'info, Position>,
+
+    /// The user token account for the token to receive
+    #[account(mut)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for token a
+    #[account(mut, token::token_program = token_a_program, token::mint = token_a_mint)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for token b
+    #[account(mut, token::token_program = token_b_program, token::mint = token_b_mint)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token a
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token b
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token account for nft
+    #[account(
+            constraint = position_nft_account.mint == position.load()?.nft_mint,
+            constraint = position_nft_account.amount == 1,
+            token::authority = owner
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// owner of position
+    pub owner: Signer<'info>,
+
+    /// Token a program
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token b program
+    pub token_b_program: Interface<'info, TokenInterface>,
+
+    /// pool oracle, updated with the price before the swap. Required once the pool has one
+    #[account(mut, has_one = pool)]
+    pub oracle: Option<AccountLoader<'info, Oracle>>,
+
+    /// Reward extension of the pool, required when the position has a position reward extension
+    #[account(mut)]
+    pub reward_extension: Option<AccountLoader<'info, RewardExtension>>,
+
+    /// Reward extension of the position, required once the position has one
+    #[account(mut)]
+    pub position_reward_extension: Option<AccountLoader<'info, PositionRewardExtension>>,
+}
+
+impl RemoveLiquiditySingleTokenCtx<'_> {
+    /// Get the direction of the swap for the unwanted token. Eg: receiving token a swaps token b to token a
+    pub fn get_trade_direction(&self) -> TradeDirection {
+        if self.output_token_account.mint == self.token_a_mint.key() {
+            return TradeDirection::BtoA;
+        }
+        TradeDirection::AtoB
+    }
+}
+
+pub fn handle_remove_liquidity_single_token(
+    ctx: Context<RemoveLiquiditySingleTokenCtx>,
+    params: RemoveLiquiditySingleTokenParameters,
+) -> Result<()> {
+    {
+        let pool = ctx.accounts.pool.load()?;
+        let access_validator = get_pool_access_validator(&pool)?;
+        require!(
+            access_validator.can_remove_liquidity(),
+            PoolError::PoolDisabled
+        );
+        require!(
+            access_validator.can_swap(&ctx.accounts.owner.key()),
+            PoolError::PoolDisabled
+        );
+    }
+
+    let RemoveLiquiditySingleTokenParameters {
+        liquidity_delta,
+        minimum_amount_out,
+    } = params;
+
+    let trade_direction = ctx.accounts.get_trade_direction();
+    let (token_out_mint, output_vault_account, output_program) = match trade_direction {
+        TradeDirection::AtoB => (
+            &ctx.accounts.token_b_mint,
+            &ctx.accounts.token_b_vault,
+            &ctx.accounts.token_b_program,
+        ),
+        TradeDirection::BtoA => (
+            &ctx.accounts.token_a_mint,
+            &ctx.accounts.token_a_vault,
+            &ctx.accounts.token_a_program,
+        ),
+    };
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let mut position = ctx.accounts.position.load_mut()?;
+    let mut reward_extension = load_reward_extension(
+        &pool,
+        ctx.accounts.pool.key(),
+        ctx.accounts.reward_extension.as_ref(),
+    )?;
+    let mut position_reward_extension = load_position_reward_extension(
+        &position,
+        ctx.accounts.position.key(),
+        ctx.accounts.position_reward_extension.as_ref(),
+    )?;
+
+    let (
+        liquidity_delta,
+        ModifyLiquidityResult {
+            token_a_amount,
+            token_b_amount,
+        },
+    ) = remove_liquidity_from_position(
+        &mut pool,
+        &mut position,
+        reward_extension.as_deref_mut(),
+        position_reward_extension.as_deref_mut(),
+        Some(liquidity_delta),
+    )?;
+
+    let (swap_amount_in, removed_amount_out) = match trade_direction {
+        TradeDirection::AtoB => (token_a_amount, token_b_amount),
+        TradeDirection::BtoA => (token_b_amount, token_a_amount),
+    };
+
+    // the unwanted token stays in the vault and is swapped against the remaining liquidity
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false)?;
+    let swap_result = if swap_amount_in > 0 {
+        require!(pool.liquidity > 0, PoolError::InsufficientLiquidity);
+
+        // update for dynamic fee reference
+        pool.update_pre_swap(current_timestamp)?;
+
+        let current_point = ActivationHandler::get_current_point(pool.activation_type)?;
+        let swap_result =
+            pool.get_swap_result(swap_amount_in, fee_mode, trade_direction, current_point)?;
+        let mut oracle = load_oracle(&pool, ctx.accounts.pool.key(), ctx.accounts.oracle.as_ref())?;
+        pool.apply_swap_result(
+            &swap_result,
+            fee_mode,
+            oracle.as_deref_mut(),
+            current_timestamp,
+        )?;
+        Some(swap_result)
+    } else {
+        None
+    };
+
+    let total_amount_out = match &swap_result {
+        Some(swap_result) => removed_amount_out.safe_add(swap_result.output_amount)?,
+        None => removed_amount_out,
+    };
+    let transfer_fee_excluded_amount_out =
+        calculate_transfer_fee_excluded_amount(token_out_mint, total_amount_out)?.amount;
+    require!(
+        transfer_fee_excluded_amount_out >= minimum_amount_out,
+        PoolError::ExceededSlippage
+    );
+
+    // send to user
+    transfer_from_pool(
+        ctx.accounts.pool_authority.to_account_info(),
+        token_out_mint,
+        output_vault_account,
+        &ctx.accounts.output_token_account,
+        output_program,
+        total_amount_out,
+        ctx.bumps.pool_authority,
+    )?;
+
+    emit_cpi!(EvtRemoveLiquidity {
+        pool: ctx.accounts.pool.key(),
+        owner: ctx.accounts.owner.key(),
+        position: ctx.accounts.position.key(),
+        params: RemoveLiquidityParameters {
+            liquidity_delta,
+            token_a_amount_threshold: 0,
+            token_b_amount_threshold: 0,
+        },
+        token_a_amount,
+        token_b_amount,
+    });
+
+    if let Some(swap_result) = swap_result {
+        emit_cpi!(EvtSwap {
+            pool: ctx.accounts.pool.key(),
+            trade_direction: trade_direction.into(),
+            params: SwapParameters {
+                amount_in: swap_amount_in,
+                minimum_amount_out: 0,
+            },
+            swap_result,
+            has_referral: false,
+            actual_amount_in: swap_amount_in,
+            current_timestamp,
+        });
+    }
+
+    Ok(())
+}
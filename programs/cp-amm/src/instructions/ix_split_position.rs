@@ -0,0 +1,468 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{create_account, CreateAccount},
+};
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::{
+    activation_handler::ActivationHandler,
+    constants::{
+        seeds::{
+            POOL_AUTHORITY_PREFIX, POSITION_NFT_ACCOUNT_PREFIX, POSITION_PREFIX,
+            POSITION_REWARD_EXTENSION_PREFIX,
+        },
+        NUM_EXTENSION_REWARDS,
+    },
+    create_position_nft, get_pool_access_validator, release_vesting_liquidity_to_position,
+    safe_math::SafeMath,
+    state::{
+        load_position_reward_extension, load_reward_extension, update_position_reward_extension,
+        Pool, Position, PositionRewardExtension, RewardExtension, SplitAmountInfo, Vesting,
+    },
+    EvtCreatePosition, EvtSplitPosition, PoolError,
+};
+use std::{cell::RefMut, collections::BTreeSet};
+
+const MAX_SPLIT_PERCENTAGE: u8 = 100;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+pub struct SplitPositionParameters {
+    /// percentage of unlocked liquidity moved to the new position
+    pub unlocked_liquidity_percentage: u8,
+    /// percentage of the remaining liquidity of each vesting moved to the new position
+    pub vested_liquidity_percentage: u8,
+    /// percentage of permanent locked liquidity moved to the new position
+    pub permanent_locked_liquidity_percentage: u8,
+    /// percentage of pending fee a moved to the new position
+    pub fee_a_percentage: u8,
+    /// percentage of pending fee b moved to the new position
+    pub fee_b_percentage: u8,
+    /// percentage of pending reward 0 moved to the new position
+    pub reward_0_percentage: u8,
+    /// percentage of pending reward 1 moved to the new position
+    pub reward_1_percentage: u8,
+    /// percentage of pending reward 2, the first reward of the reward extension, moved to the new position
+    pub reward_2_percentage: u8,
+    /// percentage of pending reward 3, the second reward of the reward extension, moved to the new position
+    pub reward_3_percentage: u8,
+}
+
+impl SplitPositionParameters {
+    pub fn validate(&self) -> Result<()> {
+        let percentages = [
+            self.unlocked_liquidity_percentage,
+            self.vested_liquidity_percentage,
+            self.permanent_locked_liquidity_percentage,
+            self.fee_a_percentage,
+            self.fee_b_percentage,
+            self.reward_0_percentage,
+            self.reward_1_percentage,
+            self.reward_2_percentage,
+            self.reward_3_percentage,
+        ];
+        require!(
+            percentages
+                .iter()
+                .all(|&percentage| percentage <= MAX_SPLIT_PERCENTAGE),
+            PoolError::InvalidSplitPositionParameters
+        );
+        require!(
+            percentages.iter().any(|&percentage| percentage > 0),
+            PoolError::InvalidSplitPositionParameters
+        );
+        Ok(())
+    }
+
+    /// Amounts to move out of `position`, rounded down so that the remainder stays in it. Extension rewards are only
+    /// split from the position reward extension, if any. The vested liquidity is split per vesting, see `split_vesting`
+    pub fn get_split_amount(
+        &self,
+        position: &Position,
+        position_reward_extension: Option<&PositionRewardExtension>,
+    ) -> Result<SplitAmountInfo> {
+        let extension_rewards = match position_reward_extension {
+            Some(position_reward_extension) => [
+                get_split_amount(
+                    position_reward_extension.reward_infos[0].reward_pendings,
+                    self.reward_2_percentage,
+                )?,
+                get_split_amount(
+                    position_reward_extension.reward_infos[1].reward_pendings,
+                    self.reward_3_percentage,
+                )?,
+            ],
+            None => [0; NUM_EXTENSION_REWARDS],
+        };
+
+        Ok(SplitAmountInfo {
+            unlocked_liquidity: get_split_liquidity(
+                position.unlocked_liquidity,
+                self.unlocked_liquidity_percentage,
+            )?,
+            vested_liquidity: 0,
+            permanent_locked_liquidity: get_split_liquidity(
+                position.permanent_locked_liquidity,
+                self.permanent_locked_liquidity_percentage,
+            )?,
+            fee_a: get_split_amount(position.fee_a_pending, self.fee_a_percentage)?,
+            fee_b: get_split_amount(position.fee_b_pending, self.fee_b_percentage)?,
+            rewards: [
+                get_split_amount(
+                    position.reward_infos[0].reward_pendings,
+                    self.reward_0_percentage,
+                )?,
+                get_split_amount(
+                    position.reward_infos[1].reward_pendings,
+                    self.reward_1_percentage,
+                )?,
+            ],
+            extension_rewards,
+        })
+    }
+}
+
+fn get_split_liquidity(liquidity: u128, percentage: u8) -> Result<u128> {
+    // liquidity is bounded by u128, split the quotient and the remainder to avoid overflow
+    let percentage = u128::from(percentage);
+    let max_percentage = u128::from(MAX_SPLIT_PERCENTAGE);
+    let quotient = liquidity.safe_div(max_percentage)?;
+    let remainder = liquidity.safe_sub(quotient.safe_mul(max_percentage)?)?;
+    Ok(quotient
+        .safe_mul(percentage)?
+        .safe_add(remainder.safe_mul(percentage)?.safe_div(max_percentage)?)?)
+}
+
+/// Move `percentage` of the vesting schedule of `vesting` to `new_vesting`, after releasing its unlocked liquidity to
+/// `position`. Returns the locked liquidity left in `vesting` before splitting and the locked liquidity moved
+pub fn split_vesting(
+    vesting: &mut RefMut<'_, Vesting>,
+    new_vesting: &mut Vesting,
+    position: &mut RefMut<'_, Position>,
+    new_position: Pubkey,
+    percentage: u8,
+    current_point: u64,
+) -> Result<(u128, u128)> {
+    release_vesting_liquidity_to_position(vesting, position, current_point)?;
+    let locked_liquidity = vesting.get_locked_liquidity()?;
+    let cliff_unlock_liquidity = get_split_liquidity(vesting.cliff_unlock_liquidity, percentage)?;
+    let liquidity_per_period = get_split_liquidity(vesting.liquidity_per_period, percentage)?;
+    let moved_liquidity = vesting.split(
+        new_vesting,
+        new_position,
+        cliff_unlock_liquidity,
+        liquidity_per_period,
+        current_point,
+    )?;
+    Ok((locked_liquidity, moved_liquidity))
+}
+
+fn get_split_amount(amount: u64, percentage: u8) -> Result<u64> {
+    let amount = u128::from(amount)
+        .safe_mul(percentage.into())?
+        .safe_div(MAX_SPLIT_PERCENTAGE.into())?;
+    Ok(u64::try_from(amount).map_err(|_| PoolError::TypeCastFailed)?)
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SplitPositionCtx<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(mut, has_one = pool)]
+    pub position: AccountLoader<'info, Position>,
+
+    /// The token account for nft
+    #[account(
+            constraint = position_nft_account.mint == position.load()?.nft_mint,
+            constraint = position_nft_account.amount == 1,
+            token::authority = owner
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// owner of position
+    pub owner: Signer<'info>,
+
+    /// CHECK: Receives the new position NFT
+    pub new_owner: UncheckedAccount<'info>,
+
+    /// new position nft mint
+    #[account(
+        init,
+        signer,
+        payer = payer,
+        mint::token_program = token_program,
+        mint::decimals = 0,
+        mint::authority = pool_authority,
+        mint::freeze_authority = pool_authority,
+        extensions::metadata_pointer::authority = pool_authority,
+        extensions::metadata_pointer::metadata_address = new_position_nft_mint,
+    )]
+    pub new_position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// new position nft account
+    #[account(
+        init,
+        seeds = [POSITION_NFT_ACCOUNT_PREFIX, new_position_nft_mint.key().as_ref()],
+        token::mint = new_position_nft_mint,
+        token::authority = new_owner,
+        token::token_program = token_program,
+        payer = payer,
+        bump,
+    )]
+    pub new_position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        seeds = [
+            POSITION_PREFIX,
+            new_position_nft_mint.key().as_ref()
+        ],
+        bump,
+        payer = payer,
+        space = 8 + Position::INIT_SPACE
+    )]
+    pub new_position: AccountLoader<'info, Position>,
+
+    /// CHECK: pool authority
+    #[account(seeds = [POOL_AUTHORITY_PREFIX], bump)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// Address paying to create the new position. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Program to create NFT mint/token account and transfer for token22 account
+    pub token_program: Program<'info, Token2022>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Reward extension of the pool, required when the position has a position reward extension
+    #[account(mut)]
+    pub reward_extension: Option<AccountLoader<'info, RewardExtension>>,
+
+    /// Reward extension of the position, required once the position has one
+    #[account(mut)]
+    pub position_reward_extension: Option<AccountLoader<'info, PositionRewardExtension>>,
+
+    /// Reward extension of the new position, required once the position has a position reward extension
+    #[account(
+        init,
+        seeds = [
+            POSITION_REWARD_EXTENSION_PREFIX,
+            new_position.key().as_ref()
+        ],
+        bump,
+        payer = payer,
+        space = 8 + PositionRewardExtension::INIT_SPACE
+    )]
+    pub new_position_reward_extension: Option<AccountLoader<'info, PositionRewardExtension>>,
+}
+
+/// Remaining accounts of `split_position`, one pair per vesting of the position when splitting vested liquidity
+#[derive(Accounts)]
+pub struct SplitVestingRemainingAccount<'info> {
+    #[account(mut)]
+    pub vesting: AccountLoader<'info, Vesting>,
+
+    /// CHECK: vesting of the new position, created by the program
+    #[account(mut, signer)]
+    pub new_vesting: UncheckedAccount<'info>,
+}
+
+impl SplitVestingRemainingAccount<'_> {
+    pub fn load_and_validate(&self, position: Pubkey) -> Result<RefMut<'_, Vesting>> {
+        let vesting = self.vesting.load_mut()?;
+        require!(
+            vesting.position == position,
+            PoolError::InvalidVestingAccount
+        );
+        Ok(vesting)
+    }
+}
+
+fn create_vesting_account<'info>(
+    vesting: &'info AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<AccountLoader<'info, Vesting>> {
+    let space = 8 + Vesting::INIT_SPACE;
+    create_account(
+        CpiContext::new(
+            system_program,
+            CreateAccount {
+                from: payer,
+                to: vesting.clone(),
+            },
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &crate::ID,
+    )?;
+    AccountLoader::try_from_unchecked(&crate::ID, vesting)
+}
+
+pub fn handle_split_position<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, SplitPositionCtx<'info>>,
+    params: SplitPositionParameters,
+) -> Result<()> {
+    {
+        let pool = ctx.accounts.pool.load()?;
+        let access_validator = get_pool_access_validator(&pool)?;
+        require!(
+            access_validator.can_create_position(),
+            PoolError::PoolDisabled
+        );
+    }
+
+    params.validate()?;
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let mut position = ctx.accounts.position.load_mut()?;
+    let mut new_position = ctx.accounts.new_position.load_init()?;
+
+    // update current pool reward & postion reward before any logic
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    position.update_rewards(&mut pool, current_time)?;
+    position.update_fee(pool.fee_a_per_liquidity(), pool.fee_b_per_liquidity())?;
+
+    let mut reward_extension = load_reward_extension(
+        &pool,
+        ctx.accounts.pool.key(),
+        ctx.accounts.reward_extension.as_ref(),
+    )?;
+    let mut position_reward_extension = load_position_reward_extension(
+        &position,
+        ctx.accounts.position.key(),
+        ctx.accounts.position_reward_extension.as_ref(),
+    )?;
+    update_position_reward_extension(
+        &position,
+        reward_extension.as_deref_mut(),
+        position_reward_extension.as_deref_mut(),
+        current_time,
+    )?;
+
+    // every vesting of the position is passed with a new vesting signer, and split into it with the same percentage
+    require!(
+        params.vested_liquidity_percentage > 0 || ctx.remaining_accounts.is_empty(),
+        PoolError::InvalidSplitPositionParameters
+    );
+    let (current_point, _) =
+        ActivationHandler::get_current_point_and_buffer_duration(pool.activation_type)?;
+    let mut vestings = BTreeSet::new();
+    let mut locked_liquidity = 0u128;
+    let mut vested_liquidity = 0u128;
+    for vesting_accounts in ctx.remaining_accounts.chunks(2) {
+        let vesting_account = SplitVestingRemainingAccount::try_accounts(
+            &crate::ID,
+            &mut &vesting_accounts[..],
+            &[],
+            &mut SplitVestingRemainingAccountBumps {},
+            &mut BTreeSet::new(),
+        )?;
+        require!(
+            vestings.insert(vesting_account.vesting.key()),
+            PoolError::InvalidVestingAccount
+        );
+
+        let mut vesting = vesting_account.load_and_validate(ctx.accounts.position.key())?;
+        let new_vesting_loader = create_vesting_account(
+            &vesting_accounts[1],
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )?;
+        let mut new_vesting = new_vesting_loader.load_init()?;
+        let (vesting_locked_liquidity, moved_liquidity) = split_vesting(
+            &mut vesting,
+            &mut new_vesting,
+            &mut position,
+            ctx.accounts.new_position.key(),
+            params.vested_liquidity_percentage,
+            current_point,
+        )?;
+        locked_liquidity = locked_liquidity.safe_add(vesting_locked_liquidity)?;
+        vested_liquidity = vested_liquidity.safe_add(moved_liquidity)?;
+
+        drop(new_vesting);
+        new_vesting_loader.exit(&crate::ID)?;
+    }
+    require!(
+        params.vested_liquidity_percentage == 0 || locked_liquidity == position.vested_liquidity,
+        PoolError::InvalidVestingAccount
+    );
+
+    let mut split_amount =
+        params.get_split_amount(&position, position_reward_extension.as_deref())?;
+    split_amount.vested_liquidity = vested_liquidity;
+
+    new_position.initialize(
+        &mut pool,
+        ctx.accounts.pool.key(),
+        ctx.accounts.new_position_nft_mint.key(),
+        0,
+    )?;
+    // the new position starts from the current checkpoints, so it doesn't earn past fees and rewards
+    new_position.update_rewards(&mut pool, current_time)?;
+    new_position.update_fee(pool.fee_a_per_liquidity(), pool.fee_b_per_liquidity())?;
+    // the new position keeps earning the extension rewards if the position does, the liquidity earning them doesn't change
+    match position_reward_extension.as_deref_mut() {
+        Some(position_reward_extension) => {
+            let reward_extension = reward_extension
+                .as_deref()
+                .ok_or(PoolError::InvalidRewardExtension)?;
+            let mut new_position_reward_extension = ctx
+                .accounts
+                .new_position_reward_extension
+                .as_ref()
+                .ok_or(PoolError::InvalidRewardExtension)?
+                .load_init()?;
+            new_position_reward_extension.initialize(ctx.accounts.new_position.key());
+            new_position_reward_extension.reset_checkpoints(reward_extension);
+            position_reward_extension.split(
+                &mut new_position_reward_extension,
+                &split_amount.extension_rewards,
+            )?;
+            new_position.reward_extension_flag = 1;
+        }
+        None => require!(
+            ctx.accounts.new_position_reward_extension.is_none(),
+            PoolError::InvalidRewardExtension
+        ),
+    }
+
+    position.split(&mut new_position, &split_amount)?;
+
+    drop(new_position);
+    create_position_nft(
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.new_position_nft_mint.to_account_info(),
+        ctx.accounts.pool_authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.new_position_nft_account.to_account_info(),
+        ctx.bumps.pool_authority,
+    )?;
+
+    emit_cpi!(EvtCreatePosition {
+        pool: ctx.accounts.pool.key(),
+        owner: ctx.accounts.new_owner.key(),
+        position: ctx.accounts.new_position.key(),
+        position_nft_mint: ctx.accounts.new_position_nft_mint.key(),
+    });
+
+    emit_cpi!(EvtSplitPosition {
+        pool: ctx.accounts.pool.key(),
+        owner: ctx.accounts.owner.key(),
+        new_owner: ctx.accounts.new_owner.key(),
+        position: ctx.accounts.position.key(),
+        new_position: ctx.accounts.new_position.key(),
+        params,
+        split_amount,
+    });
+
+    Ok(())
+}
@@ -6,15 +6,26 @@ use crate::{
     constants::seeds::POOL_AUTHORITY_PREFIX,
     get_pool_access_validator,
     params::swap::TradeDirection,
-    state::{fee::FeeMode, Pool},
-    token::{calculate_transfer_fee_excluded_amount, transfer_from_pool, transfer_from_user},
-    EvtSwap, PoolError,
+    state::{fee::FeeMode, load_oracle, Oracle, Pool, ReferralAccount, SwapWithPriceLimitResult},
+    token::{
+        calculate_transfer_fee_excluded_amount, calculate_transfer_fee_included_amount,
+        transfer_from_pool, transfer_from_user,
+    },
+    EvtSwap, EvtSwapWithPriceLimit, PoolError,
 };
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct SwapParameters {
-    amount_in: u64,
-    minimum_amount_out: u64,
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SwapWithPriceLimitParameters {
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+    /// The swap stops at this sqrt price and the remaining input stays with the user. None means the pool price bound
+    pub sqrt_price_limit: Option<u128>,
 }
 
 #[event_cpi]
@@ -67,6 +78,14 @@ pub struct SwapCtx<'info> {
     /// referral token account
     #[account(mut)]
     pub referral_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// referral account, referral fee is accrued to it instead of being sent to referral token account
+    #[account(mut)]
+    pub referral_account: Option<AccountLoader<'info, ReferralAccount>>,
+
+    /// pool oracle, updated with the price before the swap. Required once the pool has one
+    #[account(mut, has_one = pool)]
+    pub oracle: Option<AccountLoader<'info, Oracle>>,
 }
 
 impl<'info> SwapCtx<'info> {
@@ -77,10 +96,103 @@ impl<'info> SwapCtx<'info> {
         }
         TradeDirection::BtoA
     }
+
+    /// Referral fee is either sent to referral token account or accrued to referral account, not both
+    pub fn has_referral(&self) -> Result<bool> {
+        require!(
+            self.referral_token_account.is_none() || self.referral_account.is_none(),
+            PoolError::InvalidReferralAccount
+        );
+        Ok(self.referral_token_account.is_some() || self.referral_account.is_some())
+    }
+
+    pub fn send_referral_fee(
+        &self,
+        pool: &mut Pool,
+        fees_on_token_a: bool,
+        referral_fee: u64,
+        pool_authority_bump: u8,
+    ) -> Result<()> {
+        let (token_mint, token_vault, token_program) = if fees_on_token_a {
+            (
+                &self.token_a_mint,
+                &self.token_a_vault,
+                &self.token_a_program,
+            )
+        } else {
+            (
+                &self.token_b_mint,
+                &self.token_b_vault,
+                &self.token_b_program,
+            )
+        };
+
+        if let Some(referral_account) = &self.referral_account {
+            let mut referral_account = referral_account.load_mut()?;
+            require!(
+                referral_account.token_mint == token_mint.key(),
+                PoolError::InvalidReferralAccount
+            );
+            // fee stays in the pool vault until the referrer claims it, from this pool or any other pool of the mint
+            referral_account.accumulate_fee(referral_fee)?;
+            pool.accumulate_referral_fee(referral_fee, fees_on_token_a)?;
+        } else if let Some(referral_token_account) = &self.referral_token_account {
+            transfer_from_pool(
+                self.pool_authority.to_account_info(),
+                token_mint,
+                token_vault,
+                referral_token_account,
+                token_program,
+                referral_fee,
+                pool_authority_bump,
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
-// TODO impl swap exact out
 pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()> {
+    process_swap(ctx, params, None)
+}
+
+pub fn handle_swap_with_price_limit(
+    ctx: Context<SwapCtx>,
+    params: SwapWithPriceLimitParameters,
+) -> Result<()> {
+    let SwapWithPriceLimitParameters {
+        amount_in,
+        minimum_amount_out,
+        sqrt_price_limit,
+    } = params;
+
+    let sqrt_price_limit = match sqrt_price_limit {
+        Some(sqrt_price_limit) => sqrt_price_limit,
+        None => {
+            let pool = ctx.accounts.pool.load()?;
+            match ctx.accounts.get_trade_direction() {
+                TradeDirection::AtoB => pool.sqrt_min_price,
+                TradeDirection::BtoA => pool.sqrt_max_price,
+            }
+        }
+    };
+
+    process_swap(
+        ctx,
+        SwapParameters {
+            amount_in,
+            minimum_amount_out,
+        },
+        Some(sqrt_price_limit),
+    )
+}
+
+/// Swap all of `amount_in`, or only the part needed to reach `sqrt_price_limit` when it is set
+fn process_swap(
+    ctx: Context<SwapCtx>,
+    params: SwapParameters,
+    sqrt_price_limit: Option<u128>,
+) -> Result<()> {
     {
         let pool = ctx.accounts.pool.load()?;
         let access_validator = get_pool_access_validator(&pool)?;
@@ -127,7 +239,7 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
 
     require!(transfer_fee_excluded_amount_in > 0, PoolError::AmountIsZero);
 
-    let has_referral = ctx.accounts.referral_token_account.is_some();
+    let has_referral = ctx.accounts.has_referral()?;
 
     let mut pool = ctx.accounts.pool.load_mut()?;
 
@@ -138,12 +250,36 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
     let current_point = ActivationHandler::get_current_point(pool.activation_type)?;
     let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, has_referral)?;
 
-    let swap_result = pool.get_swap_result(
-        transfer_fee_excluded_amount_in,
-        fee_mode,
-        trade_direction,
-        current_point,
-    )?;
+    let SwapWithPriceLimitResult {
+        consumed_amount_in,
+        swap_result,
+    } = match sqrt_price_limit {
+        Some(sqrt_price_limit) => pool.get_swap_result_with_price_limit(
+            transfer_fee_excluded_amount_in,
+            sqrt_price_limit,
+            fee_mode,
+            trade_direction,
+            current_point,
+        )?,
+        None => SwapWithPriceLimitResult {
+            consumed_amount_in: transfer_fee_excluded_amount_in,
+            swap_result: pool.get_swap_result(
+                transfer_fee_excluded_amount_in,
+                fee_mode,
+                trade_direction,
+                current_point,
+            )?,
+        },
+    };
+    require!(consumed_amount_in > 0, PoolError::AmountIsZero);
+
+    // on partial fill the user only sends what is consumed, the rest stays in the user account
+    let transfer_fee_included_consumed_amount_in =
+        if consumed_amount_in == transfer_fee_excluded_amount_in {
+            amount_in
+        } else {
+            calculate_transfer_fee_included_amount(token_in_mint, consumed_amount_in)?.amount
+        };
 
     let transfer_fee_excluded_amount_out =
         calculate_transfer_fee_excluded_amount(&token_out_mint, swap_result.output_amount)?.amount;
@@ -152,7 +288,13 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
         PoolError::ExceededSlippage
     );
 
-    pool.apply_swap_result(&swap_result, fee_mode, current_timestamp)?;
+    let mut oracle = load_oracle(&pool, ctx.accounts.pool.key(), ctx.accounts.oracle.as_ref())?;
+    pool.apply_swap_result(
+        &swap_result,
+        fee_mode,
+        oracle.as_deref_mut(),
+        current_timestamp,
+    )?;
 
     // send to reserve
     transfer_from_user(
@@ -161,7 +303,7 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
         &ctx.accounts.input_token_account,
         &input_vault_account,
         input_program,
-        amount_in,
+        transfer_fee_included_consumed_amount_in,
     )?;
     // send to user
     transfer_from_pool(
@@ -175,27 +317,12 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
     )?;
     // send to referral
     if has_referral {
-        if fee_mode.fees_on_token_a {
-            transfer_from_pool(
-                ctx.accounts.pool_authority.to_account_info(),
-                &ctx.accounts.token_a_mint,
-                &ctx.accounts.token_a_vault,
-                &ctx.accounts.referral_token_account.clone().unwrap(),
-                &ctx.accounts.token_a_program,
-                swap_result.referral_fee,
-                ctx.bumps.pool_authority,
-            )?;
-        } else {
-            transfer_from_pool(
-                ctx.accounts.pool_authority.to_account_info(),
-                &ctx.accounts.token_b_mint,
-                &ctx.accounts.token_b_vault,
-                &ctx.accounts.referral_token_account.clone().unwrap(),
-                &ctx.accounts.token_b_program,
-                swap_result.referral_fee,
-                ctx.bumps.pool_authority,
-            )?;
-        }
+        ctx.accounts.send_referral_fee(
+            &mut pool,
+            fee_mode.fees_on_token_a,
+            swap_result.referral_fee,
+            ctx.bumps.pool_authority,
+        )?;
     }
 
     emit_cpi!(EvtSwap {
@@ -204,9 +331,18 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
         params,
         swap_result,
         has_referral,
-        actual_amount_in: transfer_fee_excluded_amount_in,
+        actual_amount_in: consumed_amount_in,
         current_timestamp,
     });
 
+    if let Some(sqrt_price_limit) = sqrt_price_limit {
+        emit_cpi!(EvtSwapWithPriceLimit {
+            pool: ctx.accounts.pool.key(),
+            sqrt_price_limit,
+            consumed_amount_in: transfer_fee_included_consumed_amount_in,
+            actual_amount_in: consumed_amount_in,
+        });
+    }
+
     Ok(())
 }
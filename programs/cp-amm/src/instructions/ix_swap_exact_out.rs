@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    activation_handler::ActivationHandler,
+    get_pool_access_validator,
+    params::swap::TradeDirection,
+    state::{fee::FeeMode, load_oracle, SwapExactOutResult},
+    token::{calculate_transfer_fee_included_amount, transfer_from_pool, transfer_from_user},
+    EvtSwapExactOut, PoolError, SwapCtx,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SwapExactOutParameters {
+    pub amount_out: u64,
+    pub maximum_amount_in: u64,
+}
+
+pub fn handle_swap_exact_out(ctx: Context<SwapCtx>, params: SwapExactOutParameters) -> Result<()> {
+    {
+        let pool = ctx.accounts.pool.load()?;
+        let access_validator = get_pool_access_validator(&pool)?;
+        require!(
+            access_validator.can_swap(&ctx.accounts.payer.key()),
+            PoolError::PoolDisabled
+        );
+    }
+
+    let SwapExactOutParameters {
+        amount_out,
+        maximum_amount_in,
+    } = params;
+
+    require!(amount_out > 0, PoolError::AmountIsZero);
+
+    let trade_direction = ctx.accounts.get_trade_direction();
+    let (
+        token_in_mint,
+        token_out_mint,
+        input_vault_account,
+        output_vault_account,
+        input_program,
+        output_program,
+    ) = match trade_direction {
+        TradeDirection::AtoB => (
+            &ctx.accounts.token_a_mint,
+            &ctx.accounts.token_b_mint,
+            &ctx.accounts.token_a_vault,
+            &ctx.accounts.token_b_vault,
+            &ctx.accounts.token_a_program,
+            &ctx.accounts.token_b_program,
+        ),
+        TradeDirection::BtoA => (
+            &ctx.accounts.token_b_mint,
+            &ctx.accounts.token_a_mint,
+            &ctx.accounts.token_b_vault,
+            &ctx.accounts.token_a_vault,
+            &ctx.accounts.token_b_program,
+            &ctx.accounts.token_a_program,
+        ),
+    };
+
+    // the pool has to send out the transfer fee on top of what user receives
+    let transfer_fee_included_amount_out =
+        calculate_transfer_fee_included_amount(token_out_mint, amount_out)?.amount;
+
+    let has_referral = ctx.accounts.has_referral()?;
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+
+    // update for dynamic fee reference
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    pool.update_pre_swap(current_timestamp)?;
+
+    let current_point = ActivationHandler::get_current_point(pool.activation_type)?;
+    let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, has_referral)?;
+
+    let SwapExactOutResult {
+        input_amount,
+        swap_result,
+    } = pool.get_swap_result_exact_out(
+        transfer_fee_included_amount_out,
+        fee_mode,
+        trade_direction,
+        current_point,
+    )?;
+
+    let transfer_fee_included_amount_in =
+        calculate_transfer_fee_included_amount(token_in_mint, input_amount)?.amount;
+    require!(
+        transfer_fee_included_amount_in <= maximum_amount_in,
+        PoolError::ExceededSlippage
+    );
+
+    let mut oracle = load_oracle(&pool, ctx.accounts.pool.key(), ctx.accounts.oracle.as_ref())?;
+    pool.apply_swap_result(
+        &swap_result,
+        fee_mode,
+        oracle.as_deref_mut(),
+        current_timestamp,
+    )?;
+
+    // send to reserve
+    transfer_from_user(
+        &ctx.accounts.payer,
+        token_in_mint,
+        &ctx.accounts.input_token_account,
+        input_vault_account,
+        input_program,
+        transfer_fee_included_amount_in,
+    )?;
+    // send to user
+    transfer_from_pool(
+        ctx.accounts.pool_authority.to_account_info(),
+        token_out_mint,
+        output_vault_account,
+        &ctx.accounts.output_token_account,
+        output_program,
+        swap_result.output_amount,
+        ctx.bumps.pool_authority,
+    )?;
+    // send to referral
+    if has_referral {
+        ctx.accounts.send_referral_fee(
+            &mut pool,
+            fee_mode.fees_on_token_a,
+            swap_result.referral_fee,
+            ctx.bumps.pool_authority,
+        )?;
+    }
+
+    emit_cpi!(EvtSwapExactOut {
+        pool: ctx.accounts.pool.key(),
+        trade_direction: trade_direction.into(),
+        params,
+        swap_result,
+        has_referral,
+        actual_amount_in: input_amount,
+        current_timestamp,
+    });
+
+    Ok(())
+}
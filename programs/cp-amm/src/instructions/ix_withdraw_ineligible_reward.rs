@@ -2,10 +2,12 @@ use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::{
-    constants::{seeds::POOL_AUTHORITY_PREFIX, NUM_REWARDS},
+    constants::seeds::POOL_AUTHORITY_PREFIX,
     error::PoolError,
     event::EvtWithdrawIneligibleReward,
-    state::pool::Pool,
+    state::{
+        get_reward_info, get_reward_info_mut, load_reward_extension, pool::Pool, RewardExtension,
+    },
     token::transfer_from_pool,
 };
 
@@ -30,14 +32,19 @@ pub struct WithdrawIneligibleRewardCtx<'info> {
     pub funder: Signer<'info>,
 
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Reward extension of the pool, required for the reward indexes from NUM_REWARDS onwards
+    #[account(mut)]
+    pub reward_extension: Option<AccountLoader<'info, RewardExtension>>,
 }
 
 impl<'info> WithdrawIneligibleRewardCtx<'info> {
     fn validate(&self, reward_index: usize) -> Result<()> {
         let pool = self.pool.load()?;
-        require!(reward_index < NUM_REWARDS, PoolError::InvalidRewardIndex);
+        let reward_extension =
+            load_reward_extension(&pool, self.pool.key(), self.reward_extension.as_ref())?;
 
-        let reward_info = &pool.reward_infos[reward_index];
+        let reward_info = get_reward_info(&pool, reward_extension.as_deref(), reward_index)?;
 
         require!(reward_info.initialized(), PoolError::RewardUninitialized);
 
@@ -71,13 +78,22 @@ pub fn handle_withdraw_ineligible_reward(
     ctx.accounts.validate(index)?;
 
     let mut pool = ctx.accounts.pool.load_mut()?;
+    let mut reward_extension = load_reward_extension(
+        &pool,
+        ctx.accounts.pool.key(),
+        ctx.accounts.reward_extension.as_ref(),
+    )?;
 
     let current_time = Clock::get()?.unix_timestamp as u64;
 
     // update pool reward
     pool.update_rewards(current_time)?;
+    if let Some(reward_extension) = reward_extension.as_deref_mut() {
+        reward_extension.update_rewards(current_time)?;
+    }
 
-    let ineligible_reward = pool.claim_ineligible_reward(index)?;
+    let ineligible_reward = get_reward_info_mut(&mut pool, reward_extension.as_deref_mut(), index)?
+        .claim_ineligible_reward()?;
 
     // transfer rewards to funder
     if ineligible_reward > 0 {
@@ -0,0 +1,313 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    activation_handler::ActivationHandler,
+    constants::seeds::POOL_AUTHORITY_PREFIX,
+    get_pool_access_validator,
+    params::swap::TradeDirection,
+    safe_math::SafeMath,
+    state::{
+        fee::FeeMode, load_oracle, load_position_reward_extension, load_reward_extension,
+        update_position_reward_extension, update_reward_extension_liquidity, ModifyLiquidityResult,
+        Oracle, Pool, Position, PositionRewardExtension, RewardExtension, ZapInResult,
+    },
+    token::{
+        calculate_transfer_fee_excluded_amount, calculate_transfer_fee_included_amount,
+        transfer_from_pool, transfer_from_user,
+    },
+    u128x128_math::Rounding,
+    AddLiquidityParameters, EvtAddLiquidity, EvtSwap, PoolError, SwapParameters,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ZapInParameters {
+    /// amount of input token to deposit, part of it is swapped to the other token
+    pub amount_in: u64,
+    /// minimum liquidity delta to be added
+    pub minimum_liquidity_delta: u128,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ZapInCtx<'info> {
+    /// CHECK: pool authority
+    #[account(
+        seeds = [
+            POOL_AUTHORITY_PREFIX,
+        ],
+        bump,
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(mut, has_one = token_a_vault, has_one = token_b_vault, has_one = token_a_mint, has_one = token_b_mint)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+      mut,
+      has_one = pool,
+    )]
+    pub position: AccountLoader<'info, Position>,
+
+    /// The user token account for input token
+    #[account(mut)]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The user token account for output token, receives the swap output left after adding liquidity
+    #[account(mut)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for token a
+    #[account(mut, token::token_program = token_a_program, token::mint = token_a_mint)]
+    pub token_a_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for token b
+    #[account(mut, token::token_program = token_b_program, token::mint = token_b_mint)]
+    pub token_b_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token a
+    pub token_a_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token b
+    pub token_b_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token account for nft
+    #[account(
+            constraint = position_nft_account.mint == position.load()?.nft_mint,
+            constraint = position_nft_account.amount == 1,
+            token::authority = owner
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// owner of position
+    pub owner: Signer<'info>,
+
+    /// Token a program
+    pub token_a_program: Interface<'info, TokenInterface>,
+
+    /// Token b program
+    pub token_b_program: Interface<'info, TokenInterface>,
+
+    /// pool oracle, updated with the price before the swap. Required once the pool has one
+    #[account(mut, has_one = pool)]
+    pub oracle: Option<AccountLoader<'info, Oracle>>,
+
+    /// Reward extension of the pool, required when the position has a position reward extension
+    #[account(mut)]
+    pub reward_extension: Option<AccountLoader<'info, RewardExtension>>,
+
+    /// Reward extension of the position, required once the position has one
+    #[account(mut)]
+    pub position_reward_extension: Option<AccountLoader<'info, PositionRewardExtension>>,
+}
+
+impl ZapInCtx<'_> {
+    /// Get the direction of the internal swap
+    pub fn get_trade_direction(&self) -> TradeDirection {
+        if self.input_token_account.mint == self.token_a_mint.key() {
+            return TradeDirection::AtoB;
+        }
+        TradeDirection::BtoA
+    }
+}
+
+pub fn handle_zap_in(ctx: Context<ZapInCtx>, params: ZapInParameters) -> Result<()> {
+    {
+        let pool = ctx.accounts.pool.load()?;
+        let access_validator = get_pool_access_validator(&pool)?;
+        require!(
+            access_validator.can_swap(&ctx.accounts.owner.key()),
+            PoolError::PoolDisabled
+        );
+        require!(
+            access_validator.can_add_liquidity(),
+            PoolError::PoolDisabled
+        );
+    }
+
+    let ZapInParameters {
+        amount_in,
+        minimum_liquidity_delta,
+    } = params;
+
+    let trade_direction = ctx.accounts.get_trade_direction();
+    let (
+        token_in_mint,
+        token_out_mint,
+        input_vault_account,
+        output_vault_account,
+        input_program,
+        output_program,
+    ) = match trade_direction {
+        TradeDirection::AtoB => (
+            &ctx.accounts.token_a_mint,
+            &ctx.accounts.token_b_mint,
+            &ctx.accounts.token_a_vault,
+            &ctx.accounts.token_b_vault,
+            &ctx.accounts.token_a_program,
+            &ctx.accounts.token_b_program,
+        ),
+        TradeDirection::BtoA => (
+            &ctx.accounts.token_b_mint,
+            &ctx.accounts.token_a_mint,
+            &ctx.accounts.token_b_vault,
+            &ctx.accounts.token_a_vault,
+            &ctx.accounts.token_b_program,
+            &ctx.accounts.token_a_program,
+        ),
+    };
+
+    let transfer_fee_excluded_amount_in =
+        calculate_transfer_fee_excluded_amount(token_in_mint, amount_in)?.amount;
+
+    require!(transfer_fee_excluded_amount_in > 0, PoolError::AmountIsZero);
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let mut position = ctx.accounts.position.load_mut()?;
+    let mut reward_extension = load_reward_extension(
+        &pool,
+        ctx.accounts.pool.key(),
+        ctx.accounts.reward_extension.as_ref(),
+    )?;
+    let mut position_reward_extension = load_position_reward_extension(
+        &position,
+        ctx.accounts.position.key(),
+        ctx.accounts.position_reward_extension.as_ref(),
+    )?;
+
+    // update current pool reward & postion reward before any logic
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    position.update_rewards(&mut pool, current_timestamp)?;
+    update_position_reward_extension(
+        &position,
+        reward_extension.as_deref_mut(),
+        position_reward_extension.as_deref_mut(),
+        current_timestamp,
+    )?;
+
+    // update for dynamic fee reference
+    pool.update_pre_swap(current_timestamp)?;
+
+    let current_point = ActivationHandler::get_current_point(pool.activation_type)?;
+    let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false)?;
+
+    let ZapInResult {
+        swap_amount_in,
+        swap_result,
+        liquidity_delta,
+    } = pool.get_zap_in_result(
+        transfer_fee_excluded_amount_in,
+        fee_mode,
+        trade_direction,
+        current_point,
+    )?;
+
+    require!(liquidity_delta > 0, PoolError::AmountIsZero);
+    require!(
+        liquidity_delta >= minimum_liquidity_delta,
+        PoolError::ExceededSlippage
+    );
+
+    if swap_amount_in > 0 {
+        let mut oracle = load_oracle(&pool, ctx.accounts.pool.key(), ctx.accounts.oracle.as_ref())?;
+        pool.apply_swap_result(
+            &swap_result,
+            fee_mode,
+            oracle.as_deref_mut(),
+            current_timestamp,
+        )?;
+    }
+
+    let ModifyLiquidityResult {
+        token_a_amount,
+        token_b_amount,
+    } = pool.get_amounts_for_modify_liquidity(liquidity_delta, Rounding::Up)?;
+
+    let liquidity_before = position.get_total_liquidity()?;
+    pool.apply_add_liquidity(&mut position, liquidity_delta)?;
+    update_reward_extension_liquidity(
+        &position,
+        reward_extension.as_deref_mut(),
+        liquidity_before,
+    )?;
+
+    // the swap output never leaves the vault, only the part not used for liquidity is sent back
+    let (deposit_amount_in, deposit_amount_out) = match trade_direction {
+        TradeDirection::AtoB => (token_a_amount, token_b_amount),
+        TradeDirection::BtoA => (token_b_amount, token_a_amount),
+    };
+    let remaining_amount_out = swap_result.output_amount.safe_sub(deposit_amount_out)?;
+
+    let total_amount_in = calculate_transfer_fee_included_amount(
+        token_in_mint,
+        swap_amount_in.safe_add(deposit_amount_in)?,
+    )?
+    .amount;
+    require!(total_amount_in <= amount_in, PoolError::ExceededSlippage);
+
+    // send to reserve
+    transfer_from_user(
+        &ctx.accounts.owner,
+        token_in_mint,
+        &ctx.accounts.input_token_account,
+        input_vault_account,
+        input_program,
+        total_amount_in,
+    )?;
+    // send the remaining swap output to user
+    if remaining_amount_out > 0 {
+        transfer_from_pool(
+            ctx.accounts.pool_authority.to_account_info(),
+            token_out_mint,
+            output_vault_account,
+            &ctx.accounts.output_token_account,
+            output_program,
+            remaining_amount_out,
+            ctx.bumps.pool_authority,
+        )?;
+    }
+
+    if swap_amount_in > 0 {
+        let transfer_fee_included_swap_amount_in =
+            calculate_transfer_fee_included_amount(token_in_mint, swap_amount_in)?.amount;
+        emit_cpi!(EvtSwap {
+            pool: ctx.accounts.pool.key(),
+            trade_direction: trade_direction.into(),
+            params: SwapParameters {
+                amount_in: transfer_fee_included_swap_amount_in,
+                minimum_amount_out: 0,
+            },
+            swap_result,
+            has_referral: false,
+            actual_amount_in: swap_amount_in,
+            current_timestamp,
+        });
+    }
+
+    let (total_amount_a, total_amount_b) = match trade_direction {
+        TradeDirection::AtoB => (
+            calculate_transfer_fee_included_amount(token_in_mint, token_a_amount)?.amount,
+            token_b_amount,
+        ),
+        TradeDirection::BtoA => (
+            token_a_amount,
+            calculate_transfer_fee_included_amount(token_in_mint, token_b_amount)?.amount,
+        ),
+    };
+    emit_cpi!(EvtAddLiquidity {
+        pool: ctx.accounts.pool.key(),
+        position: ctx.accounts.position.key(),
+        owner: ctx.accounts.owner.key(),
+        params: AddLiquidityParameters {
+            liquidity_delta,
+            token_a_amount_threshold: total_amount_a,
+            token_b_amount_threshold: total_amount_b,
+        },
+        token_a_amount,
+        token_b_amount,
+        total_amount_a,
+        total_amount_b,
+    });
+
+    Ok(())
+}
@@ -2,12 +2,20 @@ pub mod admin;
 pub use admin::*;
 pub mod ix_swap;
 pub use ix_swap::*;
+pub mod ix_swap_exact_out;
+pub use ix_swap_exact_out::*;
 pub mod ix_add_liquidity;
 pub use ix_add_liquidity::*;
+pub mod ix_add_liquidity_by_amounts;
+pub use ix_add_liquidity_by_amounts::*;
+pub mod ix_zap_in;
+pub use ix_zap_in::*;
 pub mod ix_create_position;
 pub use ix_create_position::*;
 pub mod ix_remove_liquidity;
 pub use ix_remove_liquidity::*;
+pub mod ix_remove_liquidity_single_token;
+pub use ix_remove_liquidity_single_token::*;
 pub mod ix_claim_position_fee;
 pub use ix_claim_position_fee::*;
 pub mod initialize_pool;
@@ -18,10 +26,24 @@ pub mod ix_refresh_vesting;
 pub use ix_refresh_vesting::*;
 pub mod ix_permanent_lock_position;
 pub use ix_permanent_lock_position::*;
+pub mod ix_split_position;
+pub use ix_split_position::*;
+pub mod ix_merge_positions;
+pub use ix_merge_positions::*;
 pub mod ix_claim_reward;
 pub use ix_claim_reward::*;
+pub mod ix_claim_all_rewards;
+pub use ix_claim_all_rewards::*;
+pub mod ix_create_position_reward_extension;
+pub use ix_create_position_reward_extension::*;
 pub mod partner;
 pub use partner::*;
+pub mod ix_observe;
+pub use ix_observe::*;
+pub mod referral;
+pub use referral::*;
+pub mod ix_initialize_reward_by_creator;
+pub use ix_initialize_reward_by_creator::*;
 pub mod ix_fund_reward;
 pub use ix_fund_reward::*;
 pub mod ix_withdraw_ineligible_reward;
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::seeds::POOL_AUTHORITY_PREFIX,
+    state::{Pool, ReferralAccount},
+    token::transfer_from_pool,
+    EvtClaimReferralFee, PoolError,
+};
+
+/// Accounts for referrer to claim accrued referral fee from the vault of a pool holding referral fee of the token mint
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimReferralFeeCtx<'info> {
+    /// CHECK: pool authority
+    #[account(seeds = [POOL_AUTHORITY_PREFIX], bump)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(mut, has_one = referrer, has_one = token_mint)]
+    pub referral_account: AccountLoader<'info, ReferralAccount>,
+
+    /// The referrer token account to receive the fee
+    #[account(mut)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool vault of the referral token mint
+    #[account(mut, token::token_program = token_program, token::mint = token_mint)]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The referral token mint
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub referrer: Signer<'info>,
+
+    /// Token program
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_claim_referral_fee(ctx: Context<ClaimReferralFeeCtx>, max_amount: u64) -> Result<()> {
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let token_vault = ctx.accounts.token_vault.key();
+    let is_token_a = token_vault == pool.token_a_vault;
+    require!(
+        is_token_a || token_vault == pool.token_b_vault,
+        PoolError::InvalidInput
+    );
+
+    // referral fee of a token mint is fungible across pools, the claim is capped by the referral fee held in this pool
+    let mut referral_account = ctx.accounts.referral_account.load_mut()?;
+    let amount = referral_account.claim_fee(max_amount.min(pool.get_referral_fee(is_token_a)))?;
+    pool.claim_referral_fee(amount, is_token_a)?;
+
+    transfer_from_pool(
+        ctx.accounts.pool_authority.to_account_info(),
+        &ctx.accounts.token_mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_account,
+        &ctx.accounts.token_program,
+        amount,
+        ctx.bumps.pool_authority,
+    )?;
+
+    emit_cpi!(EvtClaimReferralFee {
+        pool: ctx.accounts.pool.key(),
+        referral_account: ctx.accounts.referral_account.key(),
+        referrer: ctx.accounts.referrer.key(),
+        token_mint: ctx.accounts.token_mint.key(),
+        max_amount,
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    constants::seeds::REFERRAL_ACCOUNT_PREFIX, state::ReferralAccount, EvtCreateReferralAccount,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateReferralAccountCtx<'info> {
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            REFERRAL_ACCOUNT_PREFIX,
+            referrer.key().as_ref(),
+            token_mint.key().as_ref(),
+        ],
+        bump,
+        space = 8 + ReferralAccount::INIT_SPACE
+    )]
+    pub referral_account: AccountLoader<'info, ReferralAccount>,
+
+    /// CHECK: referrer, only the referrer can claim the accrued fee
+    pub referrer: UncheckedAccount<'info>,
+
+    /// Token mint to accrue the referral fee in, from any pool having it
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_create_referral_account(ctx: Context<CreateReferralAccountCtx>) -> Result<()> {
+    let token_mint = ctx.accounts.token_mint.key();
+    let mut referral_account = ctx.accounts.referral_account.load_init()?;
+    referral_account.initialize(ctx.accounts.referrer.key(), token_mint)?;
+
+    emit_cpi!(EvtCreateReferralAccount {
+        referral_account: ctx.accounts.referral_account.key(),
+        referrer: ctx.accounts.referrer.key(),
+        token_mint,
+    });
+
+    Ok(())
+}
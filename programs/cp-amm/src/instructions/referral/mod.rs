@@ -0,0 +1,4 @@
+pub mod ix_create_referral_account;
+pub use ix_create_referral_account::*;
+pub mod ix_claim_referral_fee;
+pub use ix_claim_referral_fee::*;
@@ -41,7 +41,19 @@ pub mod cp_amm {
         ctx: Context<CreateConfigCtx>,
         config_parameters: ConfigParameters,
     ) -> Result<()> {
-        instructions::handle_create_config(ctx, config_parameters)
+        instructions::handle_create_config(
+            ctx,
+            config_parameters,
+            params::fee_parameters::ExtraFeeParameters::default(),
+        )
+    }
+
+    pub fn create_config_with_extra_fees(
+        ctx: Context<CreateConfigCtx>,
+        config_parameters: ConfigParameters,
+        extra_fees: params::fee_parameters::ExtraFeeParameters,
+    ) -> Result<()> {
+        instructions::handle_create_config(ctx, config_parameters, extra_fees)
     }
 
     pub fn create_token_badge(ctx: Context<CreateTokenBadgeCtx>) -> Result<()> {
@@ -56,6 +68,14 @@ pub mod cp_amm {
         instructions::handle_close_claim_fee_operator(ctx)
     }
 
+    pub fn update_config(
+        ctx: Context<UpdateConfigCtx>,
+        pool_fees: params::fee_parameters::PoolFeeParameters,
+        extra_fees: params::fee_parameters::ExtraFeeParameters,
+    ) -> Result<()> {
+        instructions::handle_update_config(ctx, pool_fees, extra_fees)
+    }
+
     pub fn close_config(ctx: Context<CloseConfigCtx>) -> Result<()> {
         instructions::handle_close_config(ctx)
     }
@@ -74,8 +94,15 @@ pub mod cp_amm {
         reward_index: u8,
         amount: u64,
         carry_forward: bool,
+        reward_start_time: Option<u64>,
     ) -> Result<()> {
-        instructions::handle_fund_reward(ctx, reward_index, amount, carry_forward)
+        instructions::handle_fund_reward(
+            ctx,
+            reward_index,
+            amount,
+            carry_forward,
+            reward_start_time,
+        )
     }
 
     pub fn withdraw_ineligible_reward(
@@ -105,8 +132,41 @@ pub mod cp_amm {
         instructions::handle_set_pool_status(ctx, status)
     }
 
-    pub fn claim_protocol_fee(ctx: Context<ClaimProtocolFeesCtx>) -> Result<()> {
-        instructions::handle_claim_protocol_fee(ctx)
+    pub fn update_pool_fees(
+        ctx: Context<UpdatePoolFeesCtx>,
+        pool_fees: params::fee_parameters::PoolFeeParameters,
+        extra_fees: params::fee_parameters::ExtraFeeParameters,
+    ) -> Result<()> {
+        instructions::handle_update_pool_fees(ctx, pool_fees, extra_fees)
+    }
+
+    pub fn update_protocol_fee_receiver(
+        ctx: Context<UpdateProtocolFeeReceiverCtx>,
+        protocol_fee_receiver: Pubkey,
+    ) -> Result<()> {
+        instructions::handle_update_protocol_fee_receiver(ctx, protocol_fee_receiver)
+    }
+
+    pub fn initialize_oracle(ctx: Context<InitializeOracleCtx>) -> Result<()> {
+        instructions::handle_initialize_oracle(ctx)
+    }
+
+    pub fn initialize_reward_extension(ctx: Context<InitializeRewardExtensionCtx>) -> Result<()> {
+        instructions::handle_initialize_reward_extension(ctx)
+    }
+
+    pub fn claim_protocol_fee(
+        ctx: Context<ClaimProtocolFeesCtx>,
+        max_amount_a: u64,
+        max_amount_b: u64,
+    ) -> Result<()> {
+        instructions::handle_claim_protocol_fee(ctx, max_amount_a, max_amount_b)
+    }
+
+    pub fn claim_protocol_fee_batch<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ClaimProtocolFeesBatchCtx<'info>>,
+    ) -> Result<()> {
+        instructions::handle_claim_protocol_fee_batch(ctx)
     }
 
     pub fn claim_partner_fee(
@@ -117,6 +177,20 @@ pub mod cp_amm {
         instructions::handle_claim_partner_fee(ctx, max_amount_a, max_amount_b)
     }
 
+    pub fn initialize_reward_by_creator<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, InitializeRewardByCreatorCtx<'info>>,
+        reward_index: u8,
+        reward_duration: u64,
+        funder: Pubkey,
+    ) -> Result<()> {
+        instructions::handle_initialize_reward_by_creator(
+            ctx,
+            reward_index,
+            reward_duration,
+            funder,
+        )
+    }
+
     /// USER FUNCTIONS ////
 
     pub fn initialize_pool<'c: 'info, 'info>(
@@ -130,7 +204,19 @@ pub mod cp_amm {
         ctx: Context<'_, '_, 'c, 'info, InitializeCustomizablePoolCtx<'info>>,
         params: InitializeCustomizablePoolParameters,
     ) -> Result<()> {
-        instructions::handle_initialize_customizable_pool(ctx, params)
+        instructions::handle_initialize_customizable_pool(
+            ctx,
+            params,
+            params::fee_parameters::ExtraFeeParameters::default(),
+        )
+    }
+
+    pub fn initialize_customizable_pool_with_extra_fees<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, InitializeCustomizablePoolCtx<'info>>,
+        params: InitializeCustomizablePoolParameters,
+        extra_fees: params::fee_parameters::ExtraFeeParameters,
+    ) -> Result<()> {
+        instructions::handle_initialize_customizable_pool(ctx, params, extra_fees)
     }
 
     pub fn create_position(ctx: Context<CreatePositionCtx>) -> Result<()> {
@@ -144,6 +230,17 @@ pub mod cp_amm {
         instructions::handle_add_liquidity(ctx, params)
     }
 
+    pub fn add_liquidity_by_amounts(
+        ctx: Context<AddLiquidityCtx>,
+        params: AddLiquidityByAmountsParameters,
+    ) -> Result<()> {
+        instructions::handle_add_liquidity_by_amounts(ctx, params)
+    }
+
+    pub fn zap_in(ctx: Context<ZapInCtx>, params: ZapInParameters) -> Result<()> {
+        instructions::handle_zap_in(ctx, params)
+    }
+
     pub fn remove_liquidity(
         ctx: Context<RemoveLiquidityCtx>,
         params: RemoveLiquidityParameters,
@@ -169,10 +266,32 @@ pub mod cp_amm {
         )
     }
 
+    pub fn remove_liquidity_single_token(
+        ctx: Context<RemoveLiquiditySingleTokenCtx>,
+        params: RemoveLiquiditySingleTokenParameters,
+    ) -> Result<()> {
+        instructions::handle_remove_liquidity_single_token(ctx, params)
+    }
+
     pub fn swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()> {
         instructions::handle_swap(ctx, params)
     }
 
+    pub fn swap_with_price_limit(
+        ctx: Context<SwapCtx>,
+        params: SwapWithPriceLimitParameters,
+    ) -> Result<()> {
+        instructions::handle_swap_with_price_limit(ctx, params)
+    }
+
+    pub fn swap_exact_out(ctx: Context<SwapCtx>, params: SwapExactOutParameters) -> Result<()> {
+        instructions::handle_swap_exact_out(ctx, params)
+    }
+
+    pub fn observe(ctx: Context<ObserveCtx>, window: u64) -> Result<u128> {
+        instructions::handle_observe(ctx, window)
+    }
+
     pub fn claim_position_fee(ctx: Context<ClaimPositionFeeCtx>) -> Result<()> {
         instructions::handle_claim_position_fee(ctx)
     }
@@ -194,7 +313,39 @@ pub mod cp_amm {
         instructions::handle_permanent_lock_position(ctx, permanent_lock_liquidity)
     }
 
+    pub fn split_position<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, SplitPositionCtx<'info>>,
+        params: SplitPositionParameters,
+    ) -> Result<()> {
+        instructions::handle_split_position(ctx, params)
+    }
+
+    pub fn merge_positions(ctx: Context<MergePositionsCtx>) -> Result<()> {
+        instructions::handle_merge_positions(ctx)
+    }
+
+    pub fn create_referral_account(ctx: Context<CreateReferralAccountCtx>) -> Result<()> {
+        instructions::handle_create_referral_account(ctx)
+    }
+
+    pub fn claim_referral_fee(ctx: Context<ClaimReferralFeeCtx>, max_amount: u64) -> Result<()> {
+        instructions::handle_claim_referral_fee(ctx, max_amount)
+    }
+
     pub fn claim_reward(ctx: Context<ClaimRewardCtx>, reward_index: u8) -> Result<()> {
         instructions::handle_claim_reward(ctx, reward_index)
     }
+
+    pub fn claim_all_rewards<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ClaimAllRewardsCtx<'info>>,
+        claim_fee: bool,
+    ) -> Result<()> {
+        instructions::handle_claim_all_rewards(ctx, claim_fee)
+    }
+
+    pub fn create_position_reward_extension(
+        ctx: Context<CreatePositionRewardExtensionCtx>,
+    ) -> Result<()> {
+        instructions::handle_create_position_reward_extension(ctx)
+    }
 }
@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constants::{BASIS_POINT_MAX, ONE_Q64},
+    constants::{
+        fee::{FEE_DENOMINATOR, MAX_FEE_NUMERATOR},
+        BASIS_POINT_MAX, ONE_Q64,
+    },
     PoolError,
 };
 
@@ -33,6 +36,64 @@ pub fn get_fee_in_period(
     Ok(fee_numerator)
 }
 
+// the first reference_amount is charged cliff_fee_numerator, the k-th following chunk of reference_amount
+// is charged cliff_fee_numerator + k * fee_increment_numerator, capped at MAX_FEE_NUMERATOR.
+// Returns the average fee numerator over amount, rounded up
+pub fn get_rate_limiter_fee_numerator(
+    cliff_fee_numerator: u64,
+    fee_increment_bps: u16,
+    reference_amount: u64,
+    amount: u64,
+) -> Result<u64> {
+    if amount <= reference_amount {
+        return Ok(cliff_fee_numerator);
+    }
+    let cliff_fee_numerator = u128::from(cliff_fee_numerator);
+    let max_fee_numerator = u128::from(MAX_FEE_NUMERATOR);
+    let reference_amount = u128::from(reference_amount);
+    let amount = u128::from(amount);
+
+    let fee_increment_numerator = u128::from(fee_increment_bps)
+        .safe_mul(FEE_DENOMINATOR.into())?
+        .safe_div(BASIS_POINT_MAX.into())?;
+    // last chunk index that is still below the cap
+    let max_index = max_fee_numerator
+        .safe_sub(cliff_fee_numerator)?
+        .safe_div(fee_increment_numerator)?;
+
+    let excess_amount = amount.safe_sub(reference_amount)?;
+    let full_chunks = excess_amount.safe_div(reference_amount)?;
+    let remaining_amount = excess_amount.safe_sub(full_chunks.safe_mul(reference_amount)?)?;
+
+    // chunks 0..=n are fully charged with an increasing fee
+    let n = full_chunks.min(max_index);
+    let increasing_fee_numerator = n.safe_add(1)?.safe_mul(cliff_fee_numerator)?.safe_add(
+        fee_increment_numerator
+            .safe_mul(n)?
+            .safe_mul(n.safe_add(1)?)?
+            .safe_div(2)?,
+    )?;
+    let increasing_fee = reference_amount.safe_mul(increasing_fee_numerator)?;
+
+    let remaining_fee = if full_chunks < max_index {
+        remaining_amount.safe_mul(
+            cliff_fee_numerator.safe_add(fee_increment_numerator.safe_mul(n.safe_add(1)?)?)?,
+        )?
+    } else {
+        full_chunks
+            .safe_sub(max_index)?
+            .safe_mul(reference_amount)?
+            .safe_add(remaining_amount)?
+            .safe_mul(max_fee_numerator)?
+    };
+
+    let total_fee = increasing_fee.safe_add(remaining_fee)?;
+    let fee_numerator = total_fee.safe_add(amount.safe_sub(1)?)?.safe_div(amount)?;
+
+    let fee_numerator = u64::try_from(fee_numerator).map_err(|_| PoolError::TypeCastFailed)?;
+    Ok(fee_numerator)
+}
+
 pub fn pow(base: u128, exp: i32) -> Option<u128> {
     // If exponent is negative. We will invert the result later by 1 / base^exp.abs()
     let mut invert = exp.is_negative();
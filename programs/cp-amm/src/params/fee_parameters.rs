@@ -1,14 +1,22 @@
 //! Fees module includes information about fee charges
+use crate::activation_handler::ActivationType;
 use crate::constants::fee::{
     CUSTOMIZABLE_HOST_FEE_PERCENT, CUSTOMIZABLE_PROTOCOL_FEE_PERCENT, FEE_DENOMINATOR,
     MAX_BASIS_POINT, MAX_FEE_NUMERATOR, MIN_FEE_NUMERATOR,
 };
-use crate::constants::{BASIS_POINT_MAX, BIN_STEP_BPS_DEFAULT, BIN_STEP_BPS_U128_DEFAULT, U24_MAX};
+use crate::constants::{ALLOWED_BIN_STEPS, BASIS_POINT_MAX, MAX_FEE_CURVE_POINTS, U24_MAX};
 use crate::error::PoolError;
 use crate::fee_math::get_fee_in_period;
 use crate::safe_math::SafeMath;
-use crate::state::fee::{BaseFeeStruct, DynamicFeeStruct, FeeSchedulerMode, PoolFeesStruct};
-use crate::state::{BaseFeeConfig, DynamicFeeConfig, PoolFeesConfig};
+use crate::state::fee::{
+    BaseFeeStruct, DynamicFeeStruct, FeeCurvePoint, FeeSchedulerMode, PoolFeesStruct,
+};
+use crate::state::{
+    get_timing_constraint_by_activation_type, BaseFeeConfig, CollectFeeMode, DynamicFeeConfig,
+    PoolFeesConfig, TimingConstraint,
+};
+use crate::u128x128_math::Rounding;
+use crate::utils_math::safe_mul_div_cast_u64;
 use anchor_lang::prelude::*;
 
 use super::swap::TradeDirection;
@@ -28,6 +36,15 @@ pub struct PoolFeeParameters {
     pub dynamic_fee: Option<DynamicFeeParameters>,
 }
 
+/// Fee settings passed next to `PoolFeeParameters`, so the layout of `PoolFeeParameters` stays unchanged for existing clients
+#[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Default)]
+pub struct ExtraFeeParameters {
+    /// fee curve, required in fee curve mode
+    pub fee_curve: Option<FeeCurveParameters>,
+    /// base fee multiplier on a to b trades in basis point
+    pub a_to_b_fee_multiplier_bps: Option<u32>,
+}
+
 #[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Default)]
 pub struct BaseFeeParameters {
     pub cliff_fee_numerator: u64,
@@ -60,10 +77,29 @@ impl BaseFeeParameters {
                 )?;
                 Ok(fee_numerator)
             }
+            // fee curve points are validated in FeeCurveParameters
+            FeeSchedulerMode::RateLimiter | FeeSchedulerMode::FeeCurve => {
+                Ok(self.cliff_fee_numerator)
+            }
         }
     }
 
     fn validate(&self) -> Result<()> {
+        let fee_scheduler_mode = FeeSchedulerMode::try_from(self.fee_scheduler_mode)
+            .map_err(|_| PoolError::TypeCastFailed)?;
+        match fee_scheduler_mode {
+            FeeSchedulerMode::RateLimiter => self.validate_rate_limiter()?,
+            FeeSchedulerMode::FeeCurve => {
+                require!(
+                    self.number_of_period == 0
+                        && self.period_frequency == 0
+                        && self.reduction_factor == 0,
+                    PoolError::InvalidFeeCurve
+                );
+            }
+            FeeSchedulerMode::Linear | FeeSchedulerMode::Exponential => {}
+        }
+
         let min_fee_numerator = self.get_min_base_fee_numerator()?;
         let max_fee_numerator = self.get_max_base_fee_numerator();
         validate_fee_fraction(min_fee_numerator, FEE_DENOMINATOR)?;
@@ -74,6 +110,21 @@ impl BaseFeeParameters {
         );
         Ok(())
     }
+
+    fn validate_rate_limiter(&self) -> Result<()> {
+        let base_fee = self.to_base_fee_struct();
+        let fee_increment_bps = base_fee.get_fee_increment_bps();
+        require!(
+            fee_increment_bps > 0 && u64::from(fee_increment_bps) <= MAX_BASIS_POINT,
+            PoolError::InvalidFeeRateLimiter
+        );
+        require!(
+            base_fee.get_reference_amount() > 0,
+            PoolError::InvalidFeeRateLimiter
+        );
+        Ok(())
+    }
+
     fn to_base_fee_struct(&self) -> BaseFeeStruct {
         BaseFeeStruct {
             cliff_fee_numerator: self.cliff_fee_numerator,
@@ -98,7 +149,7 @@ impl BaseFeeParameters {
 }
 
 impl PoolFeeParameters {
-    pub fn to_pool_fees_config(&self) -> PoolFeesConfig {
+    pub fn to_pool_fees_config(&self, extra_fees: &ExtraFeeParameters) -> PoolFeesConfig {
         let &PoolFeeParameters {
             base_fee,
             protocol_fee_percent,
@@ -106,6 +157,11 @@ impl PoolFeeParameters {
             referral_fee_percent,
             dynamic_fee,
         } = self;
+        let fee_curve_points = extra_fees
+            .fee_curve
+            .map(|fee_curve| fee_curve.to_fee_curve_points())
+            .unwrap_or_default();
+        let a_to_b_fee_multiplier_bps = extra_fees.a_to_b_fee_multiplier_bps.unwrap_or_default();
         if let Some(dynamic_fee) = dynamic_fee {
             PoolFeesConfig {
                 base_fee: base_fee.to_base_fee_config(),
@@ -113,6 +169,8 @@ impl PoolFeeParameters {
                 partner_fee_percent,
                 referral_fee_percent,
                 dynamic_fee: dynamic_fee.to_dynamic_fee_config(),
+                fee_curve_points,
+                a_to_b_fee_multiplier_bps,
                 ..Default::default()
             }
         } else {
@@ -121,11 +179,13 @@ impl PoolFeeParameters {
                 protocol_fee_percent,
                 partner_fee_percent,
                 referral_fee_percent,
+                fee_curve_points,
+                a_to_b_fee_multiplier_bps,
                 ..Default::default()
             }
         }
     }
-    pub fn to_pool_fees_struct(&self) -> PoolFeesStruct {
+    pub fn to_pool_fees_struct(&self, extra_fees: &ExtraFeeParameters) -> PoolFeesStruct {
         let &PoolFeeParameters {
             base_fee,
             protocol_fee_percent,
@@ -133,6 +193,11 @@ impl PoolFeeParameters {
             referral_fee_percent,
             dynamic_fee,
         } = self;
+        let fee_curve_points = extra_fees
+            .fee_curve
+            .map(|fee_curve| fee_curve.to_fee_curve_points())
+            .unwrap_or_default();
+        let a_to_b_fee_multiplier_bps = extra_fees.a_to_b_fee_multiplier_bps.unwrap_or_default();
         if let Some(dynamic_fee) = dynamic_fee {
             PoolFeesStruct {
                 base_fee: base_fee.to_base_fee_struct(),
@@ -140,6 +205,8 @@ impl PoolFeeParameters {
                 partner_fee_percent,
                 referral_fee_percent,
                 dynamic_fee: dynamic_fee.to_dynamic_fee_struct(),
+                fee_curve_points,
+                a_to_b_fee_multiplier_bps,
                 ..Default::default()
             }
         } else {
@@ -148,6 +215,8 @@ impl PoolFeeParameters {
                 protocol_fee_percent,
                 partner_fee_percent,
                 referral_fee_percent,
+                fee_curve_points,
+                a_to_b_fee_multiplier_bps,
                 ..Default::default()
             }
         }
@@ -157,6 +226,7 @@ impl PoolFeeParameters {
 #[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Default)]
 pub struct DynamicFeeParameters {
     pub bin_step: u16,
+    /// Kept for layout compatibility, must equal bin_step << 64 / BASIS_POINT_MAX
     pub bin_step_u128: u128,
     pub filter_period: u16,
     pub decay_period: u16,
@@ -166,6 +236,11 @@ pub struct DynamicFeeParameters {
 }
 
 impl DynamicFeeParameters {
+    // bin_step << 64 / BASIS_POINT_MAX
+    pub fn get_bin_step_u128(&self) -> u128 {
+        (u128::from(self.bin_step) << 64) / u128::from(BASIS_POINT_MAX)
+    }
+
     fn to_dynamic_fee_config(&self) -> DynamicFeeConfig {
         DynamicFeeConfig {
             initialized: 1,
@@ -193,13 +268,12 @@ impl DynamicFeeParameters {
         }
     }
     pub fn validate(&self) -> Result<()> {
-        // force all bin_step as 1 bps for first version
         require!(
-            self.bin_step == BIN_STEP_BPS_DEFAULT,
+            ALLOWED_BIN_STEPS.contains(&self.bin_step),
             PoolError::InvalidInput
         );
         require!(
-            self.bin_step_u128 == BIN_STEP_BPS_U128_DEFAULT,
+            self.bin_step_u128 == self.get_bin_step_u128(),
             PoolError::InvalidInput
         );
 
@@ -229,6 +303,102 @@ impl DynamicFeeParameters {
     }
 }
 
+#[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Default)]
+pub struct FeeCurvePointParameters {
+    /// Number of slots or seconds after activation point
+    pub point_offset: u32,
+    pub fee_numerator: u32,
+}
+
+#[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Default)]
+pub struct FeeCurveParameters {
+    /// Fee curve points after the cliff fee. Unused points are zero and placed at the end
+    pub points: [FeeCurvePointParameters; MAX_FEE_CURVE_POINTS],
+}
+
+impl FeeCurveParameters {
+    pub fn from_fee_curve_points(fee_curve_points: &[FeeCurvePoint; MAX_FEE_CURVE_POINTS]) -> Self {
+        FeeCurveParameters {
+            points: fee_curve_points.map(|point| FeeCurvePointParameters {
+                point_offset: point.point_offset,
+                fee_numerator: point.fee_numerator,
+            }),
+        }
+    }
+
+    pub fn to_fee_curve_points(&self) -> [FeeCurvePoint; MAX_FEE_CURVE_POINTS] {
+        self.points.map(|point| FeeCurvePoint {
+            point_offset: point.point_offset,
+            fee_numerator: point.fee_numerator,
+        })
+    }
+
+    fn get_used_points(&self) -> impl Iterator<Item = &FeeCurvePointParameters> {
+        self.points
+            .iter()
+            .take_while(|point| point.point_offset != 0)
+    }
+
+    pub fn get_min_fee_numerator(&self, cliff_fee_numerator: u64) -> u64 {
+        // fee curve is non-increasing
+        self.get_used_points()
+            .last()
+            .map(|point| point.fee_numerator.into())
+            .unwrap_or(cliff_fee_numerator)
+    }
+
+    pub fn validate(&self, cliff_fee_numerator: u64) -> Result<()> {
+        let used_points_count = self.get_used_points().count();
+        require!(used_points_count > 0, PoolError::InvalidFeeCurve);
+        require!(
+            self.points[used_points_count..]
+                .iter()
+                .all(|point| point.point_offset == 0 && point.fee_numerator == 0),
+            PoolError::InvalidFeeCurve
+        );
+
+        // points are sorted by offset and the fee never increases
+        let mut previous_offset = 0;
+        let mut previous_fee_numerator = cliff_fee_numerator;
+        for point in self.get_used_points() {
+            let fee_numerator = u64::from(point.fee_numerator);
+            require!(
+                point.point_offset > previous_offset
+                    && fee_numerator >= MIN_FEE_NUMERATOR
+                    && fee_numerator <= previous_fee_numerator,
+                PoolError::InvalidFeeCurve
+            );
+            previous_offset = point.point_offset;
+            previous_fee_numerator = fee_numerator;
+        }
+        Ok(())
+    }
+
+    /// The cliff fee must drop to the first point within max_high_tax_duration, and the curve must end within max_fee_curve_duration
+    pub fn validate_duration(
+        &self,
+        max_fee_curve_duration: u64,
+        max_high_tax_duration: u64,
+    ) -> Result<()> {
+        let first_point_offset = self
+            .get_used_points()
+            .next()
+            .map(|point| u64::from(point.point_offset))
+            .ok_or(PoolError::InvalidFeeCurve)?;
+        let last_point_offset = self
+            .get_used_points()
+            .last()
+            .map(|point| u64::from(point.point_offset))
+            .ok_or(PoolError::InvalidFeeCurve)?;
+        require!(
+            first_point_offset <= max_high_tax_duration
+                && last_point_offset <= max_fee_curve_duration,
+            PoolError::InvalidFeeCurve
+        );
+        Ok(())
+    }
+}
+
 /// Helper function for calculating swap fee
 pub fn calculate_fee(
     token_amount: u128,
@@ -267,7 +437,7 @@ pub fn to_bps(numerator: u128, denominator: u128) -> Option<u64> {
 
 impl PoolFeeParameters {
     /// Validate that the fees are reasonable
-    pub fn validate(&self) -> Result<()> {
+    pub fn validate(&self, extra_fees: &ExtraFeeParameters) -> Result<()> {
         self.base_fee.validate()?;
         validate_fee_fraction(self.protocol_fee_percent.into(), 100)?;
         validate_fee_fraction(self.partner_fee_percent.into(), 100)?;
@@ -276,6 +446,66 @@ impl PoolFeeParameters {
         if let Some(dynamic_fee) = self.dynamic_fee {
             dynamic_fee.validate()?;
         }
+
+        let is_fee_curve_mode =
+            self.base_fee.fee_scheduler_mode == u8::from(FeeSchedulerMode::FeeCurve);
+        match extra_fees.fee_curve {
+            Some(fee_curve) => {
+                require!(is_fee_curve_mode, PoolError::InvalidFeeCurve);
+                fee_curve.validate(self.base_fee.cliff_fee_numerator)?;
+            }
+            None => require!(!is_fee_curve_mode, PoolError::InvalidFeeCurve),
+        }
+
+        if let Some(a_to_b_fee_multiplier_bps) = extra_fees.a_to_b_fee_multiplier_bps {
+            self.validate_a_to_b_fee_multiplier(a_to_b_fee_multiplier_bps, extra_fees)?;
+        }
+        Ok(())
+    }
+
+    fn get_min_base_fee_numerator(&self, extra_fees: &ExtraFeeParameters) -> Result<u64> {
+        match extra_fees.fee_curve {
+            Some(fee_curve) => {
+                Ok(fee_curve.get_min_fee_numerator(self.base_fee.cliff_fee_numerator))
+            }
+            None => self.base_fee.get_min_base_fee_numerator(),
+        }
+    }
+
+    /// Both directions must stay within the fee bounds after applying the a to b fee multiplier
+    fn validate_a_to_b_fee_multiplier(
+        &self,
+        a_to_b_fee_multiplier_bps: u32,
+        extra_fees: &ExtraFeeParameters,
+    ) -> Result<()> {
+        require!(a_to_b_fee_multiplier_bps > 0, PoolError::InvalidFee);
+        let min_fee_numerator = safe_mul_div_cast_u64::<u64>(
+            self.get_min_base_fee_numerator(extra_fees)?,
+            a_to_b_fee_multiplier_bps.into(),
+            BASIS_POINT_MAX,
+            Rounding::Down,
+        )?;
+        let max_fee_numerator = safe_mul_div_cast_u64::<u64>(
+            self.base_fee.get_max_base_fee_numerator(),
+            a_to_b_fee_multiplier_bps.into(),
+            BASIS_POINT_MAX,
+            Rounding::Down,
+        )?;
+        require!(
+            min_fee_numerator >= MIN_FEE_NUMERATOR && max_fee_numerator <= MAX_FEE_NUMERATOR,
+            PoolError::ExceedMaxFeeBps
+        );
+        Ok(())
+    }
+
+    /// Rate limiter is charged on the token b amount of the trade, so it requires fees to be collected in token b only
+    pub fn validate_collect_fee_mode(&self, collect_fee_mode: u8) -> Result<()> {
+        if self.base_fee.fee_scheduler_mode == u8::from(FeeSchedulerMode::RateLimiter) {
+            require!(
+                collect_fee_mode == u8::from(CollectFeeMode::OnlyB),
+                PoolError::InvalidFeeRateLimiter
+            );
+        }
         Ok(())
     }
 
@@ -293,6 +523,22 @@ impl PoolFeeParameters {
     }
 }
 
+impl ExtraFeeParameters {
+    pub fn validate_fee_curve_duration(&self, activation_type: u8) -> Result<()> {
+        if let Some(fee_curve) = self.fee_curve {
+            let activation_type = ActivationType::try_from(activation_type)
+                .map_err(|_| PoolError::InvalidActivationType)?;
+            let TimingConstraint {
+                max_fee_curve_duration,
+                max_high_tax_duration,
+                ..
+            } = get_timing_constraint_by_activation_type(activation_type, &Clock::get()?);
+            fee_curve.validate_duration(max_fee_curve_duration, max_high_tax_duration)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize, InitSpace, Default)]
 pub struct PartnerInfo {
     pub fee_percent: u8,
@@ -4,13 +4,16 @@ use static_assertions::const_assert_eq;
 use crate::{
     activation_handler::ActivationType,
     alpha_vault::alpha_vault,
-    constants::activation::*,
+    constants::{activation::*, MAX_FEE_CURVE_POINTS},
     error::PoolError,
     params::fee_parameters::{
-        BaseFeeParameters, DynamicFeeParameters, PartnerInfo, PoolFeeParameters,
+        BaseFeeParameters, DynamicFeeParameters, ExtraFeeParameters, FeeCurveParameters,
+        PartnerInfo, PoolFeeParameters,
     },
     safe_math::SafeMath,
-    state::fee::{BaseFeeStruct, DynamicFeeStruct, PoolFeesStruct},
+    state::fee::{
+        BaseFeeStruct, DynamicFeeStruct, FeeCurvePoint, FeeSchedulerMode, PoolFeesStruct,
+    },
 };
 
 #[zero_copy]
@@ -21,8 +24,11 @@ pub struct PoolFeesConfig {
     pub protocol_fee_percent: u8,
     pub partner_fee_percent: u8,
     pub referral_fee_percent: u8,
-    pub padding_0: [u8; 5],
-    pub padding_1: [u64; 5],
+    pub padding_0: [u8; 1],
+    /// Takes the last 4 bytes of the former `padding_0: [u8; 5]`, so configs created before it read 0, which means no multiplier
+    pub a_to_b_fee_multiplier_bps: u32,
+    pub fee_curve_points: [FeeCurvePoint; MAX_FEE_CURVE_POINTS],
+    pub padding_1: [u64; 3],
 }
 
 const_assert_eq!(PoolFeesConfig::INIT_SPACE, 128);
@@ -111,6 +117,17 @@ impl PoolFeesConfig {
         }
     }
 
+    pub fn to_extra_fee_parameters(&self) -> ExtraFeeParameters {
+        let fee_curve = (self.base_fee.fee_scheduler_mode == u8::from(FeeSchedulerMode::FeeCurve))
+            .then(|| FeeCurveParameters::from_fee_curve_points(&self.fee_curve_points));
+        let a_to_b_fee_multiplier_bps =
+            (self.a_to_b_fee_multiplier_bps != 0).then_some(self.a_to_b_fee_multiplier_bps);
+        ExtraFeeParameters {
+            fee_curve,
+            a_to_b_fee_multiplier_bps,
+        }
+    }
+
     pub fn to_pool_fees_struct(&self) -> PoolFeesStruct {
         let &PoolFeesConfig {
             base_fee,
@@ -118,6 +135,8 @@ impl PoolFeesConfig {
             partner_fee_percent,
             referral_fee_percent,
             dynamic_fee,
+            fee_curve_points,
+            a_to_b_fee_multiplier_bps,
             ..
         } = self;
 
@@ -127,6 +146,8 @@ impl PoolFeesConfig {
             partner_fee_percent,
             referral_fee_percent,
             dynamic_fee: dynamic_fee.to_dynamic_fee_struct(),
+            fee_curve_points,
+            a_to_b_fee_multiplier_bps,
             ..Default::default()
         }
     }
@@ -169,7 +190,7 @@ impl DynamicFeeConfig {
 }
 
 #[account(zero_copy)]
-#[derive(InitSpace, Debug)]
+#[derive(InitSpace, Debug, Default)]
 pub struct Config {
     /// Vault config key
     pub vault_config_key: Pubkey,
@@ -189,9 +210,10 @@ pub struct Config {
     pub sqrt_min_price: u128,
     /// sqrt max price
     pub sqrt_max_price: u128,
-    /// Fee curve point
+    /// Receiver of protocol fee of pools created from this config. When it's Pubkey::default, protocol fee goes to treasury.
+    pub protocol_fee_receiver: Pubkey,
     /// Padding for further use
-    pub _padding_1: [u64; 10],
+    pub _padding_1: [u64; 6],
 }
 
 const_assert_eq!(Config::INIT_SPACE, 320);
@@ -248,22 +270,33 @@ impl Config {
     pub fn init(
         &mut self,
         index: u64,
-        pool_fees: &PoolFeeParameters,
+        pool_fees: PoolFeesConfig,
         vault_config_key: Pubkey,
         pool_creator_authority: Pubkey,
         activation_type: u8,
         sqrt_min_price: u128,
         sqrt_max_price: u128,
         collect_fee_mode: u8,
+        protocol_fee_receiver: Pubkey,
     ) {
         self.index = index;
-        self.pool_fees = pool_fees.to_pool_fees_config();
+        self.pool_fees = pool_fees;
         self.vault_config_key = vault_config_key;
         self.pool_creator_authority = pool_creator_authority;
         self.activation_type = activation_type;
         self.sqrt_min_price = sqrt_min_price;
         self.sqrt_max_price = sqrt_max_price;
         self.collect_fee_mode = collect_fee_mode;
+        self.protocol_fee_receiver = protocol_fee_receiver;
+    }
+
+    /// Pools created before the update keep their own fees
+    pub fn update_pool_fees(
+        &mut self,
+        pool_fees: &PoolFeeParameters,
+        extra_fees: &ExtraFeeParameters,
+    ) {
+        self.pool_fees = pool_fees.to_pool_fees_config(extra_fees);
     }
 
     pub fn to_bootstrapping_config(&self, activation_point: u64) -> BootstrappingConfig {
@@ -7,10 +7,16 @@ use static_assertions::const_assert_eq;
 use crate::{
     constants::{
         fee::{FEE_DENOMINATOR, MAX_FEE_NUMERATOR},
-        BASIS_POINT_MAX, ONE_Q64,
+        BASIS_POINT_MAX, MAX_FEE_CURVE_POINTS, ONE_Q64,
+    },
+    fee_math::{get_fee_in_period, get_rate_limiter_fee_numerator},
+    params::{
+        fee_parameters::{
+            BaseFeeParameters, DynamicFeeParameters, ExtraFeeParameters, FeeCurveParameters,
+            PoolFeeParameters,
+        },
+        swap::TradeDirection,
     },
-    fee_math::get_fee_in_period,
-    params::swap::TradeDirection,
     safe_math::SafeMath,
     u128x128_math::Rounding,
     utils_math::{safe_mul_div_cast_u64, safe_shl_div_cast},
@@ -48,6 +54,11 @@ pub enum FeeSchedulerMode {
     Linear,
     // fee = cliff_fee_numerator * (1-reduction_factor/10_000)^passed_period
     Exponential,
+    // fee = cliff_fee_numerator + fee_increment * (amount / reference_amount), charged per reference_amount chunk of
+    // the token b amount of the trade, in both directions. See BaseFeeStruct getters for the fields it reuses
+    RateLimiter,
+    // fee = linear interpolation between cliff_fee_numerator at activation point and the fee curve points
+    FeeCurve,
 }
 
 #[zero_copy]
@@ -73,17 +84,36 @@ pub struct PoolFeesStruct {
     /// referral fee
     pub referral_fee_percent: u8,
     /// padding
-    pub padding_0: [u8; 5],
+    pub padding_0: [u8; 1],
+    /// base fee multiplier on a to b trades in basis point, 0 means the same fee on both directions.
+    /// Takes the last 4 bytes of the former `padding_0: [u8; 5]`, so pools created before it read 0, which means no multiplier
+    pub a_to_b_fee_multiplier_bps: u32,
 
     /// dynamic fee
     pub dynamic_fee: DynamicFeeStruct,
 
-    /// padding
-    pub padding_1: [u64; 2],
+    /// fee curve points, only used in fee curve mode
+    pub fee_curve_points: [FeeCurvePoint; MAX_FEE_CURVE_POINTS],
 }
 
 const_assert_eq!(PoolFeesStruct::INIT_SPACE, 160);
 
+#[zero_copy]
+#[derive(Debug, InitSpace, Default, PartialEq)]
+pub struct FeeCurvePoint {
+    /// Number of slots or seconds after activation point. Zero means the point is unused
+    pub point_offset: u32,
+    pub fee_numerator: u32,
+}
+
+const_assert_eq!(FeeCurvePoint::INIT_SPACE, 8);
+
+impl FeeCurvePoint {
+    pub fn is_used(&self) -> bool {
+        self.point_offset != 0
+    }
+}
+
 #[zero_copy]
 #[derive(Debug, InitSpace, Default)]
 pub struct BaseFeeStruct {
@@ -99,6 +129,21 @@ pub struct BaseFeeStruct {
 const_assert_eq!(BaseFeeStruct::INIT_SPACE, 40);
 
 impl BaseFeeStruct {
+    /// Rate limiter fee increment per reference amount in basis point, stored in `number_of_period`
+    pub fn get_fee_increment_bps(&self) -> u16 {
+        self.number_of_period
+    }
+
+    /// Rate limiter duration after activation point, stored in `period_frequency`. Zero means no time limit
+    pub fn get_max_limiter_duration(&self) -> u64 {
+        self.period_frequency
+    }
+
+    /// Rate limiter token b amount charged with each fee increment, stored in `reduction_factor`
+    pub fn get_reference_amount(&self) -> u64 {
+        self.reduction_factor
+    }
+
     pub fn get_max_base_fee_numerator(&self) -> u64 {
         self.cliff_fee_numerator
     }
@@ -139,16 +184,186 @@ impl BaseFeeStruct {
                     get_fee_in_period(self.cliff_fee_numerator, self.reduction_factor, period)?;
                 Ok(fee_numerator)
             }
+            // rate limiter doesn't decay over time, fee curve points are stored in PoolFeesStruct
+            FeeSchedulerMode::RateLimiter | FeeSchedulerMode::FeeCurve => {
+                Ok(self.cliff_fee_numerator)
+            }
+        }
+    }
+
+    /// Rate limiter is applied on both trade directions, from activation point until max_limiter_duration passed.
+    /// Zero max_limiter_duration means no time limit
+    pub fn is_rate_limiter_applied(
+        &self,
+        current_point: u64,
+        activation_point: u64,
+    ) -> Result<bool> {
+        let fee_scheduler_mode = FeeSchedulerMode::try_from(self.fee_scheduler_mode)
+            .map_err(|_| PoolError::TypeCastFailed)?;
+        if fee_scheduler_mode != FeeSchedulerMode::RateLimiter || current_point < activation_point {
+            return Ok(false);
+        }
+        let max_limiter_duration = self.get_max_limiter_duration();
+        if max_limiter_duration == 0 {
+            return Ok(true);
+        }
+        let last_effective_point = activation_point.safe_add(max_limiter_duration)?;
+        Ok(current_point <= last_effective_point)
+    }
+
+    /// Base fee numerator for a trade. `amount` is the fee included amount, the input amount when fees are collected
+    /// on input and the output amount otherwise
+    pub fn get_base_fee_numerator(
+        &self,
+        amount: u64,
+        current_point: u64,
+        activation_point: u64,
+    ) -> Result<u64> {
+        if self.is_rate_limiter_applied(current_point, activation_point)? {
+            get_rate_limiter_fee_numerator(
+                self.cliff_fee_numerator,
+                self.get_fee_increment_bps(),
+                self.get_reference_amount(),
+                amount,
+            )
+        } else {
+            self.get_current_base_fee_numerator(current_point, activation_point)
         }
     }
 }
 
 impl PoolFeesStruct {
+    pub fn to_pool_fee_parameters(&self) -> PoolFeeParameters {
+        let &PoolFeesStruct {
+            base_fee,
+            protocol_fee_percent,
+            partner_fee_percent,
+            referral_fee_percent,
+            dynamic_fee,
+            ..
+        } = self;
+        let dynamic_fee = dynamic_fee
+            .is_dynamic_fee_enable()
+            .then_some(DynamicFeeParameters {
+                bin_step: dynamic_fee.bin_step,
+                bin_step_u128: dynamic_fee.bin_step_u128,
+                filter_period: dynamic_fee.filter_period,
+                decay_period: dynamic_fee.decay_period,
+                reduction_factor: dynamic_fee.reduction_factor,
+                max_volatility_accumulator: dynamic_fee.max_volatility_accumulator,
+                variable_fee_control: dynamic_fee.variable_fee_control,
+            });
+        PoolFeeParameters {
+            base_fee: BaseFeeParameters {
+                cliff_fee_numerator: base_fee.cliff_fee_numerator,
+                number_of_period: base_fee.number_of_period,
+                period_frequency: base_fee.period_frequency,
+                reduction_factor: base_fee.reduction_factor,
+                fee_scheduler_mode: base_fee.fee_scheduler_mode,
+            },
+            protocol_fee_percent,
+            partner_fee_percent,
+            referral_fee_percent,
+            dynamic_fee,
+        }
+    }
+
+    pub fn to_extra_fee_parameters(&self) -> ExtraFeeParameters {
+        let fee_curve = self
+            .is_fee_curve_enabled()
+            .then(|| FeeCurveParameters::from_fee_curve_points(&self.fee_curve_points));
+        let a_to_b_fee_multiplier_bps =
+            (self.a_to_b_fee_multiplier_bps != 0).then_some(self.a_to_b_fee_multiplier_bps);
+        ExtraFeeParameters {
+            fee_curve,
+            a_to_b_fee_multiplier_bps,
+        }
+    }
+
+    pub fn is_fee_curve_enabled(&self) -> bool {
+        self.base_fee.fee_scheduler_mode == u8::from(FeeSchedulerMode::FeeCurve)
+    }
+
+    pub fn get_fee_curve_fee_numerator(
+        &self,
+        current_point: u64,
+        activation_point: u64,
+    ) -> Result<u64> {
+        let mut start_offset = 0u64;
+        let mut start_fee_numerator = self.base_fee.cliff_fee_numerator;
+        let used_points = self
+            .fee_curve_points
+            .iter()
+            .take_while(|point| point.is_used());
+
+        // can trade before activation point, so it is alpha-vault, we use min fee
+        if current_point < activation_point {
+            return Ok(used_points
+                .last()
+                .map(|point| point.fee_numerator.into())
+                .unwrap_or(start_fee_numerator));
+        }
+
+        let elapsed = current_point.safe_sub(activation_point)?;
+        for point in used_points {
+            let end_offset = u64::from(point.point_offset);
+            let end_fee_numerator = u64::from(point.fee_numerator);
+            if elapsed < end_offset {
+                // fee curve is non-increasing
+                let fee_drop = start_fee_numerator
+                    .safe_sub(end_fee_numerator)?
+                    .safe_mul(elapsed.safe_sub(start_offset)?)?
+                    .safe_div(end_offset.safe_sub(start_offset)?)?;
+                return Ok(start_fee_numerator.safe_sub(fee_drop)?);
+            }
+            start_offset = end_offset;
+            start_fee_numerator = end_fee_numerator;
+        }
+        Ok(start_fee_numerator)
+    }
+
+    pub fn get_base_fee_numerator(
+        &self,
+        amount: u64,
+        current_point: u64,
+        activation_point: u64,
+    ) -> Result<u64> {
+        if self.is_fee_curve_enabled() {
+            return self.get_fee_curve_fee_numerator(current_point, activation_point);
+        }
+        self.base_fee
+            .get_base_fee_numerator(amount, current_point, activation_point)
+    }
+
+    /// Base fee numerator of a direction, the a to b fee multiplier applies on a to b trades
+    pub fn get_directional_base_fee_numerator(
+        &self,
+        base_fee_numerator: u64,
+        trade_direction: TradeDirection,
+    ) -> Result<u64> {
+        if trade_direction == TradeDirection::AtoB && self.a_to_b_fee_multiplier_bps != 0 {
+            return safe_mul_div_cast_u64(
+                base_fee_numerator,
+                self.a_to_b_fee_multiplier_bps.into(),
+                BASIS_POINT_MAX,
+                Rounding::Down,
+            );
+        }
+        Ok(base_fee_numerator)
+    }
+
     // in numerator
-    pub fn get_total_trading_fee(&self, current_point: u64, activation_point: u64) -> Result<u128> {
-        let base_fee_numerator = self
-            .base_fee
-            .get_current_base_fee_numerator(current_point, activation_point)?;
+    pub fn get_total_trading_fee(
+        &self,
+        amount: u64,
+        trade_direction: TradeDirection,
+        current_point: u64,
+        activation_point: u64,
+    ) -> Result<u128> {
+        let base_fee_numerator =
+            self.get_base_fee_numerator(amount, current_point, activation_point)?;
+        let base_fee_numerator =
+            self.get_directional_base_fee_numerator(base_fee_numerator, trade_direction)?;
         let total_fee_numerator = self
             .dynamic_fee
             .get_variable_fee()?
@@ -156,24 +371,129 @@ impl PoolFeesStruct {
         Ok(total_fee_numerator)
     }
 
-    pub fn get_fee_on_amount(
+    // in numerator, capped at MAX_FEE_NUMERATOR
+    pub fn get_trading_fee_numerator(
         &self,
         amount: u64,
-        has_referral: bool,
+        trade_direction: TradeDirection,
         current_point: u64,
         activation_point: u64,
-    ) -> Result<FeeOnAmountResult> {
-        let trade_fee_numerator = self.get_total_trading_fee(current_point, activation_point)?;
+    ) -> Result<u64> {
+        let trade_fee_numerator =
+            self.get_total_trading_fee(amount, trade_direction, current_point, activation_point)?;
         let trade_fee_numerator = if trade_fee_numerator > MAX_FEE_NUMERATOR.into() {
             MAX_FEE_NUMERATOR
         } else {
             trade_fee_numerator.try_into().unwrap()
         };
+        Ok(trade_fee_numerator)
+    }
+
+    pub fn get_fee_on_amount(
+        &self,
+        amount: u64,
+        has_referral: bool,
+        trade_direction: TradeDirection,
+        current_point: u64,
+        activation_point: u64,
+    ) -> Result<FeeOnAmountResult> {
+        let trade_fee_numerator = self.get_trading_fee_numerator(
+            amount,
+            trade_direction,
+            current_point,
+            activation_point,
+        )?;
         let lp_fee: u64 =
             safe_mul_div_cast_u64(amount, trade_fee_numerator, FEE_DENOMINATOR, Rounding::Up)?;
         // update amount
         let amount = amount.safe_sub(lp_fee)?;
 
+        self.split_fees(amount, lp_fee, has_referral)
+    }
+
+    /// Inverse of `get_fee_on_amount`. Returns the fee included amount, so that deducting
+    /// the trading fee from it leaves at least `excluded_fee_amount`
+    pub fn get_fee_on_excluded_amount(
+        &self,
+        excluded_fee_amount: u64,
+        has_referral: bool,
+        trade_direction: TradeDirection,
+        current_point: u64,
+        activation_point: u64,
+    ) -> Result<FeeOnAmountResult> {
+        if self
+            .base_fee
+            .is_rate_limiter_applied(current_point, activation_point)?
+        {
+            let included_fee_amount = self.get_rate_limiter_included_fee_amount(
+                excluded_fee_amount,
+                trade_direction,
+                current_point,
+                activation_point,
+            )?;
+            let lp_fee = included_fee_amount.safe_sub(excluded_fee_amount)?;
+            return self.split_fees(included_fee_amount, lp_fee, has_referral);
+        }
+
+        let trade_fee_numerator = self.get_trading_fee_numerator(
+            excluded_fee_amount,
+            trade_direction,
+            current_point,
+            activation_point,
+        )?;
+        // included_fee_amount = excluded_fee_amount * denominator / (denominator - trade_fee_numerator)
+        let included_fee_amount: u64 = safe_mul_div_cast_u64(
+            excluded_fee_amount,
+            FEE_DENOMINATOR,
+            FEE_DENOMINATOR.safe_sub(trade_fee_numerator)?,
+            Rounding::Up,
+        )?;
+        let lp_fee = included_fee_amount.safe_sub(excluded_fee_amount)?;
+
+        self.split_fees(included_fee_amount, lp_fee, has_referral)
+    }
+
+    /// The rate limiter fee depends on the included amount, so search the smallest included amount
+    /// leaving at least `excluded_fee_amount` after the trading fee
+    fn get_rate_limiter_included_fee_amount(
+        &self,
+        excluded_fee_amount: u64,
+        trade_direction: TradeDirection,
+        current_point: u64,
+        activation_point: u64,
+    ) -> Result<u64> {
+        // trading fee is capped at MAX_FEE_NUMERATOR, so this amount always covers the fee
+        let mut upper_amount: u64 = safe_mul_div_cast_u64(
+            excluded_fee_amount,
+            FEE_DENOMINATOR,
+            FEE_DENOMINATOR.safe_sub(MAX_FEE_NUMERATOR)?,
+            Rounding::Up,
+        )?;
+        let mut lower_amount = excluded_fee_amount;
+        while lower_amount < upper_amount {
+            let middle_amount = lower_amount.safe_add(upper_amount.safe_sub(lower_amount)? / 2)?;
+            let FeeOnAmountResult { amount, .. } = self.get_fee_on_amount(
+                middle_amount,
+                false,
+                trade_direction,
+                current_point,
+                activation_point,
+            )?;
+            if amount >= excluded_fee_amount {
+                upper_amount = middle_amount;
+            } else {
+                lower_amount = middle_amount.safe_add(1)?;
+            }
+        }
+        Ok(upper_amount)
+    }
+
+    fn split_fees(
+        &self,
+        amount: u64,
+        lp_fee: u64,
+        has_referral: bool,
+    ) -> Result<FeeOnAmountResult> {
         let protocol_fee = safe_mul_div_cast_u64(
             lp_fee,
             self.protocol_fee_percent.into(),
@@ -11,3 +11,9 @@ pub mod vesting;
 pub use vesting::*;
 pub mod claim_fee_operator;
 pub use claim_fee_operator::*;
+pub mod referral_account;
+pub use referral_account::*;
+pub mod oracle;
+pub use oracle::*;
+pub mod reward_extension;
+pub use reward_extension::*;
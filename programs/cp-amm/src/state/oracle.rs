@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use static_assertions::const_assert_eq;
+use std::cell::RefMut;
+
+use crate::{
+    constants::{seeds::ORACLE_PREFIX, NUM_ORACLE_OBSERVATIONS, ORACLE_OBSERVATION_INTERVAL},
+    safe_math::SafeMath,
+    state::Pool,
+    PoolError,
+};
+
+#[zero_copy]
+#[derive(Debug, InitSpace, Default)]
+pub struct Observation {
+    /// Sum of sqrt_price * seconds elapsed, wraps around on overflow
+    pub sqrt_price_cumulative: u128,
+    /// Timestamp of the observation
+    pub timestamp: u64,
+    /// Padding
+    pub _padding: u64,
+}
+
+const_assert_eq!(Observation::INIT_SPACE, 32);
+
+#[account(zero_copy)]
+#[derive(InitSpace, Debug)]
+/// Ring buffer of pool price observations
+pub struct Oracle {
+    /// pool
+    pub pool: Pubkey,
+    /// index of the latest observation
+    pub observation_index: u64,
+    /// number of observations written, up to NUM_ORACLE_OBSERVATIONS
+    pub observation_count: u64,
+    /// Reserve
+    pub _padding: [u64; 4],
+    /// observations
+    pub observations: [Observation; NUM_ORACLE_OBSERVATIONS],
+}
+
+const_assert_eq!(Oracle::INIT_SPACE, 2128);
+
+impl Oracle {
+    pub fn initialize(&mut self, pool: Pubkey, current_timestamp: u64) {
+        self.pool = pool;
+        self.observation_index = 0;
+        self.observation_count = 1;
+        self.observations[0] = Observation {
+            timestamp: current_timestamp,
+            ..Default::default()
+        };
+    }
+
+    fn get_observation(&self, offset: u64) -> &Observation {
+        let num_observations = NUM_ORACLE_OBSERVATIONS as u64;
+        let index = (self.observation_index + num_observations - offset) % num_observations;
+        &self.observations[index as usize]
+    }
+
+    /// The cumulative is kept modulo 2^128 and `get_twap` takes the wrapping difference, which is exact
+    /// while the true difference fits in u128. Sqrt price is below 2^97, so windows up to 2^31 seconds are exact
+    fn get_sqrt_price_cumulative(&self, sqrt_price: u128, current_timestamp: u64) -> Result<u128> {
+        let latest = self.get_observation(0);
+        let elapsed = current_timestamp.safe_sub(latest.timestamp)?;
+        Ok(latest
+            .sqrt_price_cumulative
+            .wrapping_add(sqrt_price.wrapping_mul(elapsed.into())))
+    }
+
+    /// Record the sqrt price held since the latest observation
+    pub fn update(&mut self, sqrt_price: u128, current_timestamp: u64) -> Result<()> {
+        if current_timestamp <= self.get_observation(0).timestamp {
+            return Ok(());
+        }
+
+        let observation = Observation {
+            sqrt_price_cumulative: self.get_sqrt_price_cumulative(sqrt_price, current_timestamp)?,
+            timestamp: current_timestamp,
+            ..Default::default()
+        };
+
+        // keep observations apart by the interval, so the buffer covers a longer window
+        let overwrite_latest = self.observation_count > 1
+            && current_timestamp.safe_sub(self.get_observation(1).timestamp)?
+                < ORACLE_OBSERVATION_INTERVAL;
+        if !overwrite_latest {
+            self.observation_index = (self.observation_index + 1) % NUM_ORACLE_OBSERVATIONS as u64;
+            self.observation_count = self
+                .observation_count
+                .safe_add(1)?
+                .min(NUM_ORACLE_OBSERVATIONS as u64);
+        }
+        self.observations[self.observation_index as usize] = observation;
+
+        Ok(())
+    }
+
+    /// Time weighted average sqrt price over at least `window` seconds, starting from the latest observation at or before current_timestamp - window
+    pub fn get_twap(
+        &self,
+        window: u64,
+        current_sqrt_price: u128,
+        current_timestamp: u64,
+    ) -> Result<u128> {
+        require!(window > 0, PoolError::InvalidOracleWindow);
+        let target_timestamp = current_timestamp
+            .checked_sub(window)
+            .ok_or(PoolError::InvalidOracleWindow)?;
+
+        let observation = (0..self.observation_count)
+            .map(|offset| self.get_observation(offset))
+            .find(|observation| observation.timestamp <= target_timestamp)
+            .ok_or(PoolError::InvalidOracleWindow)?;
+
+        let sqrt_price_cumulative =
+            self.get_sqrt_price_cumulative(current_sqrt_price, current_timestamp)?;
+        let elapsed = current_timestamp.safe_sub(observation.timestamp)?;
+
+        Ok(sqrt_price_cumulative
+            .wrapping_sub(observation.sqrt_price_cumulative)
+            .safe_div(elapsed.into())?)
+    }
+}
+
+/// Load the oracle passed to a swap, it is required once the pool has one so that every price move is recorded
+pub fn load_oracle<'a>(
+    pool: &Pool,
+    pool_key: Pubkey,
+    oracle: Option<&'a AccountLoader<Oracle>>,
+) -> Result<Option<RefMut<'a, Oracle>>> {
+    if !pool.has_oracle() {
+        require!(oracle.is_none(), PoolError::InvalidOracleAccount);
+        return Ok(None);
+    }
+    let oracle = oracle.ok_or(PoolError::InvalidOracleAccount)?;
+    let (oracle_address, _bump) =
+        Pubkey::find_program_address(&[ORACLE_PREFIX, pool_key.as_ref()], &crate::ID);
+    require_keys_eq!(
+        oracle.key(),
+        oracle_address,
+        PoolError::InvalidOracleAccount
+    );
+    Ok(Some(oracle.load_mut()?))
+}
@@ -1,6 +1,6 @@
 use ruint::aliases::U256;
 use static_assertions::const_assert_eq;
-use std::cmp::min;
+use std::cmp::{max, min};
 use std::u64;
 
 use anchor_lang::prelude::*;
@@ -8,17 +8,23 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 
 use crate::{
     assert_eq_admin,
-    constants::{LIQUIDITY_SCALE, NUM_REWARDS, REWARD_RATE_SCALE},
+    constants::{
+        DEFAULT_QUOTE_MINTS, LIQUIDITY_SCALE, MAX_REWARD_START_DELAY, NUM_REWARDS,
+        REWARD_RATE_SCALE,
+    },
     curve::{
         get_delta_amount_a_unsigned, get_delta_amount_a_unsigned_unchecked,
-        get_delta_amount_b_unsigned, get_next_sqrt_price_from_input,
+        get_delta_amount_b_unsigned, get_delta_amount_b_unsigned_unchecked,
+        get_liquidity_delta_from_amount_a, get_liquidity_delta_from_amount_b,
+        get_next_sqrt_price_from_input, get_next_sqrt_price_from_output,
     },
     params::swap::TradeDirection,
     safe_math::SafeMath,
     state::{
         fee::{DynamicFeeStruct, FeeOnAmountResult, PoolFeesStruct},
-        Position,
+        Oracle, Position,
     },
+    treasury,
     u128x128_math::{shl_div_256, Rounding},
     utils_math::{safe_mul_shr_cast, safe_shl_div_cast},
     PoolError,
@@ -129,8 +135,10 @@ pub struct Pool {
     pub collect_fee_mode: u8,
     /// pool type
     pub pool_type: u8,
-    /// padding
-    pub _padding_0: [u8; 2],
+    /// 1 if the pool has an oracle, swaps must then record the price to it
+    pub oracle_flag: u8,
+    /// 1 if the pool has a reward extension, which positions opt in to with a position reward extension
+    pub reward_extension_flag: u8,
     /// cumulative
     pub fee_a_per_liquidity: [u8; 32], // U256
     /// cumulative
@@ -139,8 +147,14 @@ pub struct Pool {
     pub permanent_lock_liquidity: u128,
     /// metrics
     pub metrics: PoolMetrics,
-    /// Padding for further use
-    pub _padding_1: [u64; 10],
+    /// Receiver of protocol fee. When it's Pubkey::default, protocol fee goes to treasury
+    pub protocol_fee_receiver: Pubkey,
+    /// referral a fee, accrued to referral accounts and not claimed yet
+    pub referral_a_fee: u64,
+    /// referral b fee, accrued to referral accounts and not claimed yet
+    pub referral_b_fee: u64,
+    /// pool creator if it signed the pool initialization, Pubkey::default otherwise and for pools created before it was recorded
+    pub creator: Pubkey,
     /// Farming reward information
     pub reward_infos: [RewardInfo; NUM_REWARDS],
 }
@@ -203,8 +217,8 @@ pub struct RewardInfo {
     pub reward_token_flag: u8,
     /// padding
     pub _padding_0: [u8; 6],
-    /// Padding to ensure `reward_rate: u128` is 16-byte aligned
-    pub _padding_1: [u8; 8], // 8 bytes
+    /// Time the current reward period starts, rewards don't accrue before it
+    pub reward_start_time: u64,
     /// Reward token mint.
     pub mint: Pubkey,
     /// Reward vault token account.
@@ -288,11 +302,21 @@ impl RewardInfo {
 
     pub fn get_seconds_elapsed_since_last_update(&self, current_time: u64) -> Result<u64> {
         let last_time_reward_applicable = min(current_time, self.reward_duration_end);
-        let time_period = last_time_reward_applicable.safe_sub(self.last_update_time.into())?;
+        // time before the reward start doesn't count
+        let first_time_reward_applicable = max(self.last_update_time, self.reward_start_time);
+        if last_time_reward_applicable <= first_time_reward_applicable {
+            return Ok(0);
+        }
+        let time_period = last_time_reward_applicable.safe_sub(first_time_reward_applicable)?;
 
         Ok(time_period)
     }
 
+    /// Returns true if the reward period has started and not ended yet
+    pub fn is_reward_in_progress(&self, current_time: u64) -> bool {
+        current_time >= self.reward_start_time && current_time < self.reward_duration_end
+    }
+
     // To make it simple we truncate decimals of liquidity_supply for the calculation
     pub fn calculate_reward_per_token_stored_since_last_update(
         &self,
@@ -321,18 +345,54 @@ impl RewardInfo {
         U256::from_le_bytes(self.reward_per_token_stored)
     }
 
-    /// Farming rate after funding
+    pub fn claim_ineligible_reward(&mut self) -> Result<u64> {
+        // calculate ineligible reward
+        let ineligible_reward: u64 = safe_mul_shr_cast(
+            self.cumulative_seconds_with_empty_liquidity_reward.into(),
+            self.reward_rate,
+            REWARD_RATE_SCALE,
+        )?;
+
+        self.cumulative_seconds_with_empty_liquidity_reward = 0;
+
+        Ok(ineligible_reward)
+    }
+
+    /// Farming rate after funding. The reward period starts at `reward_start_time`,
+    /// or at the funding time if not specified
     pub fn update_rate_after_funding(
         &mut self,
         current_time: u64,
         funding_amount: u64,
+        reward_start_time: Option<u64>,
     ) -> Result<()> {
+        let reward_start_time = if let Some(reward_start_time) = reward_start_time {
+            require!(
+                reward_start_time >= current_time
+                    && reward_start_time <= current_time.safe_add(MAX_REWARD_START_DELAY)?,
+                PoolError::InvalidRewardStartTime
+            );
+            // rescheduling a running reward would stop it until the new start
+            require!(
+                !self.is_reward_in_progress(current_time),
+                PoolError::RewardCampaignInProgress
+            );
+            reward_start_time
+        } else if self.reward_start_time > current_time {
+            // a top up keeps the start of a scheduled reward that hasn't started yet
+            self.reward_start_time
+        } else {
+            current_time
+        };
+
         let reward_duration_end = self.reward_duration_end;
 
         let total_amount = if current_time >= reward_duration_end {
             funding_amount
         } else {
-            let remaining_seconds = reward_duration_end.safe_sub(current_time)?;
+            // leftover of a scheduled reward that hasn't started yet is the whole period
+            let remaining_seconds =
+                reward_duration_end.safe_sub(max(current_time, self.reward_start_time))?;
             let leftover: u64 = safe_mul_shr_cast(
                 self.reward_rate,
                 remaining_seconds.into(),
@@ -349,7 +409,8 @@ impl RewardInfo {
             Rounding::Down,
         )?;
         self.last_update_time = current_time;
-        self.reward_duration_end = current_time.safe_add(self.reward_duration)?;
+        self.reward_start_time = reward_start_time;
+        self.reward_duration_end = reward_start_time.safe_add(self.reward_duration)?;
 
         Ok(())
     }
@@ -365,6 +426,8 @@ impl Pool {
         token_b_vault: Pubkey,
         whitelisted_vault: Pubkey,
         partner: Pubkey,
+        creator: Pubkey,
+        protocol_fee_receiver: Pubkey,
         sqrt_min_price: u128,
         sqrt_max_price: u128,
         sqrt_price: u128,
@@ -385,6 +448,8 @@ impl Pool {
         self.token_b_vault = token_b_vault;
         self.whitelisted_vault = whitelisted_vault;
         self.partner = partner;
+        self.creator = creator;
+        self.protocol_fee_receiver = protocol_fee_receiver;
         self.sqrt_min_price = sqrt_min_price;
         self.sqrt_max_price = sqrt_max_price;
         self.activation_point = activation_point;
@@ -399,6 +464,14 @@ impl Pool {
         self.pool_type = pool_type;
     }
 
+    pub fn get_protocol_fee_receiver(&self) -> Pubkey {
+        if self.protocol_fee_receiver.eq(&Pubkey::default()) {
+            treasury::ID
+        } else {
+            self.protocol_fee_receiver
+        }
+    }
+
     pub fn pool_reward_initialized(&self) -> bool {
         self.reward_infos[0].initialized() || self.reward_infos[1].initialized()
     }
@@ -425,6 +498,7 @@ impl Pool {
             } = self.pool_fees.get_fee_on_amount(
                 amount_in,
                 fee_mode.has_referral,
+                trade_direction,
                 current_point,
                 self.activation_point,
             )?;
@@ -459,6 +533,7 @@ impl Pool {
             } = self.pool_fees.get_fee_on_amount(
                 output_amount,
                 fee_mode.has_referral,
+                trade_direction,
                 current_point,
                 self.activation_point,
             )?;
@@ -523,10 +598,317 @@ impl Pool {
         })
     }
 
+    /// Swap up to `sqrt_price_limit`, consuming only the input needed to reach it when `amount_in` would cross it
+    pub fn get_swap_result_with_price_limit(
+        &self,
+        amount_in: u64,
+        sqrt_price_limit: u128,
+        fee_mode: &FeeMode,
+        trade_direction: TradeDirection,
+        current_point: u64,
+    ) -> Result<SwapWithPriceLimitResult> {
+        // clamp the limit to the pool bound
+        let target_sqrt_price = match trade_direction {
+            TradeDirection::AtoB => {
+                require!(
+                    sqrt_price_limit < self.sqrt_price,
+                    PoolError::InvalidSqrtPriceLimit
+                );
+                sqrt_price_limit.max(self.sqrt_min_price)
+            }
+            TradeDirection::BtoA => {
+                require!(
+                    sqrt_price_limit > self.sqrt_price,
+                    PoolError::InvalidSqrtPriceLimit
+                );
+                sqrt_price_limit.min(self.sqrt_max_price)
+            }
+        };
+
+        let max_amount_in = self.get_max_amount_in_to_price(
+            target_sqrt_price,
+            fee_mode,
+            trade_direction,
+            current_point,
+        )?;
+
+        if amount_in < max_amount_in {
+            match self.get_swap_result(amount_in, fee_mode, trade_direction, current_point) {
+                Ok(swap_result) => {
+                    let is_crossed = match trade_direction {
+                        TradeDirection::AtoB => swap_result.next_sqrt_price < target_sqrt_price,
+                        TradeDirection::BtoA => swap_result.next_sqrt_price > target_sqrt_price,
+                    };
+                    if !is_crossed {
+                        return Ok(SwapWithPriceLimitResult {
+                            consumed_amount_in: amount_in,
+                            swap_result,
+                        });
+                    }
+                }
+                // rounding may push the price slightly over the pool bound, fill up to the bound instead
+                Err(err) if err == PoolError::PriceRangeViolation.into() => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        // fill up to the target price
+        let consumed_amount_in = amount_in.min(max_amount_in);
+
+        let mut actual_protocol_fee = 0;
+        let mut actual_lp_fee = 0;
+        let mut actual_referral_fee = 0;
+        let mut actual_partner_fee = 0;
+
+        if fee_mode.fees_on_input {
+            let FeeOnAmountResult {
+                amount: _,
+                lp_fee,
+                protocol_fee,
+                partner_fee,
+                referral_fee,
+            } = self.pool_fees.get_fee_on_amount(
+                consumed_amount_in,
+                fee_mode.has_referral,
+                trade_direction,
+                current_point,
+                self.activation_point,
+            )?;
+
+            actual_protocol_fee = protocol_fee;
+            actual_lp_fee = lp_fee;
+            actual_referral_fee = referral_fee;
+            actual_partner_fee = partner_fee;
+        }
+
+        let output_amount = match trade_direction {
+            TradeDirection::AtoB => get_delta_amount_b_unsigned(
+                target_sqrt_price,
+                self.sqrt_price,
+                self.liquidity,
+                Rounding::Down,
+            )?,
+            TradeDirection::BtoA => get_delta_amount_a_unsigned(
+                self.sqrt_price,
+                target_sqrt_price,
+                self.liquidity,
+                Rounding::Down,
+            )?,
+        };
+
+        let actual_amount_out = if fee_mode.fees_on_input {
+            output_amount
+        } else {
+            let FeeOnAmountResult {
+                amount,
+                lp_fee,
+                protocol_fee,
+                partner_fee,
+                referral_fee,
+            } = self.pool_fees.get_fee_on_amount(
+                output_amount,
+                fee_mode.has_referral,
+                trade_direction,
+                current_point,
+                self.activation_point,
+            )?;
+            actual_protocol_fee = protocol_fee;
+            actual_lp_fee = lp_fee;
+            actual_referral_fee = referral_fee;
+            actual_partner_fee = partner_fee;
+            amount
+        };
+
+        Ok(SwapWithPriceLimitResult {
+            consumed_amount_in,
+            swap_result: SwapResult {
+                output_amount: actual_amount_out,
+                next_sqrt_price: target_sqrt_price,
+                lp_fee: actual_lp_fee,
+                protocol_fee: actual_protocol_fee,
+                partner_fee: actual_partner_fee,
+                referral_fee: actual_referral_fee,
+            },
+        })
+    }
+
+    /// Input amount, fee included, needed to move the price to `target_sqrt_price`. Saturates at u64::MAX
+    fn get_max_amount_in_to_price(
+        &self,
+        target_sqrt_price: u128,
+        fee_mode: &FeeMode,
+        trade_direction: TradeDirection,
+        current_point: u64,
+    ) -> Result<u64> {
+        let amount = match trade_direction {
+            TradeDirection::AtoB => get_delta_amount_a_unsigned_unchecked(
+                target_sqrt_price,
+                self.sqrt_price,
+                self.liquidity,
+                Rounding::Up,
+            )?,
+            TradeDirection::BtoA => get_delta_amount_b_unsigned_unchecked(
+                self.sqrt_price,
+                target_sqrt_price,
+                self.liquidity,
+                Rounding::Up,
+            )?,
+        };
+        if amount > U256::from(u64::MAX) {
+            return Ok(u64::MAX);
+        }
+        let amount: u64 = amount.try_into().unwrap();
+
+        if fee_mode.fees_on_input {
+            match self.pool_fees.get_fee_on_excluded_amount(
+                amount,
+                fee_mode.has_referral,
+                trade_direction,
+                current_point,
+                self.activation_point,
+            ) {
+                Ok(fee_result) => Ok(fee_result.amount),
+                Err(err) if err == PoolError::TypeCastFailed.into() => Ok(u64::MAX),
+                Err(err) => Err(err),
+            }
+        } else {
+            Ok(amount)
+        }
+    }
+
+    pub fn get_swap_result_exact_out(
+        &self,
+        amount_out: u64,
+        fee_mode: &FeeMode,
+        trade_direction: TradeDirection,
+        current_point: u64,
+    ) -> Result<SwapExactOutResult> {
+        let mut actual_protocol_fee = 0;
+        let mut actual_lp_fee = 0;
+        let mut actual_referral_fee = 0;
+        let mut actual_partner_fee = 0;
+
+        // when fees are collected on output, the curve has to release the fee on top of amount_out
+        let actual_amount_out = if fee_mode.fees_on_input {
+            amount_out
+        } else {
+            let FeeOnAmountResult {
+                amount,
+                lp_fee,
+                protocol_fee,
+                partner_fee,
+                referral_fee,
+            } = self.pool_fees.get_fee_on_excluded_amount(
+                amount_out,
+                fee_mode.has_referral,
+                trade_direction,
+                current_point,
+                self.activation_point,
+            )?;
+            actual_protocol_fee = protocol_fee;
+            actual_lp_fee = lp_fee;
+            actual_referral_fee = referral_fee;
+            actual_partner_fee = partner_fee;
+            amount
+        };
+
+        let SwapExactOutAmount {
+            input_amount,
+            next_sqrt_price,
+        } = match trade_direction {
+            TradeDirection::AtoB => self.get_swap_result_exact_out_from_a_to_b(actual_amount_out),
+            TradeDirection::BtoA => self.get_swap_result_exact_out_from_b_to_a(actual_amount_out),
+        }?;
+
+        let actual_amount_in = if fee_mode.fees_on_input {
+            let FeeOnAmountResult {
+                amount,
+                lp_fee,
+                protocol_fee,
+                partner_fee,
+                referral_fee,
+            } = self.pool_fees.get_fee_on_excluded_amount(
+                input_amount,
+                fee_mode.has_referral,
+                trade_direction,
+                current_point,
+                self.activation_point,
+            )?;
+
+            actual_protocol_fee = protocol_fee;
+            actual_lp_fee = lp_fee;
+            actual_referral_fee = referral_fee;
+            actual_partner_fee = partner_fee;
+
+            amount
+        } else {
+            input_amount
+        };
+
+        Ok(SwapExactOutResult {
+            input_amount: actual_amount_in,
+            swap_result: SwapResult {
+                output_amount: amount_out,
+                next_sqrt_price,
+                lp_fee: actual_lp_fee,
+                protocol_fee: actual_protocol_fee,
+                partner_fee: actual_partner_fee,
+                referral_fee: actual_referral_fee,
+            },
+        })
+    }
+
+    fn get_swap_result_exact_out_from_a_to_b(&self, amount_out: u64) -> Result<SwapExactOutAmount> {
+        // finding new target price
+        let next_sqrt_price =
+            get_next_sqrt_price_from_output(self.sqrt_price, self.liquidity, amount_out, true)?;
+
+        if next_sqrt_price < self.sqrt_min_price {
+            return Err(PoolError::PriceRangeViolation.into());
+        }
+
+        // finding input amount
+        let input_amount = get_delta_amount_a_unsigned(
+            next_sqrt_price,
+            self.sqrt_price,
+            self.liquidity,
+            Rounding::Up,
+        )?;
+
+        Ok(SwapExactOutAmount {
+            input_amount,
+            next_sqrt_price,
+        })
+    }
+
+    fn get_swap_result_exact_out_from_b_to_a(&self, amount_out: u64) -> Result<SwapExactOutAmount> {
+        // finding new target price
+        let next_sqrt_price =
+            get_next_sqrt_price_from_output(self.sqrt_price, self.liquidity, amount_out, false)?;
+
+        if next_sqrt_price > self.sqrt_max_price {
+            return Err(PoolError::PriceRangeViolation.into());
+        }
+
+        // finding input amount
+        let input_amount = get_delta_amount_b_unsigned(
+            self.sqrt_price,
+            next_sqrt_price,
+            self.liquidity,
+            Rounding::Up,
+        )?;
+
+        Ok(SwapExactOutAmount {
+            input_amount,
+            next_sqrt_price,
+        })
+    }
+
     pub fn apply_swap_result(
         &mut self,
         swap_result: &SwapResult,
         fee_mode: &FeeMode,
+        oracle: Option<&mut Oracle>,
         current_timestamp: u64,
     ) -> Result<()> {
         let &SwapResult {
@@ -538,6 +920,11 @@ impl Pool {
             referral_fee: _referral_fee,
         } = swap_result;
 
+        // record the price held until this swap before moving it
+        if let Some(oracle) = oracle {
+            oracle.update(self.sqrt_price, current_timestamp)?;
+        }
+
         let old_sqrt_price = self.sqrt_price;
         self.sqrt_price = next_sqrt_price;
 
@@ -595,6 +982,145 @@ impl Pool {
         })
     }
 
+    /// Largest liquidity delta that can be added with at most `token_a_amount` and `token_b_amount`,
+    /// matching the rounding of `get_amounts_for_modify_liquidity` with `Rounding::Up`
+    pub fn get_liquidity_delta_from_amounts(
+        &self,
+        token_a_amount: u64,
+        token_b_amount: u64,
+    ) -> Result<u128> {
+        let (liquidity_delta_from_a, liquidity_delta_from_b) = self
+            .get_liquidity_delta_from_each_amount(
+                self.sqrt_price,
+                token_a_amount,
+                token_b_amount,
+            )?;
+
+        Ok(liquidity_delta_from_a
+            .min(liquidity_delta_from_b)
+            .min(U256::from(u128::MAX))
+            .try_into()
+            .map_err(|_| PoolError::TypeCastFailed)?)
+    }
+
+    /// Liquidity delta backed by each token amount at `sqrt_price`, U256::MAX for the token that is not needed
+    fn get_liquidity_delta_from_each_amount(
+        &self,
+        sqrt_price: u128,
+        token_a_amount: u64,
+        token_b_amount: u64,
+    ) -> Result<(U256, U256)> {
+        if sqrt_price >= self.sqrt_max_price {
+            // only token b is needed
+            let liquidity_delta_from_b =
+                get_liquidity_delta_from_amount_b(self.sqrt_min_price, sqrt_price, token_b_amount)?;
+            Ok((U256::MAX, liquidity_delta_from_b))
+        } else if sqrt_price <= self.sqrt_min_price {
+            // only token a is needed
+            let liquidity_delta_from_a =
+                get_liquidity_delta_from_amount_a(sqrt_price, self.sqrt_max_price, token_a_amount)?;
+            Ok((liquidity_delta_from_a, U256::MAX))
+        } else {
+            let liquidity_delta_from_a =
+                get_liquidity_delta_from_amount_a(sqrt_price, self.sqrt_max_price, token_a_amount)?;
+            let liquidity_delta_from_b =
+                get_liquidity_delta_from_amount_b(self.sqrt_min_price, sqrt_price, token_b_amount)?;
+            Ok((liquidity_delta_from_a, liquidity_delta_from_b))
+        }
+    }
+
+    /// Split `amount_in` of the input token into a swap leg and a deposit leg so that the liquidity
+    /// added after the swap is maximized
+    pub fn get_zap_in_result(
+        &self,
+        amount_in: u64,
+        fee_mode: &FeeMode,
+        trade_direction: TradeDirection,
+        current_point: u64,
+    ) -> Result<ZapInResult> {
+        // returns the swap result with the liquidity delta backed by the input and output token, None if the swap
+        // would move the price out of the pool range
+        let simulate = |swap_amount_in: u64| -> Result<Option<(SwapResult, U256, U256)>> {
+            let swap_result = match self.get_swap_result(
+                swap_amount_in,
+                fee_mode,
+                trade_direction,
+                current_point,
+            ) {
+                Ok(swap_result) => swap_result,
+                Err(err) if err == PoolError::PriceRangeViolation.into() => return Ok(None),
+                Err(err) => return Err(err),
+            };
+            let remaining_amount_in = amount_in.safe_sub(swap_amount_in)?;
+            let (liquidity_delta_from_input, liquidity_delta_from_output) = match trade_direction {
+                TradeDirection::AtoB => self.get_liquidity_delta_from_each_amount(
+                    swap_result.next_sqrt_price,
+                    remaining_amount_in,
+                    swap_result.output_amount,
+                )?,
+                TradeDirection::BtoA => {
+                    let (from_a, from_b) = self.get_liquidity_delta_from_each_amount(
+                        swap_result.next_sqrt_price,
+                        swap_result.output_amount,
+                        remaining_amount_in,
+                    )?;
+                    (from_b, from_a)
+                }
+            };
+            Ok(Some((
+                swap_result,
+                liquidity_delta_from_input,
+                liquidity_delta_from_output,
+            )))
+        };
+
+        // the liquidity backed by the input token decreases with the swap amount while the one backed
+        // by the output token increases, find the smallest swap amount where the output token catches up
+        let mut low = 0u64;
+        let mut high = amount_in;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let output_catches_up = match simulate(mid)? {
+                Some((_, liquidity_delta_from_input, liquidity_delta_from_output)) => {
+                    liquidity_delta_from_output >= liquidity_delta_from_input
+                }
+                None => true,
+            };
+            if output_catches_up {
+                high = mid;
+            } else {
+                low = mid.safe_add(1)?;
+            }
+        }
+
+        // the best split is either at the crossing point or right before it
+        let mut best: Option<ZapInResult> = None;
+        for swap_amount_in in [low.checked_sub(1), Some(low)].into_iter().flatten() {
+            if let Some((swap_result, liquidity_delta_from_input, liquidity_delta_from_output)) =
+                simulate(swap_amount_in)?
+            {
+                let liquidity_delta: u128 = liquidity_delta_from_input
+                    .min(liquidity_delta_from_output)
+                    .min(U256::from(u128::MAX))
+                    .try_into()
+                    .map_err(|_| PoolError::TypeCastFailed)?;
+                let is_better = match &best {
+                    Some(best) => liquidity_delta > best.liquidity_delta,
+                    None => true,
+                };
+                if is_better {
+                    best = Some(ZapInResult {
+                        swap_amount_in,
+                        swap_result,
+                        liquidity_delta,
+                    });
+                }
+            }
+        }
+
+        best.ok_or(PoolError::PriceRangeViolation.into())
+    }
+
     pub fn apply_add_liquidity(
         &mut self,
         position: &mut Position,
@@ -677,6 +1203,19 @@ impl Pool {
         Ok(())
     }
 
+    /// Replace the pool fees. Dynamic fee references restart from the current price, so volatility
+    /// accumulated under the old settings doesn't leak into the new variable fee
+    pub fn update_pool_fees(&mut self, pool_fees: PoolFeesStruct, current_timestamp: u64) {
+        self.pool_fees = pool_fees;
+        let dynamic_fee = &mut self.pool_fees.dynamic_fee;
+        dynamic_fee.volatility_accumulator = 0;
+        dynamic_fee.volatility_reference = 0;
+        if dynamic_fee.is_dynamic_fee_enable() {
+            dynamic_fee.sqrt_price_reference = self.sqrt_price;
+            dynamic_fee.last_update_timestamp = current_timestamp;
+        }
+    }
+
     pub fn accumulate_permanent_locked_liquidity(
         &mut self,
         permanent_locked_liquidity: u128,
@@ -688,12 +1227,16 @@ impl Pool {
         Ok(())
     }
 
-    pub fn claim_protocol_fee(&mut self) -> (u64, u64) {
-        let token_a_amount = self.protocol_a_fee;
-        let token_b_amount = self.protocol_b_fee;
-        self.protocol_a_fee = 0;
-        self.protocol_b_fee = 0;
-        (token_a_amount, token_b_amount)
+    pub fn claim_protocol_fee(
+        &mut self,
+        max_amount_a: u64,
+        max_amount_b: u64,
+    ) -> Result<(u64, u64)> {
+        let token_a_amount = self.protocol_a_fee.min(max_amount_a);
+        let token_b_amount = self.protocol_b_fee.min(max_amount_b);
+        self.protocol_a_fee = self.protocol_a_fee.safe_sub(token_a_amount)?;
+        self.protocol_b_fee = self.protocol_b_fee.safe_sub(token_b_amount)?;
+        Ok((token_a_amount, token_b_amount))
     }
 
     pub fn claim_partner_fee(
@@ -708,6 +1251,57 @@ impl Pool {
         Ok((token_a_amount, token_b_amount))
     }
 
+    pub fn has_oracle(&self) -> bool {
+        self.oracle_flag == 1
+    }
+
+    pub fn has_reward_extension(&self) -> bool {
+        self.reward_extension_flag == 1
+    }
+
+    /// The pool partner or creator can initialize the creator reward
+    pub fn is_reward_creator(&self, signer: Pubkey) -> bool {
+        signer.ne(&Pubkey::default()) && (signer.eq(&self.partner) || signer.eq(&self.creator))
+    }
+
+    /// Pools with a partner or a creator keep the creator reward index for them
+    pub fn has_reward_creator(&self) -> bool {
+        self.partner.ne(&Pubkey::default()) || self.creator.ne(&Pubkey::default())
+    }
+
+    /// Mints the creator reward can use without a token badge
+    pub fn is_creator_reward_mint(&self, mint: Pubkey) -> bool {
+        mint.eq(&self.token_a_mint)
+            || mint.eq(&self.token_b_mint)
+            || DEFAULT_QUOTE_MINTS.contains(&mint)
+    }
+
+    pub fn accumulate_referral_fee(&mut self, referral_fee: u64, is_token_a: bool) -> Result<()> {
+        if is_token_a {
+            self.referral_a_fee = self.referral_a_fee.safe_add(referral_fee)?;
+        } else {
+            self.referral_b_fee = self.referral_b_fee.safe_add(referral_fee)?;
+        }
+        Ok(())
+    }
+
+    pub fn get_referral_fee(&self, is_token_a: bool) -> u64 {
+        if is_token_a {
+            self.referral_a_fee
+        } else {
+            self.referral_b_fee
+        }
+    }
+
+    pub fn claim_referral_fee(&mut self, amount: u64, is_token_a: bool) -> Result<()> {
+        if is_token_a {
+            self.referral_a_fee = self.referral_a_fee.safe_sub(amount)?;
+        } else {
+            self.referral_b_fee = self.referral_b_fee.safe_sub(amount)?;
+        }
+        Ok(())
+    }
+
     /// Update the rewards per token stored.
     pub fn update_rewards(&mut self, current_time: u64) -> Result<()> {
         for reward_idx in 0..NUM_REWARDS {
@@ -718,22 +1312,6 @@ impl Pool {
         Ok(())
     }
 
-    pub fn claim_ineligible_reward(&mut self, reward_index: usize) -> Result<u64> {
-        // calculate ineligible reward
-        let reward_info = &mut self.reward_infos[reward_index];
-        let ineligible_reward: u64 = safe_mul_shr_cast(
-            reward_info
-                .cumulative_seconds_with_empty_liquidity_reward
-                .into(),
-            reward_info.reward_rate,
-            REWARD_RATE_SCALE,
-        )?;
-
-        reward_info.cumulative_seconds_with_empty_liquidity_reward = 0;
-
-        Ok(ineligible_reward)
-    }
-
     pub fn fee_a_per_liquidity(&self) -> U256 {
         U256::from_le_bytes(self.fee_a_per_liquidity)
     }
@@ -759,6 +1337,37 @@ pub struct SwapAmount {
     next_sqrt_price: u128,
 }
 
+/// Encodes all results of swapping with a sqrt price limit
+#[derive(Debug, PartialEq)]
+pub struct SwapWithPriceLimitResult {
+    /// input amount consumed, less than the requested amount when the swap is partially filled
+    pub consumed_amount_in: u64,
+    pub swap_result: SwapResult,
+}
+
+/// Encodes all results of swapping for an exact output amount
+#[derive(Debug, PartialEq)]
+pub struct SwapExactOutResult {
+    /// input amount required, fee included when fees are collected on input
+    pub input_amount: u64,
+    pub swap_result: SwapResult,
+}
+
+/// Encodes all results of zapping a single token into a position
+#[derive(Debug, PartialEq)]
+pub struct ZapInResult {
+    /// part of the input amount that is swapped
+    pub swap_amount_in: u64,
+    pub swap_result: SwapResult,
+    /// liquidity delta that the remaining input amount and the swap output can add
+    pub liquidity_delta: u128,
+}
+
+pub struct SwapExactOutAmount {
+    input_amount: u64,
+    next_sqrt_price: u128,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct ModifyLiquidityResult {
     pub token_a_amount: u64,
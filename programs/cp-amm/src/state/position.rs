@@ -4,7 +4,7 @@ use static_assertions::const_assert_eq;
 use std::{cell::RefMut, u64};
 
 use crate::{
-    constants::{LIQUIDITY_SCALE, NUM_REWARDS, TOTAL_REWARD_SCALE},
+    constants::{LIQUIDITY_SCALE, NUM_EXTENSION_REWARDS, NUM_REWARDS, TOTAL_REWARD_SCALE},
     safe_math::SafeMath,
     state::Pool,
     utils_math::safe_mul_shr_256_cast,
@@ -46,6 +46,23 @@ impl UserRewardInfo {
     pub fn reward_per_token_checkpoint(&self) -> U256 {
         U256::from_le_bytes(self.reward_per_token_checkpoint)
     }
+
+    pub fn claim_reward(&mut self) -> u64 {
+        let total_reward = self.reward_pendings;
+        self.total_claimed_rewards = self.total_claimed_rewards.wrapping_add(total_reward);
+        self.reward_pendings = 0;
+        total_reward
+    }
+
+    /// Move pending rewards of `source` into this reward info
+    pub fn merge(&mut self, source: &mut UserRewardInfo) -> Result<()> {
+        self.reward_pendings = self.reward_pendings.safe_add(source.reward_pendings)?;
+        self.total_claimed_rewards = self
+            .total_claimed_rewards
+            .wrapping_add(source.total_claimed_rewards);
+        source.reward_pendings = 0;
+        Ok(())
+    }
 }
 
 #[account(zero_copy)]
@@ -72,8 +89,12 @@ pub struct Position {
     pub metrics: PositionMetrics,
     /// Farming reward information
     pub reward_infos: [UserRewardInfo; NUM_REWARDS],
+    /// 1 if the position has a position reward extension, it must then be passed whenever the position rewards are updated
+    pub reward_extension_flag: u8,
+    /// padding
+    pub _padding_0: [u8; 15],
     /// padding for future usage
-    pub padding: [u128; 6],
+    pub padding: [u128; 5],
 }
 
 const_assert_eq!(Position::INIT_SPACE, 400);
@@ -114,11 +135,15 @@ impl Position {
         Ok(())
     }
 
+    pub fn has_reward_extension(&self) -> bool {
+        self.reward_extension_flag == 1
+    }
+
     pub fn has_sufficient_liquidity(&self, liquidity: u128) -> bool {
         self.unlocked_liquidity >= liquidity
     }
 
-    fn get_total_liquidity(&self) -> Result<u128> {
+    pub fn get_total_liquidity(&self) -> Result<u128> {
         Ok(self
             .unlocked_liquidity
             .safe_add(self.vested_liquidity)?
@@ -200,6 +225,23 @@ impl Position {
         self.fee_b_pending = 0;
     }
 
+    /// Update fee to the pool checkpoints and take all pending fee
+    pub fn claim_fee(
+        &mut self,
+        fee_a_per_token_stored: U256,
+        fee_b_per_token_stored: U256,
+    ) -> Result<(u64, u64)> {
+        self.update_fee(fee_a_per_token_stored, fee_b_per_token_stored)?;
+
+        let fee_a_pending = self.fee_a_pending;
+        let fee_b_pending = self.fee_b_pending;
+        self.metrics
+            .accumulate_claimed_fee(fee_a_pending, fee_b_pending)?;
+        self.reset_pending_fee();
+
+        Ok((fee_a_pending, fee_b_pending))
+    }
+
     pub fn update_rewards(&mut self, pool: &mut RefMut<'_, Pool>, current_time: u64) -> Result<()> {
         // update if reward has been initialized
         if pool.pool_reward_initialized() {
@@ -223,28 +265,82 @@ impl Position {
         Ok(())
     }
 
-    fn get_total_reward(&self, reward_index: usize) -> Result<u64> {
-        Ok(self.reward_infos[reward_index].reward_pendings)
+    pub fn claim_reward(&mut self, reward_index: usize) -> Result<u64> {
+        Ok(self.reward_infos[reward_index].claim_reward())
     }
 
-    fn accumulate_total_claimed_rewards(&mut self, reward_index: usize, reward: u64) {
-        let total_claimed_reward = self.reward_infos[reward_index].total_claimed_rewards;
-        self.reward_infos[reward_index].total_claimed_rewards =
-            total_claimed_reward.wrapping_add(reward);
-    }
+    /// Move the split amounts to `new_position`. Both positions must be updated to the same fee and
+    /// reward checkpoints before splitting
+    pub fn split(
+        &mut self,
+        new_position: &mut Position,
+        split_amount: &SplitAmountInfo,
+    ) -> Result<()> {
+        self.remove_unlocked_liquidity(split_amount.unlocked_liquidity)?;
+        new_position.add_liquidity(split_amount.unlocked_liquidity)?;
 
-    pub fn claim_reward(&mut self, reward_index: usize) -> Result<u64> {
-        let total_reward = self.get_total_reward(reward_index)?;
+        self.vested_liquidity = self
+            .vested_liquidity
+            .safe_sub(split_amount.vested_liquidity)?;
+        new_position.vested_liquidity = new_position
+            .vested_liquidity
+            .safe_add(split_amount.vested_liquidity)?;
+
+        self.permanent_locked_liquidity = self
+            .permanent_locked_liquidity
+            .safe_sub(split_amount.permanent_locked_liquidity)?;
+        new_position.permanent_locked_liquidity = new_position
+            .permanent_locked_liquidity
+            .safe_add(split_amount.permanent_locked_liquidity)?;
 
-        self.accumulate_total_claimed_rewards(reward_index, total_reward);
+        self.fee_a_pending = self.fee_a_pending.safe_sub(split_amount.fee_a)?;
+        new_position.fee_a_pending = new_position.fee_a_pending.safe_add(split_amount.fee_a)?;
 
-        self.reset_all_pending_reward(reward_index);
+        self.fee_b_pending = self.fee_b_pending.safe_sub(split_amount.fee_b)?;
+        new_position.fee_b_pending = new_position.fee_b_pending.safe_add(split_amount.fee_b)?;
 
-        Ok(total_reward)
+        for (reward_index, &reward) in split_amount.rewards.iter().enumerate() {
+            let reward_info = &mut self.reward_infos[reward_index];
+            reward_info.reward_pendings = reward_info.reward_pendings.safe_sub(reward)?;
+
+            let new_reward_info = &mut new_position.reward_infos[reward_index];
+            new_reward_info.reward_pendings = new_reward_info.reward_pendings.safe_add(reward)?;
+        }
+
+        Ok(())
     }
 
-    pub fn reset_all_pending_reward(&mut self, reward_index: usize) {
-        self.reward_infos[reward_index].reward_pendings = 0;
+    /// Move all liquidity, pending fees and pending rewards of `source_position` into this position.
+    /// Both positions must be updated to the same fee and reward checkpoints before merging
+    pub fn merge(&mut self, source_position: &mut Position) -> Result<()> {
+        require!(
+            source_position.vested_liquidity == 0,
+            PoolError::PositionHasVestedLiquidity
+        );
+
+        self.add_liquidity(source_position.unlocked_liquidity)?;
+        source_position.remove_unlocked_liquidity(source_position.unlocked_liquidity)?;
+
+        self.permanent_locked_liquidity = self
+            .permanent_locked_liquidity
+            .safe_add(source_position.permanent_locked_liquidity)?;
+        source_position.permanent_locked_liquidity = 0;
+
+        self.fee_a_pending = self.fee_a_pending.safe_add(source_position.fee_a_pending)?;
+        self.fee_b_pending = self.fee_b_pending.safe_add(source_position.fee_b_pending)?;
+        source_position.reset_pending_fee();
+
+        self.metrics.accumulate_claimed_fee(
+            source_position.metrics.total_claimed_a_fee,
+            source_position.metrics.total_claimed_b_fee,
+        )?;
+
+        for reward_index in 0..NUM_REWARDS {
+            self.reward_infos[reward_index]
+                .merge(&mut source_position.reward_infos[reward_index])?;
+        }
+
+        Ok(())
     }
 
     pub fn fee_a_per_token_checkpoint(&self) -> U256 {
@@ -254,3 +350,15 @@ impl Position {
         U256::from_le_bytes(self.fee_b_per_token_checkpoint)
     }
 }
+
+/// Amounts moved from a position to a new position when splitting
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Default, PartialEq)]
+pub struct SplitAmountInfo {
+    pub unlocked_liquidity: u128,
+    pub vested_liquidity: u128,
+    pub permanent_locked_liquidity: u128,
+    pub fee_a: u64,
+    pub fee_b: u64,
+    pub rewards: [u64; NUM_REWARDS],
+    pub extension_rewards: [u64; NUM_EXTENSION_REWARDS],
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use static_assertions::const_assert_eq;
+
+use crate::safe_math::SafeMath;
+
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+/// Referral fee accrued by a referrer in a token mint, across all pools. The fee stays in the vaults of the pools
+/// it was charged in, so it can be claimed from any pool holding referral fee of the token mint
+pub struct ReferralAccount {
+    /// referrer
+    pub referrer: Pubkey,
+    /// token mint of the accrued fee
+    pub token_mint: Pubkey,
+    /// fee accrued and not claimed yet
+    pub pending_fee: u64,
+    /// total fee accrued
+    pub total_fee: u64,
+    /// Reserve
+    pub _padding: [u64; 4],
+}
+
+const_assert_eq!(ReferralAccount::INIT_SPACE, 112);
+
+impl ReferralAccount {
+    pub fn initialize(&mut self, referrer: Pubkey, token_mint: Pubkey) -> Result<()> {
+        self.referrer = referrer;
+        self.token_mint = token_mint;
+        Ok(())
+    }
+
+    pub fn accumulate_fee(&mut self, fee: u64) -> Result<()> {
+        self.pending_fee = self.pending_fee.safe_add(fee)?;
+        self.total_fee = self.total_fee.safe_add(fee)?;
+        Ok(())
+    }
+
+    pub fn claim_fee(&mut self, max_amount: u64) -> Result<u64> {
+        let amount = self.pending_fee.min(max_amount);
+        self.pending_fee = self.pending_fee.safe_sub(amount)?;
+        Ok(amount)
+    }
+}
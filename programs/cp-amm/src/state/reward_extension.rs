@@ -0,0 +1,250 @@
+use anchor_lang::prelude::*;
+use static_assertions::const_assert_eq;
+use std::cell::RefMut;
+
+use crate::{
+    constants::{NUM_EXTENSION_REWARDS, NUM_REWARDS},
+    safe_math::SafeMath,
+    state::{Pool, Position, RewardInfo, UserRewardInfo},
+    PoolError,
+};
+
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+/// Extra reward slots of a pool, addressed by reward index NUM_REWARDS onwards
+pub struct RewardExtension {
+    /// pool
+    pub pool: Pubkey,
+    /// Liquidity of the positions having a position reward extension, the only ones earning the extension rewards
+    pub liquidity: u128,
+    /// Reserve
+    pub _padding: [u64; 2],
+    /// Farming reward information
+    pub reward_infos: [RewardInfo; NUM_EXTENSION_REWARDS],
+}
+
+const_assert_eq!(RewardExtension::INIT_SPACE, 448);
+
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+/// Position rewards of the extra reward slots of a pool
+pub struct PositionRewardExtension {
+    /// position
+    pub position: Pubkey,
+    /// Reserve
+    pub _padding: [u64; 4],
+    /// Farming reward information
+    pub reward_infos: [UserRewardInfo; NUM_EXTENSION_REWARDS],
+}
+
+const_assert_eq!(PositionRewardExtension::INIT_SPACE, 160);
+
+impl RewardExtension {
+    pub fn initialize(&mut self, pool: Pubkey) {
+        self.pool = pool;
+    }
+
+    pub fn update_rewards(&mut self, current_time: u64) -> Result<()> {
+        for reward_info in self.reward_infos.iter_mut() {
+            reward_info.update_rewards(self.liquidity, current_time)?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the liquidity of a position having a position reward extension. Rewards must be updated before
+    pub fn update_liquidity(
+        &mut self,
+        liquidity_before: u128,
+        liquidity_after: u128,
+    ) -> Result<()> {
+        self.liquidity = self
+            .liquidity
+            .safe_sub(liquidity_before)?
+            .safe_add(liquidity_after)?;
+        Ok(())
+    }
+}
+
+impl PositionRewardExtension {
+    pub fn initialize(&mut self, position: Pubkey) {
+        self.position = position;
+    }
+
+    /// Reward extension must be updated before
+    pub fn update_rewards(
+        &mut self,
+        reward_extension: &RewardExtension,
+        position_liquidity: u128,
+    ) -> Result<()> {
+        for (reward_info, pool_reward_info) in self
+            .reward_infos
+            .iter_mut()
+            .zip(reward_extension.reward_infos.iter())
+        {
+            if pool_reward_info.initialized() {
+                reward_info.update_rewards(
+                    position_liquidity,
+                    pool_reward_info.reward_per_token_stored(),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Start from the current checkpoints, so the position doesn't earn past rewards
+    pub fn reset_checkpoints(&mut self, reward_extension: &RewardExtension) {
+        for (reward_info, pool_reward_info) in self
+            .reward_infos
+            .iter_mut()
+            .zip(reward_extension.reward_infos.iter())
+        {
+            reward_info.reward_per_token_checkpoint = pool_reward_info.reward_per_token_stored;
+        }
+    }
+
+    /// Move `extension_rewards` of the pending rewards to the reward extension of the new position
+    pub fn split(
+        &mut self,
+        new_position_reward_extension: &mut PositionRewardExtension,
+        extension_rewards: &[u64; NUM_EXTENSION_REWARDS],
+    ) -> Result<()> {
+        for ((reward_info, new_reward_info), &reward) in self
+            .reward_infos
+            .iter_mut()
+            .zip(new_position_reward_extension.reward_infos.iter_mut())
+            .zip(extension_rewards.iter())
+        {
+            reward_info.reward_pendings = reward_info.reward_pendings.safe_sub(reward)?;
+            new_reward_info.reward_pendings = new_reward_info.reward_pendings.safe_add(reward)?;
+        }
+
+        Ok(())
+    }
+
+    /// Move all pending rewards of `source` into this extension. Both must be updated before merging
+    pub fn merge(&mut self, source: &mut PositionRewardExtension) -> Result<()> {
+        for (reward_info, source_reward_info) in self
+            .reward_infos
+            .iter_mut()
+            .zip(source.reward_infos.iter_mut())
+        {
+            reward_info.merge(source_reward_info)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reward indexes from NUM_REWARDS onwards are stored in the reward extension
+pub fn get_extension_reward_index(reward_index: usize) -> Option<usize> {
+    reward_index
+        .checked_sub(NUM_REWARDS)
+        .filter(|&index| index < NUM_EXTENSION_REWARDS)
+}
+
+/// Reward info of a reward index, the reward extension is needed for indexes from NUM_REWARDS onwards
+pub fn get_reward_info<'a>(
+    pool: &'a Pool,
+    reward_extension: Option<&'a RewardExtension>,
+    reward_index: usize,
+) -> Result<&'a RewardInfo> {
+    if reward_index < NUM_REWARDS {
+        return Ok(&pool.reward_infos[reward_index]);
+    }
+    let index = get_extension_reward_index(reward_index).ok_or(PoolError::InvalidRewardIndex)?;
+    let reward_extension = reward_extension.ok_or(PoolError::InvalidRewardIndex)?;
+    Ok(&reward_extension.reward_infos[index])
+}
+
+pub fn get_reward_info_mut<'a>(
+    pool: &'a mut Pool,
+    reward_extension: Option<&'a mut RewardExtension>,
+    reward_index: usize,
+) -> Result<&'a mut RewardInfo> {
+    if reward_index < NUM_REWARDS {
+        return Ok(&mut pool.reward_infos[reward_index]);
+    }
+    let index = get_extension_reward_index(reward_index).ok_or(PoolError::InvalidRewardIndex)?;
+    let reward_extension = reward_extension.ok_or(PoolError::InvalidRewardIndex)?;
+    Ok(&mut reward_extension.reward_infos[index])
+}
+
+/// Load the reward extension of a pool, the reward indexes from NUM_REWARDS onwards and the positions having a
+/// position reward extension need it
+pub fn load_reward_extension<'a>(
+    pool: &Pool,
+    pool_key: Pubkey,
+    reward_extension: Option<&'a AccountLoader<RewardExtension>>,
+) -> Result<Option<RefMut<'a, RewardExtension>>> {
+    let Some(reward_extension) = reward_extension else {
+        return Ok(None);
+    };
+    require!(
+        pool.has_reward_extension(),
+        PoolError::InvalidRewardExtension
+    );
+    let reward_extension = reward_extension.load_mut()?;
+    require_keys_eq!(
+        reward_extension.pool,
+        pool_key,
+        PoolError::InvalidRewardExtension
+    );
+    Ok(Some(reward_extension))
+}
+
+/// Load the reward extension of a position, it is required once the position has one
+pub fn load_position_reward_extension<'a>(
+    position: &Position,
+    position_key: Pubkey,
+    position_reward_extension: Option<&'a AccountLoader<PositionRewardExtension>>,
+) -> Result<Option<RefMut<'a, PositionRewardExtension>>> {
+    if !position.has_reward_extension() {
+        require!(
+            position_reward_extension.is_none(),
+            PoolError::InvalidRewardExtension
+        );
+        return Ok(None);
+    }
+    let position_reward_extension = position_reward_extension
+        .ok_or(PoolError::InvalidRewardExtension)?
+        .load_mut()?;
+    require_keys_eq!(
+        position_reward_extension.position,
+        position_key,
+        PoolError::InvalidRewardExtension
+    );
+    Ok(Some(position_reward_extension))
+}
+
+/// Update the extension rewards of a position before its liquidity changes. Positions without a position reward
+/// extension don't earn extension rewards, so there is nothing to update
+pub fn update_position_reward_extension(
+    position: &Position,
+    reward_extension: Option<&mut RewardExtension>,
+    position_reward_extension: Option<&mut PositionRewardExtension>,
+    current_time: u64,
+) -> Result<()> {
+    let Some(position_reward_extension) = position_reward_extension else {
+        return Ok(());
+    };
+    let reward_extension = reward_extension.ok_or(PoolError::InvalidRewardExtension)?;
+    reward_extension.update_rewards(current_time)?;
+    position_reward_extension.update_rewards(reward_extension, position.get_total_liquidity()?)
+}
+
+/// Keep the reward extension liquidity in sync after the liquidity of a position changed
+pub fn update_reward_extension_liquidity(
+    position: &Position,
+    reward_extension: Option<&mut RewardExtension>,
+    liquidity_before: u128,
+) -> Result<()> {
+    if position.has_reward_extension() {
+        reward_extension
+            .ok_or(PoolError::InvalidRewardExtension)?
+            .update_liquidity(liquidity_before, position.get_total_liquidity()?)?;
+    }
+
+    Ok(())
+}
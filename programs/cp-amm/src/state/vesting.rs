@@ -80,6 +80,46 @@ impl Vesting {
         Ok(())
     }
 
+    pub fn get_locked_liquidity(&self) -> Result<u128> {
+        let locked_liquidity = self
+            .get_total_lock_amount()?
+            .safe_sub(self.total_released_liquidity)?;
+        Ok(locked_liquidity)
+    }
+
+    /// Move `cliff_unlock_liquidity` and `liquidity_per_period` of this vesting schedule to `new_vesting`,
+    /// bound to `new_position`. The released liquidity must be up to date at `current_point`. Returns the
+    /// locked liquidity moved to `new_vesting`
+    pub fn split(
+        &mut self,
+        new_vesting: &mut Vesting,
+        new_position: Pubkey,
+        cliff_unlock_liquidity: u128,
+        liquidity_per_period: u128,
+        current_point: u64,
+    ) -> Result<u128> {
+        new_vesting.initialize(
+            new_position,
+            self.cliff_point,
+            self.period_frequency,
+            cliff_unlock_liquidity,
+            liquidity_per_period,
+            self.number_of_period,
+        );
+        // both schedules keep following the released liquidity, so neither releases more than it holds
+        let released_liquidity = new_vesting.get_max_unlocked_liquidity(current_point)?;
+        new_vesting.total_released_liquidity = released_liquidity;
+
+        self.cliff_unlock_liquidity = self
+            .cliff_unlock_liquidity
+            .safe_sub(cliff_unlock_liquidity)?;
+        self.liquidity_per_period = self.liquidity_per_period.safe_sub(liquidity_per_period)?;
+        self.total_released_liquidity =
+            self.total_released_liquidity.safe_sub(released_liquidity)?;
+
+        new_vesting.get_locked_liquidity()
+    }
+
     pub fn done(&self) -> Result<bool> {
         Ok(self.total_released_liquidity == self.get_total_lock_amount()?)
     }
@@ -1,4 +1,10 @@
-use crate::tests::price_math::get_price_from_id;
+use anchor_lang::AnchorSerialize;
+
+use crate::{
+    constants::{ALLOWED_BIN_STEPS, BIN_STEP_BPS_DEFAULT, BIN_STEP_BPS_U128_DEFAULT, U24_MAX},
+    params::fee_parameters::{DynamicFeeParameters, ExtraFeeParameters, PoolFeeParameters},
+    tests::price_math::get_price_from_id,
+};
 
 const BASIS_POINT_MAX: u64 = 10_000;
 
@@ -175,3 +181,88 @@ fn test_from_price_to_bin_id() {
 
     println!("{:?}", model);
 }
+
+fn get_dynamic_fee_parameters(bin_step: u16) -> DynamicFeeParameters {
+    let mut params = DynamicFeeParameters {
+        bin_step,
+        bin_step_u128: 0,
+        filter_period: 10,
+        decay_period: 120,
+        reduction_factor: 5000,
+        max_volatility_accumulator: U24_MAX,
+        variable_fee_control: U24_MAX,
+    };
+    params.bin_step_u128 = params.get_bin_step_u128();
+    params
+}
+
+#[test]
+fn test_bin_step_u128() {
+    let params = get_dynamic_fee_parameters(BIN_STEP_BPS_DEFAULT);
+    assert_eq!(params.get_bin_step_u128(), BIN_STEP_BPS_U128_DEFAULT);
+
+    // bin_step_u128 = floor(bin_step << 64 / BASIS_POINT_MAX)
+    for bin_step in ALLOWED_BIN_STEPS {
+        let bin_step_u128 = get_dynamic_fee_parameters(bin_step).get_bin_step_u128();
+        let scaled_bin_step = u128::from(bin_step) << 64;
+        assert!(bin_step_u128 * u128::from(BASIS_POINT_MAX) <= scaled_bin_step);
+        assert!((bin_step_u128 + 1) * u128::from(BASIS_POINT_MAX) > scaled_bin_step);
+    }
+}
+
+#[test]
+fn test_validate_bin_step() {
+    for bin_step in ALLOWED_BIN_STEPS {
+        assert!(get_dynamic_fee_parameters(bin_step).validate().is_ok());
+    }
+    for bin_step in [0, 2, 50, 101, u16::MAX] {
+        assert!(get_dynamic_fee_parameters(bin_step).validate().is_err());
+    }
+
+    // bin_step_u128 must match bin_step
+    let params = get_dynamic_fee_parameters(BIN_STEP_BPS_DEFAULT);
+    assert_eq!(params.bin_step_u128, BIN_STEP_BPS_U128_DEFAULT);
+    for bin_step_u128 in [0, BIN_STEP_BPS_U128_DEFAULT + 1] {
+        let invalid_params = DynamicFeeParameters {
+            bin_step_u128,
+            ..params
+        };
+        assert!(invalid_params.validate().is_err());
+    }
+    let invalid_params = DynamicFeeParameters {
+        bin_step: 10,
+        ..params
+    };
+    assert!(invalid_params.validate().is_err());
+}
+
+#[test]
+fn test_dynamic_fee_parameters_layout() {
+    // bin_step_u128 stays right after bin_step, so existing clients can still encode the args
+    let params = get_dynamic_fee_parameters(BIN_STEP_BPS_DEFAULT);
+    let mut data = vec![];
+    params.serialize(&mut data).unwrap();
+    assert_eq!(data.len(), 32);
+    assert_eq!(data[0..2], BIN_STEP_BPS_DEFAULT.to_le_bytes());
+    assert_eq!(data[2..18], BIN_STEP_BPS_U128_DEFAULT.to_le_bytes());
+}
+
+#[test]
+fn test_variable_fee_does_not_overflow() {
+    for bin_step in ALLOWED_BIN_STEPS {
+        let params = get_dynamic_fee_parameters(bin_step);
+        params.validate().unwrap();
+
+        let mut dynamic_fee = PoolFeeParameters {
+            dynamic_fee: Some(params),
+            ..Default::default()
+        }
+        .to_pool_fees_struct(&ExtraFeeParameters::default())
+        .dynamic_fee;
+        assert_eq!(dynamic_fee.bin_step_u128, params.get_bin_step_u128());
+
+        dynamic_fee.volatility_accumulator = U24_MAX.into();
+        let variable_fee = dynamic_fee.get_variable_fee().unwrap();
+        assert!(variable_fee > 0);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{
+    constants::activation::{MAX_FEE_CURVE_SLOT_DURATION, MAX_HIGH_TAX_SLOT_DURATION},
+    params::{
+        fee_parameters::{
+            BaseFeeParameters, ExtraFeeParameters, FeeCurveParameters, FeeCurvePointParameters,
+            PoolFeeParameters,
+        },
+        swap::TradeDirection,
+    },
+    state::fee::FeeSchedulerMode,
+};
+
+fn get_fee_curve_parameters() -> (PoolFeeParameters, ExtraFeeParameters) {
+    let pool_fees = PoolFeeParameters {
+        base_fee: BaseFeeParameters {
+            cliff_fee_numerator: 500_000_000, // 50%
+            fee_scheduler_mode: FeeSchedulerMode::FeeCurve.into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let extra_fees = ExtraFeeParameters {
+        fee_curve: Some(FeeCurveParameters {
+            points: [
+                FeeCurvePointParameters {
+                    point_offset: 1000,
+                    fee_numerator: 50_000_000, // 5%
+                },
+                FeeCurvePointParameters {
+                    point_offset: 11000,
+                    fee_numerator: 10_000_000, // 1%
+                },
+            ],
+        }),
+        ..Default::default()
+    };
+    (pool_fees, extra_fees)
+}
+
+#[test]
+fn test_fee_curve_fee_numerator() {
+    let (params, extra_fees) = get_fee_curve_parameters();
+    let pool_fees = params.to_pool_fees_struct(&extra_fees);
+    let activation_point = 100;
+
+    let get_fee_numerator = |current_point: u64| {
+        pool_fees
+            .get_trading_fee_numerator(
+                1_000_000,
+                TradeDirection::BtoA,
+                current_point,
+                activation_point,
+            )
+            .unwrap()
+    };
+
+    // alpha vault trades with the min fee
+    assert_eq!(get_fee_numerator(0), 10_000_000);
+    assert_eq!(get_fee_numerator(100), 500_000_000);
+    // fast drop
+    assert_eq!(get_fee_numerator(600), 275_000_000);
+    assert_eq!(get_fee_numerator(1100), 50_000_000);
+    // slow tail
+    assert_eq!(get_fee_numerator(6100), 30_000_000);
+    assert_eq!(get_fee_numerator(11100), 10_000_000);
+    assert_eq!(get_fee_numerator(u64::MAX), 10_000_000);
+}
+
+#[test]
+fn test_fee_curve_single_point() {
+    let (params, mut extra_fees) = get_fee_curve_parameters();
+    extra_fees.fee_curve.as_mut().unwrap().points[1] = FeeCurvePointParameters::default();
+    params.validate(&extra_fees).unwrap();
+
+    let pool_fees = params.to_pool_fees_struct(&extra_fees);
+    let fee_numerator = pool_fees
+        .get_trading_fee_numerator(1_000_000, TradeDirection::BtoA, 2000, 0)
+        .unwrap();
+    assert_eq!(fee_numerator, 50_000_000);
+}
+
+#[test]
+fn test_fee_curve_config_round_trip() {
+    let (params, extra_fees) = get_fee_curve_parameters();
+    let config = params.to_pool_fees_config(&extra_fees);
+    let fee_curve = config.to_extra_fee_parameters().fee_curve.unwrap();
+    assert_eq!(
+        fee_curve.to_fee_curve_points(),
+        extra_fees.fee_curve.unwrap().to_fee_curve_points()
+    );
+    let pool_fees = config.to_pool_fees_struct();
+    assert_eq!(
+        pool_fees.fee_curve_points,
+        params.to_pool_fees_struct(&extra_fees).fee_curve_points
+    );
+    assert_eq!(
+        pool_fees
+            .to_extra_fee_parameters()
+            .fee_curve
+            .unwrap()
+            .to_fee_curve_points(),
+        fee_curve.to_fee_curve_points()
+    );
+
+    // other modes don't carry a fee curve
+    let params = PoolFeeParameters {
+        base_fee: BaseFeeParameters {
+            cliff_fee_numerator: 10_000_000,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert!(params
+        .to_pool_fees_config(&ExtraFeeParameters::default())
+        .to_extra_fee_parameters()
+        .fee_curve
+        .is_none());
+}
+
+#[test]
+fn test_fee_curve_validation() {
+    let (params, extra_fees) = get_fee_curve_parameters();
+    params.validate(&extra_fees).unwrap();
+    let fee_curve = extra_fees.fee_curve.unwrap();
+    fee_curve
+        .validate_duration(MAX_FEE_CURVE_SLOT_DURATION, MAX_HIGH_TAX_SLOT_DURATION)
+        .unwrap();
+
+    // mode and fee curve must match
+    assert!(params.validate(&ExtraFeeParameters::default()).is_err());
+    let mut invalid_params = params;
+    invalid_params.base_fee.fee_scheduler_mode = FeeSchedulerMode::Linear.into();
+    assert!(invalid_params.validate(&extra_fees).is_err());
+    let mut invalid_params = params;
+    invalid_params.base_fee.period_frequency = 1;
+    assert!(invalid_params.validate(&extra_fees).is_err());
+
+    let invalid_points = [
+        // no point
+        [FeeCurvePointParameters::default(); 2],
+        // used point after unused point
+        [FeeCurvePointParameters::default(), fee_curve.points[1]],
+        // unsorted points
+        [fee_curve.points[1], fee_curve.points[0]],
+        // increasing fee
+        [
+            fee_curve.points[0],
+            FeeCurvePointParameters {
+                point_offset: 11000,
+                fee_numerator: 60_000_000,
+            },
+        ],
+        // fee above cliff fee
+        [
+            FeeCurvePointParameters {
+                point_offset: 1000,
+                fee_numerator: 500_000_001,
+            },
+            FeeCurvePointParameters::default(),
+        ],
+        // fee below min fee
+        [
+            fee_curve.points[0],
+            FeeCurvePointParameters {
+                point_offset: 11000,
+                fee_numerator: 99_999,
+            },
+        ],
+    ];
+    for points in invalid_points {
+        let invalid_extra_fees = ExtraFeeParameters {
+            fee_curve: Some(FeeCurveParameters { points }),
+            ..extra_fees
+        };
+        assert!(params.validate(&invalid_extra_fees).is_err());
+    }
+
+    // high tax and fee curve duration
+    let high_tax_too_long = FeeCurveParameters {
+        points: [
+            FeeCurvePointParameters {
+                point_offset: MAX_HIGH_TAX_SLOT_DURATION as u32 + 1,
+                fee_numerator: 50_000_000,
+            },
+            fee_curve.points[1],
+        ],
+    };
+    assert!(high_tax_too_long
+        .validate_duration(MAX_FEE_CURVE_SLOT_DURATION, MAX_HIGH_TAX_SLOT_DURATION)
+        .is_err());
+    let fee_curve_too_long = FeeCurveParameters {
+        points: [
+            fee_curve.points[0],
+            FeeCurvePointParameters {
+                point_offset: MAX_FEE_CURVE_SLOT_DURATION as u32 + 1,
+                fee_numerator: 10_000_000,
+            },
+        ],
+    };
+    assert!(fee_curve_too_long
+        .validate_duration(MAX_FEE_CURVE_SLOT_DURATION, MAX_HIGH_TAX_SLOT_DURATION)
+        .is_err());
+}
@@ -0,0 +1,111 @@
+use crate::{
+    params::{
+        fee_parameters::{BaseFeeParameters, ExtraFeeParameters, PoolFeeParameters},
+        swap::TradeDirection,
+    },
+    state::fee::FeeSchedulerMode,
+};
+
+fn get_pool_fee_parameters(
+    a_to_b_fee_multiplier_bps: Option<u32>,
+) -> (PoolFeeParameters, ExtraFeeParameters) {
+    let pool_fees = PoolFeeParameters {
+        base_fee: BaseFeeParameters {
+            cliff_fee_numerator: 100_000_000, // 10%
+            number_of_period: 9,
+            period_frequency: 10,
+            reduction_factor: 10_000_000, // 1% each period
+            fee_scheduler_mode: FeeSchedulerMode::Linear.into(),
+        },
+        protocol_fee_percent: 20,
+        ..Default::default()
+    };
+    let extra_fees = ExtraFeeParameters {
+        a_to_b_fee_multiplier_bps,
+        ..Default::default()
+    };
+    (pool_fees, extra_fees)
+}
+
+fn validate(a_to_b_fee_multiplier_bps: u32) -> bool {
+    let (params, extra_fees) = get_pool_fee_parameters(Some(a_to_b_fee_multiplier_bps));
+    params.validate(&extra_fees).is_ok()
+}
+
+#[test]
+fn test_direction_fee_numerator() {
+    let (params, extra_fees) = get_pool_fee_parameters(Some(30_000));
+    let pool_fees = params.to_pool_fees_struct(&extra_fees);
+
+    for (current_point, base_fee_numerator) in
+        [(0, 100_000_000), (50, 50_000_000), (1000, 10_000_000)]
+    {
+        let b_to_a_fee_numerator = pool_fees
+            .get_trading_fee_numerator(1_000, TradeDirection::BtoA, current_point, 0)
+            .unwrap();
+        assert_eq!(b_to_a_fee_numerator, base_fee_numerator);
+
+        let a_to_b_fee_numerator = pool_fees
+            .get_trading_fee_numerator(1_000, TradeDirection::AtoB, current_point, 0)
+            .unwrap();
+        assert_eq!(a_to_b_fee_numerator, base_fee_numerator * 3);
+    }
+
+    let fee_result = pool_fees
+        .get_fee_on_amount(1_000_000, false, TradeDirection::AtoB, 1000, 0)
+        .unwrap();
+    assert_eq!(fee_result.amount, 970_000);
+}
+
+#[test]
+fn test_direction_fee_disabled() {
+    let (params, extra_fees) = get_pool_fee_parameters(None);
+    let pool_fees = params.to_pool_fees_struct(&extra_fees);
+    assert_eq!(pool_fees.a_to_b_fee_multiplier_bps, 0);
+
+    let a_to_b_fee_numerator = pool_fees
+        .get_trading_fee_numerator(1_000, TradeDirection::AtoB, 0, 0)
+        .unwrap();
+    let b_to_a_fee_numerator = pool_fees
+        .get_trading_fee_numerator(1_000, TradeDirection::BtoA, 0, 0)
+        .unwrap();
+    assert_eq!(a_to_b_fee_numerator, b_to_a_fee_numerator);
+}
+
+#[test]
+fn test_direction_fee_config_round_trip() {
+    for a_to_b_fee_multiplier_bps in [None, Some(5_000)] {
+        let (params, extra_fees) = get_pool_fee_parameters(a_to_b_fee_multiplier_bps);
+        let config = params.to_pool_fees_config(&extra_fees);
+        assert_eq!(
+            config.to_extra_fee_parameters().a_to_b_fee_multiplier_bps,
+            a_to_b_fee_multiplier_bps
+        );
+        let pool_fees = config.to_pool_fees_struct();
+        assert_eq!(
+            pool_fees.a_to_b_fee_multiplier_bps,
+            a_to_b_fee_multiplier_bps.unwrap_or_default()
+        );
+        assert_eq!(
+            pool_fees
+                .to_extra_fee_parameters()
+                .a_to_b_fee_multiplier_bps,
+            a_to_b_fee_multiplier_bps
+        );
+    }
+}
+
+#[test]
+fn test_direction_fee_validation() {
+    // 30% to 3% on a to b
+    assert!(validate(30_000));
+    // 5% to 0.5% on a to b
+    assert!(validate(5_000));
+
+    // 0 multiplier
+    assert!(!validate(0));
+    // max fee exceeds 50%
+    assert!(!validate(50_001));
+    // min fee is lower than 0.01%
+    assert!(!validate(99));
+}
@@ -1,4 +1,18 @@
-use crate::{fee_math::get_fee_in_period, state::fee::BaseFeeStruct};
+use crate::{
+    constants::{
+        fee::{FEE_DENOMINATOR, MAX_FEE_NUMERATOR},
+        BASIS_POINT_MAX, MAX_SQRT_PRICE, MIN_SQRT_PRICE,
+    },
+    fee_math::get_fee_in_period,
+    params::{
+        fee_parameters::{BaseFeeParameters, ExtraFeeParameters, PoolFeeParameters},
+        swap::TradeDirection,
+    },
+    state::{
+        fee::{BaseFeeStruct, FeeMode, FeeSchedulerMode, PoolFeesStruct},
+        CollectFeeMode, Pool,
+    },
+};
 use proptest::prelude::*;
 
 proptest! {
@@ -32,3 +46,185 @@ fn test_base_fee() {
     let current_fee = base_fee.get_current_base_fee_numerator(100, 0).unwrap();
     println!("{}", current_fee)
 }
+
+fn get_rate_limiter_base_fee(max_limiter_duration: u64) -> BaseFeeStruct {
+    BaseFeeStruct {
+        cliff_fee_numerator: 10_000_000, // 1%
+        fee_scheduler_mode: FeeSchedulerMode::RateLimiter.into(),
+        number_of_period: 10, // 0.1% fee increment
+        period_frequency: max_limiter_duration,
+        reduction_factor: 1_000_000_000, // reference amount
+        ..Default::default()
+    }
+}
+
+// charge each token with the fee numerator of its chunk
+fn get_rate_limiter_fee_brute_force(base_fee: &BaseFeeStruct, amount: u64) -> u128 {
+    let reference_amount = base_fee.reduction_factor;
+    let fee_increment_numerator =
+        u64::from(base_fee.number_of_period) * FEE_DENOMINATOR / BASIS_POINT_MAX;
+    let mut total_fee = 0u128;
+    let mut charged_amount = 0u64;
+    let mut index = 0u64;
+    while charged_amount < amount {
+        let chunk = reference_amount.min(amount - charged_amount);
+        let fee_numerator =
+            (base_fee.cliff_fee_numerator + index * fee_increment_numerator).min(MAX_FEE_NUMERATOR);
+        total_fee += u128::from(chunk) * u128::from(fee_numerator);
+        charged_amount += chunk;
+        index += 1;
+    }
+    total_fee
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 100, .. ProptestConfig::default()
+    })]
+
+    #[test]
+    fn fee_rate_limiter_matches_brute_force(
+        amount in 1..=1_000_000_000_000u64,
+    ) {
+        let base_fee = get_rate_limiter_base_fee(0);
+        let fee_numerator = base_fee.get_base_fee_numerator(amount, 100, 0)?;
+        let total_fee = get_rate_limiter_fee_brute_force(&base_fee, amount);
+        assert_eq!(u128::from(fee_numerator), total_fee.div_ceil(u128::from(amount)));
+    }
+
+    #[test]
+    fn fee_rate_limiter_inverse(
+        excluded_fee_amount in 1..=u64::MAX / 4,
+    ) {
+        let pool_fees = PoolFeesStruct {
+            base_fee: get_rate_limiter_base_fee(0),
+            protocol_fee_percent: 20,
+            ..Default::default()
+        };
+        let included = pool_fees.get_fee_on_excluded_amount(excluded_fee_amount, false, TradeDirection::BtoA, 100, 0)?;
+        let excluded = pool_fees.get_fee_on_amount(included.amount, false, TradeDirection::BtoA, 100, 0)?;
+        assert!(excluded.amount >= excluded_fee_amount);
+        // one less input must not be enough
+        let excluded = pool_fees.get_fee_on_amount(included.amount - 1, false, TradeDirection::BtoA, 100, 0)?;
+        assert!(excluded.amount < excluded_fee_amount);
+    }
+}
+
+#[test]
+fn test_fee_rate_limiter() {
+    let base_fee = get_rate_limiter_base_fee(1000);
+    let reference_amount = base_fee.reduction_factor;
+
+    // small trade pays cliff fee
+    let fee_numerator = base_fee
+        .get_base_fee_numerator(reference_amount, 100, 0)
+        .unwrap();
+    assert_eq!(fee_numerator, base_fee.cliff_fee_numerator);
+
+    // the second chunk pays 1.1%
+    let fee_numerator = base_fee
+        .get_base_fee_numerator(reference_amount * 2, 100, 0)
+        .unwrap();
+    assert_eq!(fee_numerator, 10_500_000);
+
+    // huge trade is capped at max fee
+    let fee_numerator = base_fee.get_base_fee_numerator(u64::MAX, 100, 0).unwrap();
+    assert!(fee_numerator <= MAX_FEE_NUMERATOR);
+    assert!(fee_numerator > MAX_FEE_NUMERATOR - 1_000_000);
+
+    // not applied before activation and after the limiter duration
+    for (current_point, activation_point) in [(0, 1), (1001, 0)] {
+        let fee_numerator = base_fee
+            .get_base_fee_numerator(u64::MAX, current_point, activation_point)
+            .unwrap();
+        assert_eq!(fee_numerator, base_fee.cliff_fee_numerator);
+    }
+}
+
+#[test]
+fn test_fee_rate_limiter_a_to_b_dump() {
+    let base_fee = get_rate_limiter_base_fee(1000);
+    let pool = Pool {
+        pool_fees: PoolFeesStruct {
+            base_fee,
+            ..Default::default()
+        },
+        collect_fee_mode: CollectFeeMode::OnlyB.into(),
+        liquidity: 1_000_000_000_000_000 << 64,
+        sqrt_price: 1 << 64,
+        sqrt_min_price: MIN_SQRT_PRICE,
+        sqrt_max_price: MAX_SQRT_PRICE,
+        ..Default::default()
+    };
+    let trade_direction = TradeDirection::AtoB;
+    let fee_mode = FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+    assert!(!fee_mode.fees_on_input);
+
+    // fees are collected on the token b output, which is charged per reference amount chunk like a buy
+    let amount_in = base_fee.reduction_factor * 100;
+    let get_fee_numerator = |current_point: u64| {
+        let swap_result = pool
+            .get_swap_result(amount_in, &fee_mode, trade_direction, current_point)
+            .unwrap();
+        let total_fee = swap_result.lp_fee + swap_result.protocol_fee + swap_result.partner_fee;
+        let output_amount = swap_result.output_amount + total_fee;
+        let fee_numerator = base_fee
+            .get_base_fee_numerator(output_amount, current_point, 0)
+            .unwrap();
+        assert_eq!(
+            u128::from(total_fee),
+            (u128::from(output_amount) * u128::from(fee_numerator))
+                .div_ceil(u128::from(FEE_DENOMINATOR))
+        );
+        fee_numerator
+    };
+    assert!(get_fee_numerator(100) > base_fee.cliff_fee_numerator * 5);
+    assert_eq!(get_fee_numerator(1001), base_fee.cliff_fee_numerator);
+}
+
+#[test]
+fn test_fee_rate_limiter_validation() {
+    let base_fee = BaseFeeParameters {
+        cliff_fee_numerator: 10_000_000,
+        number_of_period: 10,
+        period_frequency: 1000,
+        reduction_factor: 1_000_000_000,
+        fee_scheduler_mode: FeeSchedulerMode::RateLimiter.into(),
+    };
+    let pool_fees = PoolFeeParameters {
+        base_fee,
+        ..Default::default()
+    };
+    assert!(pool_fees.validate(&ExtraFeeParameters::default()).is_ok());
+    assert!(pool_fees
+        .validate_collect_fee_mode(CollectFeeMode::OnlyB.into())
+        .is_ok());
+    assert!(pool_fees
+        .validate_collect_fee_mode(CollectFeeMode::BothToken.into())
+        .is_err());
+
+    for invalid_base_fee in [
+        BaseFeeParameters {
+            number_of_period: 0,
+            ..base_fee
+        },
+        BaseFeeParameters {
+            number_of_period: 10_001,
+            ..base_fee
+        },
+        BaseFeeParameters {
+            reduction_factor: 0,
+            ..base_fee
+        },
+        BaseFeeParameters {
+            cliff_fee_numerator: MAX_FEE_NUMERATOR + 1,
+            ..base_fee
+        },
+    ] {
+        let pool_fees = PoolFeeParameters {
+            base_fee: invalid_base_fee,
+            ..Default::default()
+        };
+        assert!(pool_fees.validate(&ExtraFeeParameters::default()).is_err());
+    }
+}
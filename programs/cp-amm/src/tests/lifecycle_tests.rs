@@ -0,0 +1,296 @@
+//! Multi-step scenarios run against the pool and position state transitions used by the
+//! instruction handlers. Account validation, token transfers and events are covered by the
+//! integration tests under `programs/cp-amm/tests/integration` and the bankrun suite under `tests/`.
+use std::cell::RefCell;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFee;
+
+use crate::{
+    constants::{MAX_SQRT_PRICE, MIN_SQRT_PRICE},
+    params::swap::TradeDirection,
+    state::{
+        fee::{BaseFeeStruct, FeeMode, PoolFeesStruct},
+        CollectFeeMode, ModifyLiquidityResult, Pool, Position, Vesting,
+    },
+    token::{
+        calculate_transfer_fee_excluded_amount_with_epoch_fee,
+        calculate_transfer_fee_included_amount_with_epoch_fee,
+    },
+    u128x128_math::Rounding,
+};
+
+const LIQUIDITY: u128 = 1_000_000_000u128 << 64;
+
+fn new_pool(collect_fee_mode: CollectFeeMode) -> Pool {
+    let pool_fees = PoolFeesStruct {
+        base_fee: BaseFeeStruct {
+            cliff_fee_numerator: 10_000_000, // 1%
+            ..Default::default()
+        },
+        protocol_fee_percent: 20,
+        partner_fee_percent: 50,
+        ..Default::default()
+    };
+    Pool {
+        pool_fees,
+        sqrt_price: 1u128 << 64,
+        sqrt_min_price: MIN_SQRT_PRICE,
+        sqrt_max_price: MAX_SQRT_PRICE,
+        collect_fee_mode: collect_fee_mode.into(),
+        ..Default::default()
+    }
+}
+
+fn new_transfer_fee(transfer_fee_basis_points: u16) -> TransferFee {
+    TransferFee {
+        epoch: 0.into(),
+        maximum_fee: u64::MAX.into(),
+        transfer_fee_basis_points: transfer_fee_basis_points.into(),
+    }
+}
+
+#[test]
+fn test_lifecycle_swap_with_transfer_fee() {
+    let mut pool = new_pool(CollectFeeMode::BothToken);
+    let mut position = Position::default();
+    pool.apply_add_liquidity(&mut position, LIQUIDITY).unwrap();
+
+    // 1% transfer fee on token a, 0.5% on token b
+    let token_a_transfer_fee = new_transfer_fee(100);
+    let token_b_transfer_fee = new_transfer_fee(50);
+    let trade_direction = TradeDirection::AtoB;
+    let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+
+    // the pool receives token a without the token a transfer fee
+    let amount_in = 1_000_000;
+    let transfer_fee_excluded_amount_in = calculate_transfer_fee_excluded_amount_with_epoch_fee(
+        Some(&token_a_transfer_fee),
+        amount_in,
+    )
+    .unwrap();
+    assert_eq!(transfer_fee_excluded_amount_in.amount, 990_000);
+
+    let swap_result = pool
+        .get_swap_result(
+            transfer_fee_excluded_amount_in.amount,
+            fee_mode,
+            trade_direction,
+            0,
+        )
+        .unwrap();
+    pool.apply_swap_result(&swap_result, fee_mode, None, 0)
+        .unwrap();
+
+    // price 1 with a 1% trading fee on the output
+    assert!(swap_result.output_amount < 990_000 * 99 / 100);
+    assert!(pool.sqrt_price < 1u128 << 64);
+
+    // the pool sends token b out and the user receives it without the token b transfer fee
+    let transfer_fee_excluded_amount_out = calculate_transfer_fee_excluded_amount_with_epoch_fee(
+        Some(&token_b_transfer_fee),
+        swap_result.output_amount,
+    )
+    .unwrap();
+    assert_eq!(
+        transfer_fee_excluded_amount_out.transfer_fee,
+        swap_result.output_amount.div_ceil(200)
+    );
+    assert_eq!(
+        transfer_fee_excluded_amount_out.amount + transfer_fee_excluded_amount_out.transfer_fee,
+        swap_result.output_amount
+    );
+
+    // quoting the other way round, the user must send the token a transfer fee on top
+    let transfer_fee_included_amount_in = calculate_transfer_fee_included_amount_with_epoch_fee(
+        Some(&token_a_transfer_fee),
+        transfer_fee_excluded_amount_in.amount,
+    )
+    .unwrap();
+    assert_eq!(transfer_fee_included_amount_in.amount, amount_in);
+}
+
+#[test]
+fn test_lifecycle_claim_position_fee() {
+    let mut pool = new_pool(CollectFeeMode::OnlyB);
+    let mut position_0 = Position::default();
+    let mut position_1 = Position::default();
+    pool.apply_add_liquidity(&mut position_0, LIQUIDITY)
+        .unwrap();
+    pool.apply_add_liquidity(&mut position_1, LIQUIDITY * 3)
+        .unwrap();
+
+    let mut total_lp_fee = 0;
+    for trade_direction in [TradeDirection::AtoB, TradeDirection::BtoA] {
+        let fee_mode =
+            &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+        let swap_result = pool
+            .get_swap_result(1_000_000, fee_mode, trade_direction, 0)
+            .unwrap();
+        pool.apply_swap_result(&swap_result, fee_mode, None, 0)
+            .unwrap();
+        total_lp_fee += swap_result.lp_fee;
+    }
+
+    position_0
+        .update_fee(pool.fee_a_per_liquidity(), pool.fee_b_per_liquidity())
+        .unwrap();
+    position_1
+        .update_fee(pool.fee_a_per_liquidity(), pool.fee_b_per_liquidity())
+        .unwrap();
+
+    // fees are only collected in token b and shared pro rata
+    assert_eq!(position_0.fee_a_pending, 0);
+    assert_eq!(position_1.fee_a_pending, 0);
+    assert!(position_0.fee_b_pending + position_1.fee_b_pending <= total_lp_fee);
+    assert!(position_1.fee_b_pending >= position_0.fee_b_pending * 3);
+
+    position_0.reset_pending_fee();
+    assert_eq!(position_0.fee_b_pending, 0);
+}
+
+#[test]
+fn test_lifecycle_claim_fee_of_many_positions() {
+    let mut pool = new_pool(CollectFeeMode::BothToken);
+    let mut positions = [Position::default(), Position::default()];
+    for position in positions.iter_mut() {
+        pool.apply_add_liquidity(position, LIQUIDITY).unwrap();
+    }
+
+    let mut total_lp_fee = 0;
+    for trade_direction in [TradeDirection::AtoB, TradeDirection::BtoA] {
+        let fee_mode =
+            &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+        let swap_result = pool
+            .get_swap_result(1_000_000, fee_mode, trade_direction, 0)
+            .unwrap();
+        pool.apply_swap_result(&swap_result, fee_mode, None, 0)
+            .unwrap();
+        total_lp_fee += swap_result.lp_fee;
+    }
+
+    let mut total_fee_claimed = 0;
+    for position in positions.iter_mut() {
+        let (fee_a, fee_b) = position
+            .claim_fee(pool.fee_a_per_liquidity(), pool.fee_b_per_liquidity())
+            .unwrap();
+        assert!(fee_a > 0 && fee_b > 0);
+        assert_eq!(position.metrics.total_claimed_a_fee, fee_a);
+        assert_eq!(position.metrics.total_claimed_b_fee, fee_b);
+        assert_eq!((position.fee_a_pending, position.fee_b_pending), (0, 0));
+        total_fee_claimed += fee_a + fee_b;
+
+        // nothing left to claim at the same checkpoint
+        let claimed_again = position
+            .claim_fee(pool.fee_a_per_liquidity(), pool.fee_b_per_liquidity())
+            .unwrap();
+        assert_eq!(claimed_again, (0, 0));
+    }
+    assert!(total_fee_claimed <= total_lp_fee);
+}
+
+#[test]
+fn test_lifecycle_rewards() {
+    let reward_duration = 1_000;
+    let funding_amount = 1_000_000;
+
+    let mut pool = new_pool(CollectFeeMode::BothToken);
+    pool.reward_infos[0].init_reward(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        reward_duration,
+        0,
+    );
+    pool.reward_infos[0]
+        .update_rate_after_funding(0, funding_amount, None)
+        .unwrap();
+
+    let pool = RefCell::new(pool);
+    let mut position_0 = Position::default();
+    let mut position_1 = Position::default();
+    {
+        let mut pool = pool.borrow_mut();
+        position_0.update_rewards(&mut pool, 0).unwrap();
+        pool.apply_add_liquidity(&mut position_0, LIQUIDITY)
+            .unwrap();
+    }
+    {
+        // second position joins half way and only earns from then on
+        let mut pool = pool.borrow_mut();
+        position_1.update_rewards(&mut pool, 500).unwrap();
+        pool.apply_add_liquidity(&mut position_1, LIQUIDITY)
+            .unwrap();
+    }
+    {
+        let mut pool = pool.borrow_mut();
+        position_0.update_rewards(&mut pool, 2_000).unwrap();
+        position_1.update_rewards(&mut pool, 2_000).unwrap();
+    }
+
+    let reward_0 = position_0.claim_reward(0).unwrap();
+    let reward_1 = position_1.claim_reward(0).unwrap();
+    assert!(reward_0 + reward_1 <= funding_amount);
+    // 3/4 of the reward goes to the first position
+    assert!(reward_0 >= funding_amount * 3 / 4 - 1);
+    assert!(reward_1 >= funding_amount / 4 - 1);
+    assert_eq!(position_0.reward_infos[0].reward_pendings, 0);
+    assert_eq!(position_0.reward_infos[0].total_claimed_rewards, reward_0);
+}
+
+#[test]
+fn test_lifecycle_vesting() {
+    let mut pool = new_pool(CollectFeeMode::BothToken);
+    let mut position = Position::default();
+    pool.apply_add_liquidity(&mut position, LIQUIDITY).unwrap();
+
+    let cliff_unlock_liquidity = LIQUIDITY / 2;
+    let liquidity_per_period = LIQUIDITY / 10;
+    let mut vesting = Vesting::default();
+    vesting.initialize(
+        Pubkey::new_unique(),
+        100,
+        10,
+        cliff_unlock_liquidity,
+        liquidity_per_period,
+        5,
+    );
+    position
+        .lock(vesting.get_total_lock_amount().unwrap())
+        .unwrap();
+    assert_eq!(position.unlocked_liquidity, 0);
+    assert_eq!(position.vested_liquidity, LIQUIDITY);
+
+    // nothing to release before the cliff
+    assert_eq!(vesting.get_new_release_liquidity(99).unwrap(), 0);
+
+    for (current_point, expected_unlocked_liquidity) in [
+        (100, cliff_unlock_liquidity),
+        (125, cliff_unlock_liquidity + liquidity_per_period * 2),
+        (1_000, LIQUIDITY),
+    ] {
+        let released_liquidity = vesting.get_new_release_liquidity(current_point).unwrap();
+        position
+            .release_vested_liquidity(released_liquidity)
+            .unwrap();
+        vesting
+            .accumulate_released_liquidity(released_liquidity)
+            .unwrap();
+        assert_eq!(position.unlocked_liquidity, expected_unlocked_liquidity);
+    }
+    assert!(vesting.done().unwrap());
+    assert_eq!(position.vested_liquidity, 0);
+
+    // released liquidity can be withdrawn
+    let unlocked_liquidity = position.unlocked_liquidity;
+    let ModifyLiquidityResult {
+        token_a_amount,
+        token_b_amount,
+    } = pool
+        .get_amounts_for_modify_liquidity(unlocked_liquidity, Rounding::Down)
+        .unwrap();
+    pool.apply_remove_liquidity(&mut position, unlocked_liquidity)
+        .unwrap();
+    assert!(token_a_amount > 0 && token_b_amount > 0);
+    assert_eq!(pool.liquidity, 0);
+}
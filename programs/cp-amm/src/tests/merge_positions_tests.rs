@@ -0,0 +1,47 @@
+use crate::{state::Position, PoolError};
+
+fn new_position(liquidity: u128, fee_pending: u64, reward_pendings: u64) -> Position {
+    let mut position = Position {
+        unlocked_liquidity: liquidity,
+        permanent_locked_liquidity: liquidity,
+        fee_a_pending: fee_pending,
+        fee_b_pending: fee_pending,
+        ..Default::default()
+    };
+    position.metrics.total_claimed_a_fee = fee_pending;
+    position.reward_infos[0].reward_pendings = reward_pendings;
+    position.reward_infos[1].total_claimed_rewards = reward_pendings;
+    position
+}
+
+#[test]
+fn test_merge_positions() {
+    let mut position = new_position(1_000, 10, 100);
+    let mut source_position = new_position(500, 5, 50);
+
+    position.merge(&mut source_position).unwrap();
+
+    assert_eq!(position.unlocked_liquidity, 1_500);
+    assert_eq!(position.permanent_locked_liquidity, 1_500);
+    assert_eq!(position.fee_a_pending, 15);
+    assert_eq!(position.fee_b_pending, 15);
+    assert_eq!(position.metrics.total_claimed_a_fee, 15);
+    assert_eq!(position.reward_infos[0].reward_pendings, 150);
+    assert_eq!(position.reward_infos[1].total_claimed_rewards, 150);
+
+    assert_eq!(source_position.unlocked_liquidity, 0);
+    assert_eq!(source_position.permanent_locked_liquidity, 0);
+    assert_eq!(source_position.fee_a_pending, 0);
+    assert_eq!(source_position.fee_b_pending, 0);
+    assert_eq!(source_position.reward_infos[0].reward_pendings, 0);
+}
+
+#[test]
+fn test_merge_positions_with_vested_liquidity() {
+    let mut position = new_position(1_000, 0, 0);
+    let mut source_position = new_position(500, 0, 0);
+    source_position.vested_liquidity = 1;
+
+    let err = position.merge(&mut source_position).unwrap_err();
+    assert_eq!(err, PoolError::PositionHasVestedLiquidity.into());
+}
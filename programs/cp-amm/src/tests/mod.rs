@@ -27,3 +27,42 @@ mod fee_scheduler_tests;
 
 #[cfg(test)]
 mod test_volatility_accumulate;
+
+#[cfg(test)]
+mod swap_exact_out_tests;
+
+#[cfg(test)]
+mod swap_with_price_limit_tests;
+
+#[cfg(test)]
+mod zap_in_tests;
+
+#[cfg(test)]
+mod split_position_tests;
+
+#[cfg(test)]
+mod merge_positions_tests;
+
+#[cfg(test)]
+mod lifecycle_tests;
+
+#[cfg(test)]
+mod fee_curve_tests;
+
+#[cfg(test)]
+mod fee_direction_tests;
+
+#[cfg(test)]
+mod update_pool_fees_tests;
+
+#[cfg(test)]
+mod protocol_fee_tests;
+
+#[cfg(test)]
+mod referral_fee_tests;
+
+#[cfg(test)]
+mod oracle_tests;
+
+#[cfg(test)]
+mod reward_extension_tests;
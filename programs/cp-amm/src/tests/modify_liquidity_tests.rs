@@ -43,4 +43,39 @@ proptest! {
         assert!(result_0.token_a_amount >= result_1.token_a_amount);
         assert!(result_0.token_b_amount >= result_1.token_b_amount);
     }
+
+    #[test]
+    fn test_liquidity_delta_from_amounts_fits_amounts(
+        sqrt_price in MIN_SQRT_PRICE..=MAX_SQRT_PRICE,
+        token_a_amount in 0..=u64::MAX,
+        token_b_amount in 0..=u64::MAX,
+    ) {
+        let pool = Pool {
+            sqrt_price,
+            sqrt_min_price: MIN_SQRT_PRICE,
+            sqrt_max_price: MAX_SQRT_PRICE,
+            ..Default::default()
+        };
+
+        let liquidity_delta = pool
+            .get_liquidity_delta_from_amounts(token_a_amount, token_b_amount)
+            .unwrap();
+
+        if let Ok(result) = pool.get_amounts_for_modify_liquidity(liquidity_delta, Rounding::Up) {
+            assert!(result.token_a_amount <= token_a_amount);
+            assert!(result.token_b_amount <= token_b_amount);
+        }
+
+        // one more unit of liquidity doesn't fit anymore
+        if liquidity_delta < u128::MAX {
+            if let Ok(result) =
+                pool.get_amounts_for_modify_liquidity(liquidity_delta + 1, Rounding::Down)
+            {
+                assert!(
+                    result.token_a_amount >= token_a_amount.saturating_sub(1)
+                        || result.token_b_amount >= token_b_amount.saturating_sub(1)
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,177 @@
+use anchor_lang::prelude::Pubkey;
+
+use crate::{
+    constants::{
+        MAX_SQRT_PRICE, MIN_SQRT_PRICE, NUM_ORACLE_OBSERVATIONS, ORACLE_OBSERVATION_INTERVAL,
+    },
+    params::swap::TradeDirection,
+    state::{fee::FeeMode, Observation, Oracle, Pool},
+};
+
+fn new_oracle(current_timestamp: u64) -> Oracle {
+    let mut oracle = Oracle {
+        pool: Pubkey::default(),
+        observation_index: 0,
+        observation_count: 0,
+        _padding: [0; 4],
+        observations: [Observation::default(); NUM_ORACLE_OBSERVATIONS],
+    };
+    oracle.initialize(Pubkey::new_unique(), current_timestamp);
+    oracle
+}
+
+#[test]
+fn test_oracle_twap_constant_price() {
+    let sqrt_price = 1u128 << 64;
+    let mut oracle = new_oracle(1_000);
+
+    oracle.update(sqrt_price, 1_100).unwrap();
+    oracle.update(sqrt_price, 1_200).unwrap();
+
+    assert_eq!(oracle.get_twap(100, sqrt_price, 1_300).unwrap(), sqrt_price);
+    assert_eq!(oracle.get_twap(300, sqrt_price, 1_300).unwrap(), sqrt_price);
+    // older than the first observation
+    assert!(oracle.get_twap(301, sqrt_price, 1_300).is_err());
+    assert!(oracle.get_twap(0, sqrt_price, 1_300).is_err());
+}
+
+#[test]
+fn test_oracle_twap_weighted_by_time() {
+    let mut oracle = new_oracle(0);
+
+    // price 100 held for 100 seconds, then 400 for 300 seconds
+    oracle.update(100, 100).unwrap();
+    oracle.update(400, 400).unwrap();
+
+    assert_eq!(oracle.get_twap(400, 400, 400).unwrap(), 325);
+    // window starts at the latest observation at or before 400 - 300
+    assert_eq!(oracle.get_twap(300, 400, 400).unwrap(), 400);
+    // current price is held since the latest observation
+    assert_eq!(oracle.get_twap(200, 1_000, 600).unwrap(), 1_000);
+    assert_eq!(oracle.get_twap(300, 1_000, 600).unwrap(), 640);
+}
+
+#[test]
+fn test_oracle_observation_interval() {
+    let mut oracle = new_oracle(0);
+    let interval = ORACLE_OBSERVATION_INTERVAL;
+
+    oracle.update(100, interval).unwrap();
+    oracle.update(100, interval + 1).unwrap();
+    assert_eq!(oracle.observation_count, 3);
+
+    // within interval of the previous observation, the latest one is updated
+    oracle.update(200, interval + 2).unwrap();
+    assert_eq!(oracle.observation_count, 3);
+    assert_eq!(oracle.observation_index, 2);
+    assert_eq!(oracle.observations[2].timestamp, interval + 2);
+    assert_eq!(
+        oracle.observations[2].sqrt_price_cumulative,
+        100 * (interval as u128 + 1) + 200
+    );
+
+    // same timestamp is a no-op
+    oracle.update(300, interval + 2).unwrap();
+    assert_eq!(
+        oracle.observations[2].sqrt_price_cumulative,
+        100 * (interval as u128 + 1) + 200
+    );
+
+    oracle.update(300, 2 * interval + 1).unwrap();
+    assert_eq!(oracle.observation_count, 4);
+}
+
+#[test]
+fn test_oracle_ring_buffer_wraps() {
+    let mut oracle = new_oracle(0);
+    let num_observations = NUM_ORACLE_OBSERVATIONS as u64;
+
+    for i in 1..=num_observations + 10 {
+        oracle
+            .update(1_000, i * ORACLE_OBSERVATION_INTERVAL)
+            .unwrap();
+    }
+
+    assert_eq!(oracle.observation_count, num_observations);
+    assert_eq!(oracle.observation_index, 10);
+
+    let current_timestamp = (num_observations + 10) * ORACLE_OBSERVATION_INTERVAL;
+    let oldest_window = (num_observations - 1) * ORACLE_OBSERVATION_INTERVAL;
+    assert_eq!(
+        oracle
+            .get_twap(oldest_window, 1_000, current_timestamp)
+            .unwrap(),
+        1_000
+    );
+    assert!(oracle
+        .get_twap(oldest_window + 1, 1_000, current_timestamp)
+        .is_err());
+}
+
+#[test]
+fn test_oracle_cumulative_wraps_around() {
+    let mut oracle = new_oracle(0);
+    oracle.observations[0].sqrt_price_cumulative = u128::MAX - 50;
+
+    oracle.update(1, 100).unwrap();
+    assert_eq!(oracle.observations[1].sqrt_price_cumulative, 49);
+    assert_eq!(oracle.get_twap(100, 1, 100).unwrap(), 1);
+}
+
+#[test]
+fn test_oracle_twap_exact_when_product_wraps() {
+    let mut oracle = new_oracle(0);
+    oracle.observations[0].sqrt_price_cumulative = u128::MAX - 1_000;
+
+    // MAX_SQRT_PRICE * elapsed wraps the cumulative, the difference over the window is still exact
+    let elapsed = 1 << 30;
+    oracle.update(MAX_SQRT_PRICE, elapsed).unwrap();
+    assert!(oracle.observations[1].sqrt_price_cumulative < u128::MAX - 1_000);
+    assert_eq!(
+        oracle.get_twap(elapsed, MAX_SQRT_PRICE, elapsed).unwrap(),
+        MAX_SQRT_PRICE
+    );
+    assert_eq!(
+        oracle
+            .get_twap(2 * elapsed, MIN_SQRT_PRICE, 2 * elapsed)
+            .unwrap(),
+        (MAX_SQRT_PRICE + MIN_SQRT_PRICE) / 2
+    );
+}
+
+#[test]
+fn test_apply_swap_result_updates_oracle() {
+    let mut pool = Pool {
+        liquidity: 1_000_000 << 64,
+        sqrt_price: 1 << 64,
+        sqrt_min_price: MIN_SQRT_PRICE,
+        sqrt_max_price: MAX_SQRT_PRICE,
+        ..Default::default()
+    };
+    let mut oracle = new_oracle(0);
+    let fee_mode =
+        &FeeMode::get_fee_mode(pool.collect_fee_mode, TradeDirection::AtoB, false).unwrap();
+
+    // the price held before the swap is recorded
+    let old_sqrt_price = pool.sqrt_price;
+    let swap_result = pool
+        .get_swap_result(1_000_000, fee_mode, TradeDirection::AtoB, 0)
+        .unwrap();
+    pool.apply_swap_result(&swap_result, fee_mode, Some(&mut oracle), 100)
+        .unwrap();
+    assert_eq!(oracle.observation_count, 2);
+    assert_eq!(
+        oracle.observations[1].sqrt_price_cumulative,
+        old_sqrt_price * 100
+    );
+    assert_eq!(pool.sqrt_price, swap_result.next_sqrt_price);
+
+    // swaps without the oracle leave it as is
+    let swap_result = pool
+        .get_swap_result(1_000_000, fee_mode, TradeDirection::AtoB, 0)
+        .unwrap();
+    pool.apply_swap_result(&swap_result, fee_mode, None, 200)
+        .unwrap();
+    assert_eq!(oracle.observation_count, 2);
+    assert_eq!(oracle.observations[1].timestamp, 100);
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::Pubkey;
+
+use crate::{state::Pool, treasury};
+
+#[test]
+fn test_protocol_fee_receiver_fallback_to_treasury() {
+    let mut pool = Pool::default();
+    assert_eq!(pool.get_protocol_fee_receiver(), treasury::ID);
+
+    let protocol_fee_receiver = Pubkey::new_unique();
+    pool.protocol_fee_receiver = protocol_fee_receiver;
+    assert_eq!(pool.get_protocol_fee_receiver(), protocol_fee_receiver);
+}
+
+#[test]
+fn test_claim_protocol_fee_with_max_amounts() {
+    let mut pool = Pool {
+        protocol_a_fee: 1_000,
+        protocol_b_fee: 2_000,
+        ..Default::default()
+    };
+
+    // only claim token b
+    let (token_a_amount, token_b_amount) = pool.claim_protocol_fee(0, 500).unwrap();
+    assert_eq!((token_a_amount, token_b_amount), (0, 500));
+    assert_eq!((pool.protocol_a_fee, pool.protocol_b_fee), (1_000, 1_500));
+
+    let (token_a_amount, token_b_amount) = pool.claim_protocol_fee(u64::MAX, u64::MAX).unwrap();
+    assert_eq!((token_a_amount, token_b_amount), (1_000, 1_500));
+    assert_eq!((pool.protocol_a_fee, pool.protocol_b_fee), (0, 0));
+}
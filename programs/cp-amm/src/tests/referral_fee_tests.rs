@@ -0,0 +1,65 @@
+use anchor_lang::prelude::Pubkey;
+
+use crate::state::{Pool, ReferralAccount};
+
+#[test]
+fn test_referral_fee_accrual_and_claim() {
+    let mut pool = Pool::default();
+    let mut referral_account = ReferralAccount::default();
+
+    referral_account.accumulate_fee(700).unwrap();
+    pool.accumulate_referral_fee(700, true).unwrap();
+    referral_account.accumulate_fee(300).unwrap();
+    pool.accumulate_referral_fee(300, true).unwrap();
+
+    assert_eq!(referral_account.pending_fee, 1_000);
+    assert_eq!(referral_account.total_fee, 1_000);
+    assert_eq!((pool.referral_a_fee, pool.referral_b_fee), (1_000, 0));
+
+    let amount = referral_account.claim_fee(400).unwrap();
+    pool.claim_referral_fee(amount, true).unwrap();
+    assert_eq!(amount, 400);
+    assert_eq!(referral_account.pending_fee, 600);
+    assert_eq!(referral_account.total_fee, 1_000);
+    assert_eq!(pool.referral_a_fee, 600);
+
+    // claim is capped by the fee accrued to the referral account
+    let amount = referral_account.claim_fee(u64::MAX).unwrap();
+    pool.claim_referral_fee(amount, true).unwrap();
+    assert_eq!(amount, 600);
+    assert_eq!(referral_account.pending_fee, 0);
+    assert_eq!(pool.referral_a_fee, 0);
+}
+
+#[test]
+fn test_referral_fee_is_tracked_per_mint() {
+    let mut pools = [Pool::default(), Pool::default()];
+    let mut referral_account = ReferralAccount::default();
+    referral_account
+        .initialize(Pubkey::new_unique(), Pubkey::new_unique())
+        .unwrap();
+
+    // one referral account accrues the fee of the mint in every pool
+    referral_account.accumulate_fee(1_000).unwrap();
+    pools[0].accumulate_referral_fee(1_000, false).unwrap();
+    referral_account.accumulate_fee(200).unwrap();
+    pools[1].accumulate_referral_fee(200, false).unwrap();
+    assert_eq!(referral_account.pending_fee, 1_200);
+
+    // a claim is capped by the referral fee held in the vault of the pool it is claimed from
+    let amount = referral_account
+        .claim_fee(pools[1].get_referral_fee(false))
+        .unwrap();
+    pools[1].claim_referral_fee(amount, false).unwrap();
+    assert_eq!(amount, 200);
+    assert_eq!(referral_account.pending_fee, 1_000);
+    assert!(pools[1].claim_referral_fee(1, false).is_err());
+
+    let amount = referral_account
+        .claim_fee(pools[0].get_referral_fee(false))
+        .unwrap();
+    pools[0].claim_referral_fee(amount, false).unwrap();
+    assert_eq!(amount, 1_000);
+    assert_eq!(referral_account.pending_fee, 0);
+    assert_eq!(pools[0].referral_b_fee, 0);
+}
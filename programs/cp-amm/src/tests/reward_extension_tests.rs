@@ -0,0 +1,195 @@
+use anchor_lang::prelude::Pubkey;
+
+use crate::{
+    constants::NUM_REWARDS,
+    state::{
+        get_extension_reward_index, get_reward_info, update_position_reward_extension,
+        update_reward_extension_liquidity, Pool, Position, PositionRewardExtension,
+        RewardExtension,
+    },
+    PoolError,
+};
+
+const LIQUIDITY: u128 = 1_000_000_000u128 << 64;
+
+fn new_reward_extension(reward_duration: u64, funding_amount: u64) -> RewardExtension {
+    let mut reward_extension = RewardExtension::default();
+    let reward_info = &mut reward_extension.reward_infos[0];
+    reward_info.init_reward(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        reward_duration,
+        0,
+    );
+    reward_info
+        .update_rate_after_funding(0, funding_amount, None)
+        .unwrap();
+    reward_extension
+}
+
+#[test]
+fn test_extension_reward_index() {
+    assert_eq!(get_extension_reward_index(0), None);
+    assert_eq!(get_extension_reward_index(NUM_REWARDS - 1), None);
+    assert_eq!(get_extension_reward_index(NUM_REWARDS), Some(0));
+    assert_eq!(get_extension_reward_index(NUM_REWARDS + 1), Some(1));
+    assert_eq!(get_extension_reward_index(NUM_REWARDS + 2), None);
+
+    let pool = Pool::default();
+    let reward_extension = new_reward_extension(1_000, 1_000_000);
+
+    // extension reward indexes need the reward extension
+    let err = get_reward_info(&pool, None, NUM_REWARDS).unwrap_err();
+    assert_eq!(err, PoolError::InvalidRewardIndex.into());
+
+    let reward_info = get_reward_info(&pool, Some(&reward_extension), NUM_REWARDS).unwrap();
+    assert!(reward_info.initialized());
+    let reward_info = get_reward_info(&pool, Some(&reward_extension), 0).unwrap();
+    assert!(!reward_info.initialized());
+
+    let err = get_reward_info(&pool, Some(&reward_extension), NUM_REWARDS + 2).unwrap_err();
+    assert_eq!(err, PoolError::InvalidRewardIndex.into());
+}
+
+#[test]
+fn test_position_reward_extension_rewards() {
+    let funding_amount = 1_000_000;
+    let mut reward_extension = new_reward_extension(1_000, funding_amount);
+    let mut pool = Pool::default();
+
+    // first position has a position reward extension from the start
+    let mut position_0 = Position {
+        reward_extension_flag: 1,
+        ..Default::default()
+    };
+    let mut position_reward_extension_0 = PositionRewardExtension::default();
+    pool.apply_add_liquidity(&mut position_0, LIQUIDITY)
+        .unwrap();
+    update_reward_extension_liquidity(&position_0, Some(&mut reward_extension), 0).unwrap();
+
+    // positions without a position reward extension don't share the extension rewards
+    let mut position_2 = Position::default();
+    pool.apply_add_liquidity(&mut position_2, LIQUIDITY)
+        .unwrap();
+    update_reward_extension_liquidity(&position_2, None, 0).unwrap();
+    assert_eq!(reward_extension.liquidity, LIQUIDITY);
+
+    // second position joins half way and only earns from then on
+    let mut position_1 = Position {
+        reward_extension_flag: 1,
+        ..Default::default()
+    };
+    let mut position_reward_extension_1 = PositionRewardExtension::default();
+    update_position_reward_extension(
+        &position_1,
+        Some(&mut reward_extension),
+        Some(&mut position_reward_extension_1),
+        500,
+    )
+    .unwrap();
+    pool.apply_add_liquidity(&mut position_1, LIQUIDITY)
+        .unwrap();
+    update_reward_extension_liquidity(&position_1, Some(&mut reward_extension), 0).unwrap();
+    assert_eq!(reward_extension.liquidity, 2 * LIQUIDITY);
+
+    for (position, position_reward_extension) in [
+        (&position_0, &mut position_reward_extension_0),
+        (&position_1, &mut position_reward_extension_1),
+    ] {
+        update_position_reward_extension(
+            position,
+            Some(&mut reward_extension),
+            Some(position_reward_extension),
+            2_000,
+        )
+        .unwrap();
+    }
+
+    let reward_0 = position_reward_extension_0.reward_infos[0].claim_reward();
+    let reward_1 = position_reward_extension_1.reward_infos[0].claim_reward();
+    assert!(reward_0 + reward_1 <= funding_amount);
+    // 3/4 of the reward goes to the first position
+    assert!(reward_0 >= funding_amount * 3 / 4 - 1);
+    assert!(reward_1 >= funding_amount / 4 - 1);
+    assert_eq!(
+        position_reward_extension_0.reward_infos[0].reward_pendings,
+        0
+    );
+    assert_eq!(
+        position_reward_extension_0.reward_infos[0].total_claimed_rewards,
+        reward_0
+    );
+    // base rewards are untouched
+    assert_eq!(position_0.reward_infos[0].reward_pendings, 0);
+}
+
+#[test]
+fn test_position_without_reward_extension() {
+    let mut reward_extension = new_reward_extension(1_000, 1_000_000);
+    let mut pool = Pool::default();
+    let mut position = Position::default();
+
+    // nothing to update without a position reward extension, with or without the reward extension
+    update_position_reward_extension(&position, None, None, 500).unwrap();
+    update_position_reward_extension(&position, Some(&mut reward_extension), None, 500).unwrap();
+    pool.apply_add_liquidity(&mut position, LIQUIDITY).unwrap();
+    update_reward_extension_liquidity(&position, Some(&mut reward_extension), 0).unwrap();
+    assert_eq!(reward_extension.liquidity, 0);
+
+    // once it has one, the reward extension must follow its liquidity
+    position.reward_extension_flag = 1;
+    let err = update_position_reward_extension(
+        &position,
+        None,
+        Some(&mut PositionRewardExtension::default()),
+        500,
+    )
+    .unwrap_err();
+    assert_eq!(err, PoolError::InvalidRewardExtension.into());
+    let err = update_reward_extension_liquidity(&position, None, 0).unwrap_err();
+    assert_eq!(err, PoolError::InvalidRewardExtension.into());
+}
+
+#[test]
+fn test_position_reward_extension_reset_checkpoints() {
+    let mut reward_extension = new_reward_extension(1_000, 1_000_000);
+    reward_extension.liquidity = LIQUIDITY;
+    reward_extension.update_rewards(500).unwrap();
+
+    let mut position_reward_extension = PositionRewardExtension::default();
+    position_reward_extension.reset_checkpoints(&reward_extension);
+    position_reward_extension
+        .update_rewards(&reward_extension, LIQUIDITY)
+        .unwrap();
+
+    assert_eq!(position_reward_extension.reward_infos[0].reward_pendings, 0);
+}
+
+#[test]
+fn test_position_reward_extension_merge() {
+    let mut position_reward_extension = PositionRewardExtension::default();
+    position_reward_extension.reward_infos[0].reward_pendings = 100;
+    position_reward_extension.reward_infos[1].total_claimed_rewards = 100;
+
+    let mut source_position_reward_extension = PositionRewardExtension::default();
+    source_position_reward_extension.reward_infos[0].reward_pendings = 50;
+    source_position_reward_extension.reward_infos[1].total_claimed_rewards = 50;
+
+    position_reward_extension
+        .merge(&mut source_position_reward_extension)
+        .unwrap();
+
+    assert_eq!(
+        position_reward_extension.reward_infos[0].reward_pendings,
+        150
+    );
+    assert_eq!(
+        position_reward_extension.reward_infos[1].total_claimed_rewards,
+        150
+    );
+    assert_eq!(
+        source_position_reward_extension.reward_infos[0].reward_pendings,
+        0
+    );
+}
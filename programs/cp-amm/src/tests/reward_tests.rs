@@ -2,8 +2,13 @@ use std::u128;
 
 use proptest::proptest;
 
+use anchor_lang::prelude::Pubkey;
+use ruint::aliases::U256;
+
 use crate::{
-    constants::REWARD_RATE_SCALE, state::Pool, u128x128_math::Rounding,
+    constants::{DEFAULT_QUOTE_MINTS, MAX_REWARD_START_DELAY, REWARD_RATE_SCALE},
+    state::Pool,
+    u128x128_math::Rounding,
     utils_math::safe_shl_div_cast,
 };
 use proptest::prelude::*;
@@ -19,9 +24,151 @@ proptest! {
         let reward_info = &mut pool.reward_infos[0];
         reward_info.reward_duration = PER_DAY;
         // reward_info.reward_duration_end = ONE_DAY;
-        reward_info.update_rate_after_funding(60 * 60 * 48, funding_amount)?;
+        reward_info.update_rate_after_funding(60 * 60 * 48, funding_amount, None)?;
 
         let expect_rate: u128 = safe_shl_div_cast(funding_amount.into(), reward_info.reward_duration.into(), REWARD_RATE_SCALE, Rounding::Down)?;
         assert!(expect_rate == reward_info.reward_rate)
     }
 }
+
+#[test]
+fn test_is_reward_creator() {
+    let partner = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let pool = Pool {
+        partner,
+        creator,
+        ..Default::default()
+    };
+    assert!(pool.is_reward_creator(partner));
+    assert!(pool.is_reward_creator(creator));
+    assert!(!pool.is_reward_creator(Pubkey::new_unique()));
+    assert!(pool.has_reward_creator());
+
+    // creators that didn't sign the pool initialization aren't recorded, the partner keeps the creator reward index
+    let pool = Pool {
+        partner,
+        ..Default::default()
+    };
+    assert!(pool.has_reward_creator());
+
+    // pools created before the creator was recorded, admin can use the creator reward index
+    let pool = Pool::default();
+    assert!(!pool.is_reward_creator(Pubkey::default()));
+    assert!(!pool.has_reward_creator());
+}
+
+#[test]
+fn test_is_creator_reward_mint() {
+    let pool = Pool {
+        token_a_mint: Pubkey::new_unique(),
+        token_b_mint: Pubkey::new_unique(),
+        ..Default::default()
+    };
+    assert!(pool.is_creator_reward_mint(pool.token_a_mint));
+    assert!(pool.is_creator_reward_mint(pool.token_b_mint));
+    assert!(pool.is_creator_reward_mint(DEFAULT_QUOTE_MINTS[0]));
+    assert!(!pool.is_creator_reward_mint(Pubkey::new_unique()));
+}
+
+#[test]
+fn test_scheduled_reward_ignores_time_before_start() {
+    let mut pool = Pool::default();
+    let reward_info = &mut pool.reward_infos[0];
+    reward_info.initialized = 1;
+    reward_info.reward_duration = PER_DAY;
+
+    let funding_time = 1_000;
+    let start_time = 5_000;
+    reward_info
+        .update_rate_after_funding(funding_time, 1_000_000, Some(start_time))
+        .unwrap();
+    assert_eq!(reward_info.reward_start_time, start_time);
+    assert_eq!(reward_info.reward_duration_end, start_time + PER_DAY);
+
+    // nothing accrues before the start, even without liquidity
+    reward_info.update_rewards(0, start_time - 1).unwrap();
+    assert_eq!(
+        reward_info.cumulative_seconds_with_empty_liquidity_reward,
+        0
+    );
+    reward_info.update_rewards(1 << 64, start_time).unwrap();
+    assert_eq!(reward_info.reward_per_token_stored(), U256::ZERO);
+
+    reward_info.update_rewards(0, start_time + 100).unwrap();
+    assert_eq!(
+        reward_info.cumulative_seconds_with_empty_liquidity_reward,
+        100
+    );
+}
+
+#[test]
+fn test_fund_scheduled_reward() {
+    let mut pool = Pool::default();
+    let reward_info = &mut pool.reward_infos[0];
+    reward_info.initialized = 1;
+    reward_info.reward_duration = PER_DAY;
+
+    let current_time = 1_000;
+    assert!(reward_info
+        .update_rate_after_funding(current_time, 1_000_000, Some(current_time - 1))
+        .is_err());
+    assert!(reward_info
+        .update_rate_after_funding(
+            current_time,
+            1_000_000,
+            Some(current_time + MAX_REWARD_START_DELAY + 1)
+        )
+        .is_err());
+
+    let start_time = current_time + 100;
+    reward_info
+        .update_rate_after_funding(current_time, 1_000_000, Some(start_time))
+        .unwrap();
+
+    // funding again before the start carries the whole scheduled amount
+    reward_info
+        .update_rate_after_funding(current_time, 1_000_000, Some(start_time + 100))
+        .unwrap();
+    let expect_rate: u128 =
+        safe_shl_div_cast(1_999_999, PER_DAY.into(), REWARD_RATE_SCALE, Rounding::Down).unwrap();
+    assert!(reward_info.reward_rate.abs_diff(expect_rate) <= 1 << REWARD_RATE_SCALE);
+    assert_eq!(reward_info.reward_duration_end, start_time + 100 + PER_DAY);
+
+    // a running reward can't be rescheduled
+    assert!(reward_info
+        .update_rate_after_funding(start_time + 200, 1_000_000, Some(start_time + 300))
+        .is_err());
+    reward_info
+        .update_rate_after_funding(start_time + 200, 1_000_000, None)
+        .unwrap();
+    assert_eq!(reward_info.reward_start_time, start_time + 200);
+}
+
+#[test]
+fn test_top_up_keeps_scheduled_start() {
+    let mut pool = Pool::default();
+    let reward_info = &mut pool.reward_infos[0];
+    reward_info.initialized = 1;
+    reward_info.reward_duration = PER_DAY;
+
+    let current_time = 1_000;
+    let start_time = current_time + 3_600;
+    reward_info
+        .update_rate_after_funding(current_time, 1_000_000, Some(start_time))
+        .unwrap();
+
+    // a top up without start time doesn't move the pending reward to start now
+    reward_info
+        .update_rate_after_funding(current_time + 100, 1_000_000, None)
+        .unwrap();
+    assert_eq!(reward_info.reward_start_time, start_time);
+    assert_eq!(reward_info.reward_duration_end, start_time + PER_DAY);
+    let expect_rate: u128 =
+        safe_shl_div_cast(1_999_999, PER_DAY.into(), REWARD_RATE_SCALE, Rounding::Down).unwrap();
+    assert!(reward_info.reward_rate.abs_diff(expect_rate) <= 1 << REWARD_RATE_SCALE);
+
+    // nothing accrues before the scheduled start
+    reward_info.update_rewards(1 << 64, start_time).unwrap();
+    assert_eq!(reward_info.reward_per_token_stored(), U256::ZERO);
+}
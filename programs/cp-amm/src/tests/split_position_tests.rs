@@ -0,0 +1,304 @@
+use crate::{
+    split_vesting,
+    state::{Position, PositionRewardExtension, Vesting},
+    PoolError, SplitPositionParameters,
+};
+use anchor_lang::prelude::Pubkey;
+use proptest::prelude::*;
+use ruint::aliases::U256;
+use std::cell::RefCell;
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 10000, .. ProptestConfig::default()
+    })]
+    #[test]
+    fn test_split_position_keeps_totals(
+        unlocked_liquidity in 0..=u128::MAX,
+        permanent_locked_liquidity in 0..=u128::MAX,
+        fee_a_pending in 0..=u64::MAX,
+        fee_b_pending in 0..=u64::MAX,
+        reward_pendings in 0..=u64::MAX,
+        unlocked_liquidity_percentage in 0..=100u8,
+        permanent_locked_liquidity_percentage in 0..=100u8,
+        fee_percentage in 0..=100u8,
+        reward_percentage in 0..=100u8,
+    ) {
+        let mut position = Position {
+            unlocked_liquidity,
+            permanent_locked_liquidity,
+            fee_a_pending,
+            fee_b_pending,
+            ..Default::default()
+        };
+        position.reward_infos[0].reward_pendings = reward_pendings;
+        let mut new_position = Position::default();
+        let mut position_reward_extension = PositionRewardExtension::default();
+        position_reward_extension.reward_infos[1].reward_pendings = reward_pendings;
+        let mut new_position_reward_extension = PositionRewardExtension::default();
+
+        let params = SplitPositionParameters {
+            unlocked_liquidity_percentage,
+            vested_liquidity_percentage: 0,
+            permanent_locked_liquidity_percentage,
+            fee_a_percentage: fee_percentage,
+            fee_b_percentage: fee_percentage,
+            reward_0_percentage: reward_percentage,
+            reward_1_percentage: reward_percentage,
+            reward_2_percentage: reward_percentage,
+            reward_3_percentage: reward_percentage,
+        };
+        let split_amount = params
+            .get_split_amount(&position, Some(&position_reward_extension))
+            .unwrap();
+        position.split(&mut new_position, &split_amount).unwrap();
+        position_reward_extension
+            .split(&mut new_position_reward_extension, &split_amount.extension_rewards)
+            .unwrap();
+
+        assert_eq!(position.unlocked_liquidity + new_position.unlocked_liquidity, unlocked_liquidity);
+        assert_eq!(
+            position.permanent_locked_liquidity + new_position.permanent_locked_liquidity,
+            permanent_locked_liquidity
+        );
+        assert_eq!(position.fee_a_pending + new_position.fee_a_pending, fee_a_pending);
+        assert_eq!(position.fee_b_pending + new_position.fee_b_pending, fee_b_pending);
+        assert_eq!(
+            position.reward_infos[0].reward_pendings + new_position.reward_infos[0].reward_pendings,
+            reward_pendings
+        );
+        assert_eq!(
+            position_reward_extension.reward_infos[1].reward_pendings
+                + new_position_reward_extension.reward_infos[1].reward_pendings,
+            reward_pendings
+        );
+
+        if unlocked_liquidity_percentage == 100 {
+            assert_eq!(position.unlocked_liquidity, 0);
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_split_vesting_keeps_schedule(
+        cliff_unlock_liquidity in 0..=u64::MAX as u128,
+        liquidity_per_period in 0..=u64::MAX as u128,
+        number_of_period in 0..=u16::MAX,
+        period_frequency in 0..=1_000u64,
+        split_point in 0..=u32::MAX as u64,
+        later_point in 0..=u32::MAX as u64,
+        percentage in 1..=100u8,
+    ) {
+        let vesting = RefCell::new(Vesting {
+            cliff_point: 1_000,
+            period_frequency,
+            cliff_unlock_liquidity,
+            liquidity_per_period,
+            number_of_period,
+            ..Default::default()
+        });
+        let total_lock_amount = vesting.borrow().get_total_lock_amount().unwrap();
+        let position = RefCell::new(Position {
+            vested_liquidity: total_lock_amount,
+            ..Default::default()
+        });
+        let mut new_vesting = Vesting::default();
+
+        let (locked_liquidity, vested_liquidity) = split_vesting(
+            &mut vesting.borrow_mut(),
+            &mut new_vesting,
+            &mut position.borrow_mut(),
+            Pubkey::new_unique(),
+            percentage,
+            split_point,
+        )
+        .unwrap();
+        let vesting = vesting.into_inner();
+        let position = position.into_inner();
+
+        assert_eq!(locked_liquidity, position.vested_liquidity);
+        assert_eq!(
+            vesting.get_locked_liquidity().unwrap() + vested_liquidity,
+            locked_liquidity
+        );
+        assert_eq!(
+            vesting.get_total_lock_amount().unwrap() + new_vesting.get_total_lock_amount().unwrap(),
+            total_lock_amount
+        );
+
+        // neither vesting releases more than it holds, and both release what the original schedule would have
+        let later_point = later_point.max(split_point);
+        let released_liquidity = vesting.get_new_release_liquidity(later_point).unwrap();
+        let new_released_liquidity = new_vesting.get_new_release_liquidity(later_point).unwrap();
+        assert!(released_liquidity <= vesting.get_locked_liquidity().unwrap());
+        assert!(new_released_liquidity <= new_vesting.get_locked_liquidity().unwrap());
+        let original_vesting = Vesting {
+            cliff_point: 1_000,
+            period_frequency,
+            cliff_unlock_liquidity,
+            liquidity_per_period,
+            number_of_period,
+            ..Default::default()
+        };
+        assert_eq!(
+            released_liquidity + new_released_liquidity,
+            original_vesting.get_new_release_liquidity(later_point).unwrap()
+                - original_vesting.get_max_unlocked_liquidity(split_point).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_split_position_earns_fee_pro_rata() {
+    let fee_per_token_checkpoint = U256::from(1u128 << 64);
+    let mut position = Position {
+        unlocked_liquidity: 1_000u128 << 64,
+        fee_a_per_token_checkpoint: fee_per_token_checkpoint.to_le_bytes(),
+        fee_b_per_token_checkpoint: fee_per_token_checkpoint.to_le_bytes(),
+        ..Default::default()
+    };
+    let mut new_position = Position {
+        fee_a_per_token_checkpoint: fee_per_token_checkpoint.to_le_bytes(),
+        fee_b_per_token_checkpoint: fee_per_token_checkpoint.to_le_bytes(),
+        ..Default::default()
+    };
+
+    let params = SplitPositionParameters {
+        unlocked_liquidity_percentage: 25,
+        ..Default::default()
+    };
+    let split_amount = params.get_split_amount(&position, None).unwrap();
+    position.split(&mut new_position, &split_amount).unwrap();
+
+    // 1 token of fee per unit of liquidity
+    let fee_per_token_stored = fee_per_token_checkpoint + U256::from(1u128 << 64);
+    position
+        .update_fee(fee_per_token_stored, fee_per_token_stored)
+        .unwrap();
+    new_position
+        .update_fee(fee_per_token_stored, fee_per_token_stored)
+        .unwrap();
+
+    assert_eq!(position.fee_a_pending, 750);
+    assert_eq!(new_position.fee_a_pending, 250);
+}
+
+#[test]
+fn test_split_position_invalid_parameters() {
+    let err = SplitPositionParameters::default().validate().unwrap_err();
+    assert_eq!(err, PoolError::InvalidSplitPositionParameters.into());
+
+    let err = SplitPositionParameters {
+        fee_a_percentage: 101,
+        ..Default::default()
+    }
+    .validate()
+    .unwrap_err();
+    assert_eq!(err, PoolError::InvalidSplitPositionParameters.into());
+}
+
+#[test]
+fn test_split_position_with_vested_liquidity() {
+    let position = RefCell::new(Position {
+        vested_liquidity: 1_999,
+        ..Default::default()
+    });
+    let mut new_position = Position::default();
+    let vesting = RefCell::new(Vesting {
+        cliff_point: 100,
+        period_frequency: 10,
+        cliff_unlock_liquidity: 1_000,
+        liquidity_per_period: 333,
+        number_of_period: 3,
+        ..Default::default()
+    });
+    let mut new_vesting = Vesting::default();
+    let new_position_key = Pubkey::new_unique();
+
+    let params = SplitPositionParameters {
+        vested_liquidity_percentage: 50,
+        ..Default::default()
+    };
+    // the cliff and the first period are released to the position before splitting
+    let (locked_liquidity, vested_liquidity) = split_vesting(
+        &mut vesting.borrow_mut(),
+        &mut new_vesting,
+        &mut position.borrow_mut(),
+        new_position_key,
+        params.vested_liquidity_percentage,
+        115,
+    )
+    .unwrap();
+    assert_eq!(locked_liquidity, 666);
+    assert_eq!(vested_liquidity, 332);
+
+    let mut position = position.into_inner();
+    let vesting = vesting.into_inner();
+    assert_eq!(position.unlocked_liquidity, 1_333);
+    assert_eq!(position.vested_liquidity, 666);
+    assert_eq!(new_vesting.position, new_position_key);
+    assert_eq!(new_vesting.cliff_unlock_liquidity, 500);
+    assert_eq!(new_vesting.liquidity_per_period, 166);
+    assert_eq!(new_vesting.total_released_liquidity, 666);
+    assert_eq!(vesting.cliff_unlock_liquidity, 500);
+    assert_eq!(vesting.liquidity_per_period, 167);
+    assert_eq!(vesting.total_released_liquidity, 667);
+
+    let mut split_amount = params.get_split_amount(&position, None).unwrap();
+    split_amount.vested_liquidity = vested_liquidity;
+    position.split(&mut new_position, &split_amount).unwrap();
+    assert_eq!(position.vested_liquidity, 334);
+    assert_eq!(new_position.vested_liquidity, 332);
+    assert_eq!(position.unlocked_liquidity, 1_333);
+    assert_eq!(new_position.unlocked_liquidity, 0);
+
+    // both vestings release the rest of their schedule to their own position
+    assert_eq!(vesting.get_new_release_liquidity(130).unwrap(), 334);
+    assert_eq!(new_vesting.get_new_release_liquidity(130).unwrap(), 332);
+}
+
+#[test]
+fn test_split_position_reward_extension() {
+    let position = Position::default();
+    let mut position_reward_extension = PositionRewardExtension::default();
+    position_reward_extension.reward_infos[0].reward_pendings = 1_000;
+    position_reward_extension.reward_infos[1].reward_pendings = 1_000;
+    let mut new_position_reward_extension = PositionRewardExtension::default();
+
+    let params = SplitPositionParameters {
+        reward_2_percentage: 30,
+        reward_3_percentage: 100,
+        ..Default::default()
+    };
+    params.validate().unwrap();
+
+    // positions without a position reward extension have no extension rewards to split
+    let split_amount = params.get_split_amount(&position, None).unwrap();
+    assert_eq!(split_amount.extension_rewards, [0, 0]);
+
+    let split_amount = params
+        .get_split_amount(&position, Some(&position_reward_extension))
+        .unwrap();
+    assert_eq!(split_amount.extension_rewards, [300, 1_000]);
+    position_reward_extension
+        .split(
+            &mut new_position_reward_extension,
+            &split_amount.extension_rewards,
+        )
+        .unwrap();
+
+    assert_eq!(
+        position_reward_extension.reward_infos[0].reward_pendings,
+        700
+    );
+    assert_eq!(position_reward_extension.reward_infos[1].reward_pendings, 0);
+    assert_eq!(
+        new_position_reward_extension.reward_infos[0].reward_pendings,
+        300
+    );
+    assert_eq!(
+        new_position_reward_extension.reward_infos[1].reward_pendings,
+        1_000
+    );
+}
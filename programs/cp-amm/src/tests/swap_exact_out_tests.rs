@@ -0,0 +1,128 @@
+use crate::{
+    constants::{MAX_SQRT_PRICE, MIN_SQRT_PRICE},
+    params::swap::TradeDirection,
+    state::{
+        fee::{BaseFeeStruct, FeeMode, PoolFeesStruct},
+        CollectFeeMode, Pool, SwapExactOutResult,
+    },
+    tests::LIQUIDITY_MAX,
+    PoolError,
+};
+use proptest::{bool::ANY, prelude::*};
+
+fn new_pool(sqrt_price: u128, liquidity: u128, collect_fee_mode: CollectFeeMode) -> Pool {
+    let pool_fees = PoolFeesStruct {
+        base_fee: BaseFeeStruct {
+            cliff_fee_numerator: 10_000_000, // 1%
+            ..Default::default()
+        },
+        protocol_fee_percent: 20,
+        partner_fee_percent: 50,
+        referral_fee_percent: 20,
+        ..Default::default()
+    };
+    Pool {
+        pool_fees,
+        liquidity,
+        sqrt_price,
+        sqrt_min_price: MIN_SQRT_PRICE,
+        sqrt_max_price: MAX_SQRT_PRICE,
+        collect_fee_mode: collect_fee_mode.into(),
+        ..Default::default()
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 10000, .. ProptestConfig::default()
+    })]
+    #[test]
+    fn test_swap_exact_out_matches_swap_exact_in(
+        sqrt_price in MIN_SQRT_PRICE..=MAX_SQRT_PRICE,
+        amount_out in 1..=u32::MAX as u64,
+        liquidity in 1..=LIQUIDITY_MAX,
+        a_to_b in ANY,
+        only_b in ANY,
+        has_referral in ANY,
+    ) {
+        let collect_fee_mode = if only_b {
+            CollectFeeMode::OnlyB
+        } else {
+            CollectFeeMode::BothToken
+        };
+        let pool = new_pool(sqrt_price, liquidity, collect_fee_mode);
+
+        let trade_direction = if a_to_b {
+            TradeDirection::AtoB
+        } else {
+            TradeDirection::BtoA
+        };
+        let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, has_referral).unwrap();
+
+        if let Ok(SwapExactOutResult { input_amount, swap_result }) =
+            pool.get_swap_result_exact_out(amount_out, fee_mode, trade_direction, 0)
+        {
+            assert_eq!(swap_result.output_amount, amount_out);
+
+            // paying the quoted input must release at least the requested output
+            let exact_in_result = pool
+                .get_swap_result(input_amount, fee_mode, trade_direction, 0)
+                .unwrap();
+            assert!(exact_in_result.output_amount >= amount_out);
+        }
+    }
+}
+
+#[test]
+fn test_swap_exact_out_fee_on_output() {
+    let pool = new_pool(1 << 64, LIQUIDITY_MAX, CollectFeeMode::BothToken);
+    let trade_direction = TradeDirection::AtoB;
+    let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+
+    let SwapExactOutResult {
+        input_amount,
+        swap_result,
+    } = pool
+        .get_swap_result_exact_out(990_000, fee_mode, trade_direction, 0)
+        .unwrap();
+
+    // 1% fee is taken on the 1_000_000 released by the curve
+    let total_fee = swap_result.lp_fee + swap_result.protocol_fee + swap_result.partner_fee;
+    assert_eq!(total_fee, 10_000);
+    assert_eq!(swap_result.output_amount, 990_000);
+    assert!(input_amount >= 1_000_000);
+}
+
+#[test]
+fn test_swap_exact_out_fee_on_input() {
+    let pool = new_pool(1 << 64, LIQUIDITY_MAX, CollectFeeMode::OnlyB);
+    let trade_direction = TradeDirection::BtoA;
+    let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+
+    let SwapExactOutResult {
+        input_amount,
+        swap_result,
+    } = pool
+        .get_swap_result_exact_out(1_000_000, fee_mode, trade_direction, 0)
+        .unwrap();
+
+    let total_fee = swap_result.lp_fee + swap_result.protocol_fee + swap_result.partner_fee;
+    assert_eq!(swap_result.output_amount, 1_000_000);
+    assert!(total_fee >= input_amount / 100);
+}
+
+#[test]
+fn test_swap_exact_out_exceed_pool_liquidity() {
+    // pool holds around 1_000 of each token
+    let liquidity = 1_000u128 << 64;
+    let pool = new_pool(1 << 64, liquidity, CollectFeeMode::BothToken);
+
+    for trade_direction in [TradeDirection::AtoB, TradeDirection::BtoA] {
+        let fee_mode =
+            &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+        let err = pool
+            .get_swap_result_exact_out(1_000, fee_mode, trade_direction, 0)
+            .unwrap_err();
+        assert_eq!(err, PoolError::PriceRangeViolation.into());
+    }
+}
@@ -37,7 +37,7 @@ proptest! {
             .get_swap_result(amount_in, fee_mode, trade_direction, 0)
             .unwrap();
 
-            pool.apply_swap_result(&swap_result_0, fee_mode, 0).unwrap();
+            pool.apply_swap_result(&swap_result_0, fee_mode, None, 0).unwrap();
             // swap back
 
             let swap_result_1 = pool
@@ -73,7 +73,7 @@ proptest! {
             .get_swap_result(amount_in, fee_mode, trade_direction, 0)
             .unwrap();
 
-            pool.apply_swap_result(&swap_result_0, fee_mode, 0).unwrap();
+            pool.apply_swap_result(&swap_result_0, fee_mode, None, 0).unwrap();
             // swap back
 
             let swap_result_1 = pool
@@ -138,7 +138,8 @@ fn test_reserve_wont_lost_when_swap_from_b_to_a_single() {
 
     println!("{:?}", swap_result_0);
 
-    pool.apply_swap_result(&swap_result_0, fee_mode, 0).unwrap();
+    pool.apply_swap_result(&swap_result_0, fee_mode, None, 0)
+        .unwrap();
 
     let swap_result_1 = pool
         .get_swap_result(
@@ -202,7 +203,8 @@ fn test_swap_basic() {
 
     // return;
 
-    pool.apply_swap_result(&swap_result, fee_mode, 0).unwrap();
+    pool.apply_swap_result(&swap_result, fee_mode, None, 0)
+        .unwrap();
 
     let swap_result_referse = pool
         .get_swap_result(swap_result.output_amount, fee_mode, TradeDirection::BtoA, 0)
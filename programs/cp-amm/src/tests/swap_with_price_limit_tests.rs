@@ -0,0 +1,150 @@
+use crate::{
+    constants::{MAX_SQRT_PRICE, MIN_SQRT_PRICE},
+    params::swap::TradeDirection,
+    state::{
+        fee::{BaseFeeStruct, FeeMode, PoolFeesStruct},
+        CollectFeeMode, Pool, SwapWithPriceLimitResult,
+    },
+    tests::LIQUIDITY_MAX,
+    PoolError,
+};
+use proptest::{bool::ANY, prelude::*};
+
+fn new_pool(sqrt_price: u128, liquidity: u128, collect_fee_mode: CollectFeeMode) -> Pool {
+    let pool_fees = PoolFeesStruct {
+        base_fee: BaseFeeStruct {
+            cliff_fee_numerator: 10_000_000, // 1%
+            ..Default::default()
+        },
+        protocol_fee_percent: 20,
+        partner_fee_percent: 50,
+        referral_fee_percent: 20,
+        ..Default::default()
+    };
+    Pool {
+        pool_fees,
+        liquidity,
+        sqrt_price,
+        sqrt_min_price: MIN_SQRT_PRICE,
+        sqrt_max_price: MAX_SQRT_PRICE,
+        collect_fee_mode: collect_fee_mode.into(),
+        ..Default::default()
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 10000, .. ProptestConfig::default()
+    })]
+    #[test]
+    fn test_swap_with_price_limit_never_cross_limit(
+        sqrt_price in MIN_SQRT_PRICE + 1..MAX_SQRT_PRICE,
+        limit_bps in 1..=10_000u128,
+        amount_in in 1..=u64::MAX,
+        liquidity in 1..=LIQUIDITY_MAX,
+        a_to_b in ANY,
+        only_b in ANY,
+    ) {
+        let collect_fee_mode = if only_b {
+            CollectFeeMode::OnlyB
+        } else {
+            CollectFeeMode::BothToken
+        };
+        let pool = new_pool(sqrt_price, liquidity, collect_fee_mode);
+
+        let (trade_direction, sqrt_price_limit) = if a_to_b {
+            (TradeDirection::AtoB, sqrt_price - sqrt_price * limit_bps / 10_000 / 2)
+        } else {
+            (TradeDirection::BtoA, sqrt_price + sqrt_price * limit_bps / 10_000)
+        };
+        let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+
+        if let Ok(SwapWithPriceLimitResult { consumed_amount_in, swap_result }) = pool
+            .get_swap_result_with_price_limit(amount_in, sqrt_price_limit, fee_mode, trade_direction, 0)
+        {
+            assert!(consumed_amount_in <= amount_in);
+            match trade_direction {
+                TradeDirection::AtoB => {
+                    assert!(swap_result.next_sqrt_price >= sqrt_price_limit.max(MIN_SQRT_PRICE));
+                }
+                TradeDirection::BtoA => {
+                    assert!(swap_result.next_sqrt_price <= sqrt_price_limit.min(MAX_SQRT_PRICE));
+                }
+            }
+
+            // the consumed amount swapped without limit never gives less output
+            if let Ok(exact_in_result) =
+                pool.get_swap_result(consumed_amount_in, fee_mode, trade_direction, 0)
+            {
+                assert!(exact_in_result.output_amount >= swap_result.output_amount);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_swap_with_price_limit_partial_fill() {
+    let sqrt_price = 1u128 << 64;
+    let pool = new_pool(sqrt_price, LIQUIDITY_MAX, CollectFeeMode::OnlyB);
+
+    for trade_direction in [TradeDirection::AtoB, TradeDirection::BtoA] {
+        let sqrt_price_limit = match trade_direction {
+            TradeDirection::AtoB => sqrt_price - sqrt_price / 100,
+            TradeDirection::BtoA => sqrt_price + sqrt_price / 100,
+        };
+        let fee_mode =
+            &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+
+        let SwapWithPriceLimitResult {
+            consumed_amount_in,
+            swap_result,
+        } = pool
+            .get_swap_result_with_price_limit(
+                u64::MAX,
+                sqrt_price_limit,
+                fee_mode,
+                trade_direction,
+                0,
+            )
+            .unwrap();
+
+        assert!(consumed_amount_in < u64::MAX);
+        assert_eq!(swap_result.next_sqrt_price, sqrt_price_limit);
+    }
+}
+
+#[test]
+fn test_swap_with_price_limit_full_fill() {
+    let sqrt_price = 1u128 << 64;
+    let pool = new_pool(sqrt_price, LIQUIDITY_MAX, CollectFeeMode::BothToken);
+    let trade_direction = TradeDirection::AtoB;
+    let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+
+    let amount_in = 1_000_000;
+    let SwapWithPriceLimitResult {
+        consumed_amount_in,
+        swap_result,
+    } = pool
+        .get_swap_result_with_price_limit(amount_in, MIN_SQRT_PRICE, fee_mode, trade_direction, 0)
+        .unwrap();
+
+    assert_eq!(consumed_amount_in, amount_in);
+    assert_eq!(
+        swap_result,
+        pool.get_swap_result(amount_in, fee_mode, trade_direction, 0)
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_swap_with_price_limit_wrong_side() {
+    let sqrt_price = 1u128 << 64;
+    let pool = new_pool(sqrt_price, LIQUIDITY_MAX, CollectFeeMode::BothToken);
+    let trade_direction = TradeDirection::AtoB;
+    let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+
+    let err = pool
+        .get_swap_result_with_price_limit(1_000, sqrt_price + 1, fee_mode, trade_direction, 0)
+        .unwrap_err();
+    assert_eq!(err, PoolError::InvalidSqrtPriceLimit.into());
+}
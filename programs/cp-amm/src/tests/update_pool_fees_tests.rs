@@ -0,0 +1,178 @@
+use anchor_lang::prelude::Pubkey;
+
+use crate::{
+    constants::{MAX_SQRT_PRICE, MIN_SQRT_PRICE},
+    params::fee_parameters::{
+        BaseFeeParameters, DynamicFeeParameters, ExtraFeeParameters, PoolFeeParameters,
+    },
+    state::{fee::FeeSchedulerMode, Config, Pool},
+};
+
+fn get_pool_fee_parameters(dynamic_fee: Option<DynamicFeeParameters>) -> PoolFeeParameters {
+    PoolFeeParameters {
+        base_fee: BaseFeeParameters {
+            cliff_fee_numerator: 10_000_000, // 1%
+            fee_scheduler_mode: FeeSchedulerMode::Linear.into(),
+            ..Default::default()
+        },
+        protocol_fee_percent: 20,
+        partner_fee_percent: 10,
+        referral_fee_percent: 20,
+        dynamic_fee,
+    }
+}
+
+fn get_extra_fee_parameters() -> ExtraFeeParameters {
+    ExtraFeeParameters {
+        a_to_b_fee_multiplier_bps: Some(20_000),
+        ..Default::default()
+    }
+}
+
+fn get_dynamic_fee_parameters() -> DynamicFeeParameters {
+    get_dynamic_fee_parameters_with_bin_step(10)
+}
+
+fn get_dynamic_fee_parameters_with_bin_step(bin_step: u16) -> DynamicFeeParameters {
+    let mut params = DynamicFeeParameters {
+        bin_step,
+        bin_step_u128: 0,
+        filter_period: 10,
+        decay_period: 120,
+        reduction_factor: 5000,
+        max_volatility_accumulator: 14460000,
+        variable_fee_control: 5,
+    };
+    params.bin_step_u128 = params.get_bin_step_u128();
+    params
+}
+
+#[test]
+fn test_pool_fee_parameters_round_trip() {
+    for dynamic_fee in [None, Some(get_dynamic_fee_parameters())] {
+        let pool_fees = get_pool_fee_parameters(dynamic_fee);
+        let extra_fees = get_extra_fee_parameters();
+        let pool_fees_struct = pool_fees.to_pool_fees_struct(&extra_fees);
+        assert_eq!(
+            format!("{:?}", pool_fees_struct.to_pool_fee_parameters()),
+            format!("{:?}", pool_fees)
+        );
+        assert_eq!(
+            format!("{:?}", pool_fees_struct.to_extra_fee_parameters()),
+            format!("{:?}", extra_fees)
+        );
+    }
+}
+
+#[test]
+fn test_update_pool_fees_resets_dynamic_fee_references() {
+    let mut pool = Pool {
+        sqrt_price: 1 << 64,
+        pool_fees: get_pool_fee_parameters(Some(get_dynamic_fee_parameters()))
+            .to_pool_fees_struct(&get_extra_fee_parameters()),
+        ..Default::default()
+    };
+    pool.update_pre_swap(100).unwrap();
+    pool.sqrt_price = 2 << 64;
+    pool.update_post_swap(1 << 64, 100).unwrap();
+    assert!(pool.pool_fees.dynamic_fee.volatility_accumulator > 0);
+
+    let mut new_pool_fees =
+        get_pool_fee_parameters(Some(get_dynamic_fee_parameters_with_bin_step(25)));
+    new_pool_fees.base_fee.cliff_fee_numerator = 5_000_000;
+    pool.update_pool_fees(
+        new_pool_fees.to_pool_fees_struct(&get_extra_fee_parameters()),
+        200,
+    );
+
+    let dynamic_fee = &pool.pool_fees.dynamic_fee;
+    assert_eq!(pool.pool_fees.base_fee.cliff_fee_numerator, 5_000_000);
+    assert_eq!(dynamic_fee.bin_step, 25);
+    assert_eq!(dynamic_fee.sqrt_price_reference, pool.sqrt_price);
+    assert_eq!(dynamic_fee.last_update_timestamp, 200);
+    assert_eq!(dynamic_fee.volatility_accumulator, 0);
+    assert_eq!(dynamic_fee.volatility_reference, 0);
+    assert_eq!(dynamic_fee.get_variable_fee().unwrap(), 0);
+}
+
+#[test]
+fn test_update_pool_fees_clears_stale_volatility() {
+    let mut pool = Pool {
+        sqrt_price: 1 << 64,
+        pool_fees: get_pool_fee_parameters(Some(get_dynamic_fee_parameters()))
+            .to_pool_fees_struct(&get_extra_fee_parameters()),
+        ..Default::default()
+    };
+    pool.update_pre_swap(100).unwrap();
+    pool.sqrt_price = 2 << 64;
+    pool.update_post_swap(1 << 64, 100).unwrap();
+    pool.update_pre_swap(110).unwrap();
+    assert!(pool.pool_fees.dynamic_fee.volatility_accumulator > 0);
+    assert!(pool.pool_fees.dynamic_fee.volatility_reference > 0);
+
+    // the new fees still carry the volatility accumulated under the old settings
+    let mut new_pool_fees = pool.pool_fees;
+    new_pool_fees.base_fee.cliff_fee_numerator = 5_000_000;
+    pool.update_pool_fees(new_pool_fees, 200);
+
+    let dynamic_fee = &pool.pool_fees.dynamic_fee;
+    assert_eq!(pool.pool_fees.base_fee.cliff_fee_numerator, 5_000_000);
+    assert_eq!(dynamic_fee.volatility_accumulator, 0);
+    assert_eq!(dynamic_fee.volatility_reference, 0);
+    assert_eq!(dynamic_fee.sqrt_price_reference, pool.sqrt_price);
+    assert_eq!(dynamic_fee.last_update_timestamp, 200);
+}
+
+#[test]
+fn test_update_pool_fees_disable_dynamic_fee() {
+    let mut pool = Pool {
+        sqrt_price: 1 << 64,
+        pool_fees: get_pool_fee_parameters(Some(get_dynamic_fee_parameters()))
+            .to_pool_fees_struct(&get_extra_fee_parameters()),
+        ..Default::default()
+    };
+    pool.update_pool_fees(
+        get_pool_fee_parameters(None).to_pool_fees_struct(&get_extra_fee_parameters()),
+        200,
+    );
+
+    assert!(!pool.pool_fees.dynamic_fee.is_dynamic_fee_enable());
+    assert_eq!(pool.pool_fees.dynamic_fee.sqrt_price_reference, 0);
+    assert!(pool
+        .pool_fees
+        .to_pool_fee_parameters()
+        .dynamic_fee
+        .is_none());
+}
+
+#[test]
+fn test_update_config_pool_fees() {
+    let mut config = Config::default();
+    config.init(
+        1,
+        get_pool_fee_parameters(None).to_pool_fees_config(&ExtraFeeParameters::default()),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        0,
+        MIN_SQRT_PRICE,
+        MAX_SQRT_PRICE,
+        1,
+        Pubkey::default(),
+    );
+
+    let new_pool_fees = get_pool_fee_parameters(Some(get_dynamic_fee_parameters()));
+    config.update_pool_fees(&new_pool_fees, &get_extra_fee_parameters());
+
+    assert_eq!(
+        format!("{:?}", config.pool_fees.to_pool_fee_parameters()),
+        format!("{:?}", new_pool_fees)
+    );
+    assert_eq!(
+        format!("{:?}", config.pool_fees.to_extra_fee_parameters()),
+        format!("{:?}", get_extra_fee_parameters())
+    );
+    assert_eq!(config.index, 1);
+    assert_eq!(config.collect_fee_mode, 1);
+    assert_eq!(config.sqrt_min_price, MIN_SQRT_PRICE);
+    assert_eq!(config.sqrt_max_price, MAX_SQRT_PRICE);
+}
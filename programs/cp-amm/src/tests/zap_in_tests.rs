@@ -0,0 +1,155 @@
+use crate::{
+    constants::{MAX_SQRT_PRICE, MIN_SQRT_PRICE},
+    params::swap::TradeDirection,
+    state::{
+        fee::{BaseFeeStruct, FeeMode, PoolFeesStruct},
+        CollectFeeMode, ModifyLiquidityResult, Pool, ZapInResult,
+    },
+    tests::LIQUIDITY_MAX,
+    u128x128_math::Rounding,
+    PoolError,
+};
+use proptest::{bool::ANY, prelude::*};
+
+fn new_pool(sqrt_price: u128, liquidity: u128, collect_fee_mode: CollectFeeMode) -> Pool {
+    let pool_fees = PoolFeesStruct {
+        base_fee: BaseFeeStruct {
+            cliff_fee_numerator: 10_000_000, // 1%
+            ..Default::default()
+        },
+        protocol_fee_percent: 20,
+        partner_fee_percent: 50,
+        ..Default::default()
+    };
+    Pool {
+        pool_fees,
+        liquidity,
+        sqrt_price,
+        sqrt_min_price: MIN_SQRT_PRICE,
+        sqrt_max_price: MAX_SQRT_PRICE,
+        collect_fee_mode: collect_fee_mode.into(),
+        ..Default::default()
+    }
+}
+
+/// Apply the zap result on the pool and return the amounts deposited for the liquidity delta
+fn apply_zap_in(
+    pool: &mut Pool,
+    zap_in_result: &ZapInResult,
+    fee_mode: &FeeMode,
+) -> ModifyLiquidityResult {
+    pool.apply_swap_result(&zap_in_result.swap_result, fee_mode, None, 0)
+        .unwrap();
+    pool.get_amounts_for_modify_liquidity(zap_in_result.liquidity_delta, Rounding::Up)
+        .unwrap()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 1000, .. ProptestConfig::default()
+    })]
+    #[test]
+    fn test_zap_in_deposit_fits_amount_in(
+        sqrt_price in MIN_SQRT_PRICE..=MAX_SQRT_PRICE,
+        amount_in in 1..=u64::MAX,
+        liquidity in 1..=LIQUIDITY_MAX,
+        a_to_b in ANY,
+        only_b in ANY,
+    ) {
+        let collect_fee_mode = if only_b {
+            CollectFeeMode::OnlyB
+        } else {
+            CollectFeeMode::BothToken
+        };
+        let mut pool = new_pool(sqrt_price, liquidity, collect_fee_mode);
+
+        let trade_direction = if a_to_b {
+            TradeDirection::AtoB
+        } else {
+            TradeDirection::BtoA
+        };
+        let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+
+        if let Ok(zap_in_result) = pool.get_zap_in_result(amount_in, fee_mode, trade_direction, 0) {
+            assert!(zap_in_result.swap_amount_in <= amount_in);
+
+            let ModifyLiquidityResult { token_a_amount, token_b_amount } =
+                apply_zap_in(&mut pool, &zap_in_result, fee_mode);
+            let (deposit_amount_in, deposit_amount_out) = match trade_direction {
+                TradeDirection::AtoB => (token_a_amount, token_b_amount),
+                TradeDirection::BtoA => (token_b_amount, token_a_amount),
+            };
+            assert!(zap_in_result.swap_amount_in + deposit_amount_in <= amount_in);
+            assert!(deposit_amount_out <= zap_in_result.swap_result.output_amount);
+        }
+    }
+}
+
+#[test]
+fn test_zap_in_leaves_little_dust() {
+    let sqrt_price = 1u128 << 64;
+    let amount_in = 1_000_000;
+
+    for trade_direction in [TradeDirection::AtoB, TradeDirection::BtoA] {
+        let mut pool = new_pool(sqrt_price, LIQUIDITY_MAX, CollectFeeMode::BothToken);
+        let fee_mode =
+            &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+
+        let zap_in_result = pool
+            .get_zap_in_result(amount_in, fee_mode, trade_direction, 0)
+            .unwrap();
+
+        // at price 1 with a 1% fee around half of the input is swapped
+        assert!(zap_in_result.swap_amount_in > amount_in * 49 / 100);
+        assert!(zap_in_result.swap_amount_in < amount_in * 51 / 100);
+
+        let ModifyLiquidityResult {
+            token_a_amount,
+            token_b_amount,
+        } = apply_zap_in(&mut pool, &zap_in_result, fee_mode);
+        let (deposit_amount_in, deposit_amount_out) = match trade_direction {
+            TradeDirection::AtoB => (token_a_amount, token_b_amount),
+            TradeDirection::BtoA => (token_b_amount, token_a_amount),
+        };
+        let dust_in = amount_in - zap_in_result.swap_amount_in - deposit_amount_in;
+        let dust_out = zap_in_result.swap_result.output_amount - deposit_amount_out;
+        assert!(dust_in <= 1);
+        assert!(dust_out <= 1);
+    }
+}
+
+#[test]
+fn test_zap_in_without_swap_at_price_bound() {
+    // the pool only needs token a at the min price, nothing has to be swapped
+    let mut pool = new_pool(MIN_SQRT_PRICE, LIQUIDITY_MAX, CollectFeeMode::BothToken);
+    let trade_direction = TradeDirection::AtoB;
+    let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+
+    let zap_in_result = pool
+        .get_zap_in_result(1_000_000, fee_mode, trade_direction, 0)
+        .unwrap();
+    assert_eq!(zap_in_result.swap_amount_in, 0);
+    assert!(zap_in_result.liquidity_delta > 0);
+
+    let ModifyLiquidityResult { token_b_amount, .. } =
+        apply_zap_in(&mut pool, &zap_in_result, fee_mode);
+    assert_eq!(token_b_amount, 0);
+}
+
+#[test]
+fn test_zap_in_surfaces_swap_errors() {
+    // an unknown fee scheduler mode fails the swap for a reason other than the price range
+    let mut pool = new_pool(1u128 << 64, LIQUIDITY_MAX, CollectFeeMode::BothToken);
+    pool.pool_fees.base_fee.fee_scheduler_mode = u8::MAX;
+    let trade_direction = TradeDirection::AtoB;
+    let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, false).unwrap();
+
+    let swap_err = pool
+        .get_swap_result(500_000, fee_mode, trade_direction, 0)
+        .unwrap_err();
+    let zap_in_err = pool
+        .get_zap_in_result(1_000_000, fee_mode, trade_direction, 0)
+        .unwrap_err();
+    assert_eq!(zap_in_err, swap_err);
+    assert_ne!(zap_in_err, PoolError::PriceRangeViolation.into());
+}
@@ -60,7 +60,18 @@ pub fn calculate_transfer_fee_excluded_amount<'info>(
     token_mint: &InterfaceAccount<'info, Mint>,
     transfer_fee_included_amount: u64,
 ) -> Result<TransferFeeExcludedAmount> {
-    if let Some(epoch_transfer_fee) = get_epoch_transfer_fee(token_mint)? {
+    let epoch_transfer_fee = get_epoch_transfer_fee(token_mint)?;
+    calculate_transfer_fee_excluded_amount_with_epoch_fee(
+        epoch_transfer_fee.as_ref(),
+        transfer_fee_included_amount,
+    )
+}
+
+pub fn calculate_transfer_fee_excluded_amount_with_epoch_fee(
+    epoch_transfer_fee: Option<&TransferFee>,
+    transfer_fee_included_amount: u64,
+) -> Result<TransferFeeExcludedAmount> {
+    if let Some(epoch_transfer_fee) = epoch_transfer_fee {
         let transfer_fee = epoch_transfer_fee
             .calculate_fee(transfer_fee_included_amount)
             .ok_or_else(|| PoolError::MathOverflow)?;
@@ -90,7 +101,25 @@ pub fn calculate_transfer_fee_included_amount<'info>(
         });
     }
 
-    if let Some(epoch_transfer_fee) = get_epoch_transfer_fee(token_mint)? {
+    let epoch_transfer_fee = get_epoch_transfer_fee(token_mint)?;
+    calculate_transfer_fee_included_amount_with_epoch_fee(
+        epoch_transfer_fee.as_ref(),
+        transfer_fee_excluded_amount,
+    )
+}
+
+pub fn calculate_transfer_fee_included_amount_with_epoch_fee(
+    epoch_transfer_fee: Option<&TransferFee>,
+    transfer_fee_excluded_amount: u64,
+) -> Result<TransferFeeIncludedAmount> {
+    if transfer_fee_excluded_amount == 0 {
+        return Ok(TransferFeeIncludedAmount {
+            amount: 0,
+            transfer_fee: 0,
+        });
+    }
+
+    if let Some(epoch_transfer_fee) = epoch_transfer_fee {
         let transfer_fee: u64 =
             if u16::from(epoch_transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
                 // edge-case: if transfer fee rate is 100%, current SPL implementation returns 0 as inverse fee.
@@ -0,0 +1,822 @@
+use std::cmp::{max, min};
+
+use anchor_lang::{
+    event::EVENT_IX_TAG_LE, prelude::*, AnchorDeserialize, Discriminator, InstructionData,
+    ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address_with_program_id,
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    },
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{transfer_fee::instruction::initialize_transfer_fee_config, ExtensionType},
+        state::Account as TokenAccount,
+    },
+};
+use bytemuck::Pod;
+use cp_amm::{
+    constants::{
+        seeds::{
+            CUSTOMIZABLE_POOL_PREFIX, ORACLE_PREFIX, POOL_AUTHORITY_PREFIX, POOL_PREFIX,
+            POSITION_NFT_ACCOUNT_PREFIX, POSITION_PREFIX, POSITION_REWARD_EXTENSION_PREFIX,
+            REFERRAL_ACCOUNT_PREFIX, REWARD_EXTENSION_PREFIX, REWARD_VAULT_PREFIX,
+            TOKEN_VAULT_PREFIX,
+        },
+        MAX_SQRT_PRICE, MIN_SQRT_PRICE,
+    },
+    params::fee_parameters::{BaseFeeParameters, ExtraFeeParameters, PoolFeeParameters},
+    state::{CollectFeeMode, Config},
+    AddLiquidityParameters, InitializePoolParameters, PoolError, RemoveLiquidityParameters,
+    SwapParameters,
+};
+use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+pub const DEFAULT_SQRT_PRICE: u128 = 1 << 64;
+pub const DEFAULT_LIQUIDITY: u128 = 1_000_000 << 64;
+pub const INITIAL_BALANCE: u64 = 1_000_000_000_000;
+/// Compute unit limit of every transaction, the position NFT creation doesn't fit the default limit
+pub const COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+pub fn pool_authority() -> Pubkey {
+    Pubkey::find_program_address(&[POOL_AUTHORITY_PREFIX], &cp_amm::ID).0
+}
+
+pub fn event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &cp_amm::ID).0
+}
+
+pub fn derive_pool(config: Pubkey, token_a_mint: Pubkey, token_b_mint: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            POOL_PREFIX,
+            config.as_ref(),
+            max(token_a_mint, token_b_mint).as_ref(),
+            min(token_a_mint, token_b_mint).as_ref(),
+        ],
+        &cp_amm::ID,
+    )
+    .0
+}
+
+pub fn derive_customizable_pool(token_a_mint: Pubkey, token_b_mint: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            CUSTOMIZABLE_POOL_PREFIX,
+            max(token_a_mint, token_b_mint).as_ref(),
+            min(token_a_mint, token_b_mint).as_ref(),
+        ],
+        &cp_amm::ID,
+    )
+    .0
+}
+
+pub fn derive_position(position_nft_mint: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[POSITION_PREFIX, position_nft_mint.as_ref()], &cp_amm::ID).0
+}
+
+pub fn derive_position_nft_account(position_nft_mint: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[POSITION_NFT_ACCOUNT_PREFIX, position_nft_mint.as_ref()],
+        &cp_amm::ID,
+    )
+    .0
+}
+
+pub fn derive_token_vault(token_mint: Pubkey, pool: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[TOKEN_VAULT_PREFIX, token_mint.as_ref(), pool.as_ref()],
+        &cp_amm::ID,
+    )
+    .0
+}
+
+pub fn derive_referral_account(referrer: Pubkey, token_mint: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            REFERRAL_ACCOUNT_PREFIX,
+            referrer.as_ref(),
+            token_mint.as_ref(),
+        ],
+        &cp_amm::ID,
+    )
+    .0
+}
+
+pub fn derive_reward_vault(pool: Pubkey, reward_index: u8) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            REWARD_VAULT_PREFIX,
+            pool.as_ref(),
+            reward_index.to_le_bytes().as_ref(),
+        ],
+        &cp_amm::ID,
+    )
+    .0
+}
+
+pub fn derive_oracle(pool: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ORACLE_PREFIX, pool.as_ref()], &cp_amm::ID).0
+}
+
+pub fn derive_reward_extension(pool: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[REWARD_EXTENSION_PREFIX, pool.as_ref()], &cp_amm::ID).0
+}
+
+pub fn derive_position_reward_extension(position: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[POSITION_REWARD_EXTENSION_PREFIX, position.as_ref()],
+        &cp_amm::ID,
+    )
+    .0
+}
+
+/// Build a program instruction from its accounts and arguments
+pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: cp_amm::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Assert that the transaction failed with `error`
+pub fn assert_pool_error(result: std::result::Result<(), BanksClientError>, error: PoolError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, u32::from(error))
+        }
+        err => panic!("unexpected error {err:?}"),
+    }
+}
+
+/// Fees of the config every test can create pools from: 1% base fee, 20% of it to the protocol
+/// and 20% of the protocol fee to the referral
+pub fn default_pool_fees() -> PoolFeeParameters {
+    PoolFeeParameters {
+        base_fee: BaseFeeParameters {
+            cliff_fee_numerator: 10_000_000,
+            ..Default::default()
+        },
+        protocol_fee_percent: 20,
+        referral_fee_percent: 20,
+        ..Default::default()
+    }
+}
+
+/// Mint of a token program, optionally with a Token-2022 transfer fee
+#[derive(Clone, Copy)]
+pub struct TestMint {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+/// Addresses of a pool and of the position created along with it
+pub struct TestPool {
+    pub pool: Pubkey,
+    pub token_a: TestMint,
+    pub token_b: TestMint,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub position: TestPosition,
+}
+
+pub struct TestPosition {
+    pub position: Pubkey,
+    pub position_nft_mint: Pubkey,
+    pub position_nft_account: Pubkey,
+}
+
+pub struct TestContext {
+    pub context: ProgramTestContext,
+    /// Public config with `default_pool_fees`
+    pub config: Pubkey,
+    /// Event CPIs of the last processed transaction, in order
+    events: Vec<Vec<u8>>,
+    /// Return data of the last processed transaction
+    return_data: Option<Vec<u8>>,
+}
+
+impl TestContext {
+    pub async fn new() -> Self {
+        // the program is loaded from `cp_amm.so` in the output directory of `cargo build-sbf`
+        let mut program_test = ProgramTest::new("cp_amm", cp_amm::ID, None);
+
+        // configs can only be created by the admin, so the config is stored at genesis
+        let config = Pubkey::new_unique();
+        let mut config_state = Config::default();
+        config_state.init(
+            0,
+            default_pool_fees().to_pool_fees_config(&ExtraFeeParameters::default()),
+            Pubkey::default(),
+            Pubkey::default(),
+            1, // timestamp
+            MIN_SQRT_PRICE,
+            MAX_SQRT_PRICE,
+            CollectFeeMode::BothToken.into(),
+            Pubkey::default(),
+        );
+        let mut data = Config::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&config_state));
+        program_test.add_account(
+            config,
+            Account {
+                lamports: u32::MAX.into(),
+                data,
+                owner: cp_amm::ID,
+                ..Account::default()
+            },
+        );
+
+        let context = program_test.start_with_context().await;
+        Self {
+            context,
+            config,
+            events: Vec::new(),
+            return_data: None,
+        }
+    }
+
+    pub fn payer(&self) -> Keypair {
+        self.context.payer.insecure_clone()
+    }
+
+    /// Process the instructions in one transaction signed by the payer and `signers`
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<(), BanksClientError> {
+        let payer = self.payer();
+        let mut all_signers = vec![&payer];
+        all_signers.extend_from_slice(signers);
+        let mut all_instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+            COMPUTE_UNIT_LIMIT,
+        )];
+        all_instructions.extend_from_slice(instructions);
+        let recent_blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &all_instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            recent_blockhash,
+        );
+
+        // events are self CPIs of the program, only a simulation returns the inner instructions
+        let simulation_details = self
+            .context
+            .banks_client
+            .simulate_transaction(transaction.clone())
+            .await?
+            .simulation_details;
+        let account_keys = &transaction.message.account_keys;
+        self.events = simulation_details
+            .as_ref()
+            .and_then(|details| details.inner_instructions.as_ref())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|inner_instruction| {
+                account_keys[usize::from(inner_instruction.instruction.program_id_index)]
+                    == cp_amm::ID
+            })
+            .filter_map(|inner_instruction| {
+                inner_instruction
+                    .instruction
+                    .data
+                    .strip_prefix(EVENT_IX_TAG_LE)
+                    .map(<[u8]>::to_vec)
+            })
+            .collect();
+        self.return_data = simulation_details
+            .and_then(|details| details.return_data)
+            .filter(|return_data| return_data.program_id == cp_amm::ID)
+            .map(|return_data| return_data.data);
+
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    /// Events emitted by the last processed transaction
+    pub fn events<T: AnchorDeserialize + Discriminator>(&self) -> Vec<T> {
+        self.events
+            .iter()
+            .filter_map(|event| event.strip_prefix(T::DISCRIMINATOR))
+            .map(|mut data| T::deserialize(&mut data).unwrap())
+            .collect()
+    }
+
+    /// Value returned by the last processed transaction
+    pub fn return_data<T: AnchorDeserialize>(&self) -> T {
+        T::deserialize(&mut self.return_data.as_deref().unwrap()).unwrap()
+    }
+
+    pub async fn get_account(&mut self, address: Pubkey) -> Account {
+        self.context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    /// State of a zero copy program account
+    pub async fn get_state<T: Pod + Discriminator>(&mut self, address: Pubkey) -> T {
+        let account = self.get_account(address).await;
+        let data = account.data.strip_prefix(T::DISCRIMINATOR).unwrap();
+        bytemuck::pod_read_unaligned(&data[..std::mem::size_of::<T>()])
+    }
+
+    /// Overwrite a zero copy program account, for state only the admin can set up
+    pub fn set_state<T: Pod + Discriminator>(&mut self, address: Pubkey, state: &T) {
+        let mut data = T::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(state));
+        self.context.set_account(
+            &address,
+            &Account {
+                lamports: u32::MAX.into(),
+                data,
+                owner: cp_amm::ID,
+                ..Account::default()
+            }
+            .into(),
+        );
+    }
+
+    pub async fn get_token_balance(&mut self, token_account: Pubkey) -> u64 {
+        let account = self.get_account(token_account).await;
+        TokenAccount::unpack_from_slice(&account.data[..TokenAccount::LEN])
+            .unwrap()
+            .amount
+    }
+
+    /// Move the clock to `unix_timestamp`. The slot moves too, so a transaction repeated after
+    /// the clock change gets a new blockhash instead of being deduplicated
+    pub async fn set_timestamp(&mut self, unix_timestamp: i64) {
+        let slot = self.context.banks_client.get_root_slot().await.unwrap();
+        self.context.warp_to_slot(slot + 1).unwrap();
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn get_timestamp(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    pub async fn create_user(&mut self) -> Keypair {
+        let user = Keypair::new();
+        let payer = self.payer();
+        self.process(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &user.pubkey(),
+                10_000_000_000,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        user
+    }
+
+    /// Create a mint with the payer as authority. Only Token-2022 mints can have a transfer fee
+    pub async fn create_mint(
+        &mut self,
+        token_program: Pubkey,
+        transfer_fee_basis_points: Option<u16>,
+    ) -> TestMint {
+        let mint = Keypair::new();
+        let payer = self.payer();
+        let extensions: &[ExtensionType] = if transfer_fee_basis_points.is_some() {
+            &[ExtensionType::TransferFeeConfig]
+        } else {
+            &[]
+        };
+        let space =
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extensions)
+                .unwrap();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+
+        let mut instructions = vec![system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &token_program,
+        )];
+        if let Some(transfer_fee_basis_points) = transfer_fee_basis_points {
+            instructions.push(
+                initialize_transfer_fee_config(
+                    &token_program,
+                    &mint.pubkey(),
+                    Some(&payer.pubkey()),
+                    Some(&payer.pubkey()),
+                    transfer_fee_basis_points,
+                    u64::MAX,
+                )
+                .unwrap(),
+            );
+        }
+        instructions.push(
+            spl_token_2022::instruction::initialize_mint2(
+                &token_program,
+                &mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                6,
+            )
+            .unwrap(),
+        );
+        self.process(&instructions, &[&mint]).await.unwrap();
+
+        TestMint {
+            mint: mint.pubkey(),
+            token_program,
+        }
+    }
+
+    /// Associated token account of `owner`, created on first use
+    pub async fn get_or_create_token_account(&mut self, owner: Pubkey, mint: TestMint) -> Pubkey {
+        let payer = self.payer();
+        self.process(
+            &[create_associated_token_account_idempotent(
+                &payer.pubkey(),
+                &owner,
+                &mint.mint,
+                &mint.token_program,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        get_associated_token_address_with_program_id(&owner, &mint.mint, &mint.token_program)
+    }
+
+    /// Mint `amount` to the associated token account of `owner`
+    pub async fn mint_to(&mut self, owner: Pubkey, mint: TestMint, amount: u64) -> Pubkey {
+        let token_account = self.get_or_create_token_account(owner, mint).await;
+        let payer = self.payer();
+        self.process(
+            &[spl_token_2022::instruction::mint_to(
+                &mint.token_program,
+                &mint.mint,
+                &token_account,
+                &payer.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap()],
+            &[],
+        )
+        .await
+        .unwrap();
+        token_account
+    }
+
+    /// Create a pool from the default config, with the initial position owned by the payer
+    pub async fn initialize_pool(&mut self, token_a: TestMint, token_b: TestMint) -> TestPool {
+        let payer = self.payer();
+        self.initialize_pool_with_creator(token_a, token_b, payer.pubkey())
+            .await
+    }
+
+    /// Create a pool from the default config, with the initial position owned by `creator`. The creator
+    /// only signs when it is the payer
+    pub async fn initialize_pool_with_creator(
+        &mut self,
+        token_a: TestMint,
+        token_b: TestMint,
+        creator: Pubkey,
+    ) -> TestPool {
+        let payer = self.payer();
+        let payer_token_a = self.mint_to(payer.pubkey(), token_a, INITIAL_BALANCE).await;
+        let payer_token_b = self.mint_to(payer.pubkey(), token_b, INITIAL_BALANCE).await;
+
+        let pool = derive_pool(self.config, token_a.mint, token_b.mint);
+        let position_nft_mint = Keypair::new();
+        let position = TestPosition::new(position_nft_mint.pubkey());
+        let token_a_vault = derive_token_vault(token_a.mint, pool);
+        let token_b_vault = derive_token_vault(token_b.mint, pool);
+
+        let ix = instruction(
+            cp_amm::accounts::InitializePoolCtx {
+                creator,
+                position_nft_mint: position.position_nft_mint,
+                position_nft_account: position.position_nft_account,
+                payer: payer.pubkey(),
+                config: self.config,
+                pool_authority: pool_authority(),
+                pool,
+                position: position.position,
+                token_a_mint: token_a.mint,
+                token_b_mint: token_b.mint,
+                token_a_vault,
+                token_b_vault,
+                payer_token_a,
+                payer_token_b,
+                token_a_program: token_a.token_program,
+                token_b_program: token_b.token_program,
+                token_2022_program: spl_token_2022::ID,
+                system_program: anchor_lang::system_program::ID,
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            cp_amm::instruction::InitializePool {
+                params: InitializePoolParameters {
+                    liquidity: DEFAULT_LIQUIDITY,
+                    sqrt_price: DEFAULT_SQRT_PRICE,
+                    activation_point: None,
+                },
+            },
+        );
+        self.process(&[ix], &[&position_nft_mint]).await.unwrap();
+
+        TestPool {
+            pool,
+            token_a,
+            token_b,
+            token_a_vault,
+            token_b_vault,
+            position,
+        }
+    }
+
+    /// Create an empty position in `pool` owned by `owner`
+    pub async fn create_position(&mut self, pool: Pubkey, owner: Pubkey) -> TestPosition {
+        let payer = self.payer();
+        let position_nft_mint = Keypair::new();
+        let position = TestPosition::new(position_nft_mint.pubkey());
+        let ix = instruction(
+            cp_amm::accounts::CreatePositionCtx {
+                owner,
+                position_nft_mint: position.position_nft_mint,
+                position_nft_account: position.position_nft_account,
+                pool,
+                position: position.position,
+                pool_authority: pool_authority(),
+                payer: payer.pubkey(),
+                token_program: spl_token_2022::ID,
+                system_program: anchor_lang::system_program::ID,
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            cp_amm::instruction::CreatePosition {},
+        );
+        self.process(&[ix], &[&position_nft_mint]).await.unwrap();
+        position
+    }
+
+    /// Swap instruction of `user` with its associated token accounts, `data` selects the variant
+    pub fn swap_instruction(
+        &self,
+        test_pool: &TestPool,
+        user: Pubkey,
+        a_to_b: bool,
+        data: impl InstructionData,
+    ) -> Instruction {
+        self.swap_instruction_with_accounts(test_pool, user, a_to_b, None, None, data)
+    }
+
+    /// Swap instruction accruing the referral fee to `referral_account` and recording the price to `oracle`
+    pub fn swap_instruction_with_accounts(
+        &self,
+        test_pool: &TestPool,
+        user: Pubkey,
+        a_to_b: bool,
+        referral_account: Option<Pubkey>,
+        oracle: Option<Pubkey>,
+        data: impl InstructionData,
+    ) -> Instruction {
+        let (input, output) = if a_to_b {
+            (test_pool.token_a, test_pool.token_b)
+        } else {
+            (test_pool.token_b, test_pool.token_a)
+        };
+        instruction(
+            cp_amm::accounts::SwapCtx {
+                pool_authority: pool_authority(),
+                pool: test_pool.pool,
+                input_token_account: get_associated_token_address_with_program_id(
+                    &user,
+                    &input.mint,
+                    &input.token_program,
+                ),
+                output_token_account: get_associated_token_address_with_program_id(
+                    &user,
+                    &output.mint,
+                    &output.token_program,
+                ),
+                token_a_vault: test_pool.token_a_vault,
+                token_b_vault: test_pool.token_b_vault,
+                token_a_mint: test_pool.token_a.mint,
+                token_b_mint: test_pool.token_b.mint,
+                payer: user,
+                token_a_program: test_pool.token_a.token_program,
+                token_b_program: test_pool.token_b.token_program,
+                referral_token_account: None,
+                referral_account,
+                oracle,
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            data,
+        )
+    }
+
+    pub async fn swap(
+        &mut self,
+        test_pool: &TestPool,
+        user: &Keypair,
+        a_to_b: bool,
+        params: SwapParameters,
+    ) -> std::result::Result<(), BanksClientError> {
+        let ix = self.swap_instruction(
+            test_pool,
+            user.pubkey(),
+            a_to_b,
+            cp_amm::instruction::Swap { params },
+        );
+        self.process(&[ix], &[user]).await
+    }
+
+    /// Add liquidity instruction of `owner` with its associated token accounts, `data` selects the variant
+    pub fn add_liquidity_instruction(
+        &self,
+        test_pool: &TestPool,
+        position: &TestPosition,
+        owner: Pubkey,
+        data: impl InstructionData,
+    ) -> Instruction {
+        instruction(
+            cp_amm::accounts::AddLiquidityCtx {
+                pool: test_pool.pool,
+                position: position.position,
+                token_a_account: get_associated_token_address_with_program_id(
+                    &owner,
+                    &test_pool.token_a.mint,
+                    &test_pool.token_a.token_program,
+                ),
+                token_b_account: get_associated_token_address_with_program_id(
+                    &owner,
+                    &test_pool.token_b.mint,
+                    &test_pool.token_b.token_program,
+                ),
+                token_a_vault: test_pool.token_a_vault,
+                token_b_vault: test_pool.token_b_vault,
+                token_a_mint: test_pool.token_a.mint,
+                token_b_mint: test_pool.token_b.mint,
+                position_nft_account: position.position_nft_account,
+                owner,
+                token_a_program: test_pool.token_a.token_program,
+                token_b_program: test_pool.token_b.token_program,
+                reward_extension: None,
+                position_reward_extension: None,
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            data,
+        )
+    }
+
+    pub async fn add_liquidity(
+        &mut self,
+        test_pool: &TestPool,
+        position: &TestPosition,
+        owner: &Keypair,
+        params: AddLiquidityParameters,
+    ) -> std::result::Result<(), BanksClientError> {
+        let ix = self.add_liquidity_instruction(
+            test_pool,
+            position,
+            owner.pubkey(),
+            cp_amm::instruction::AddLiquidity { params },
+        );
+        self.process(&[ix], &[owner]).await
+    }
+
+    /// Remove liquidity instruction of `owner` with its associated token accounts, `data` selects the variant
+    pub fn remove_liquidity_instruction(
+        &self,
+        test_pool: &TestPool,
+        position: &TestPosition,
+        owner: Pubkey,
+        data: impl InstructionData,
+    ) -> Instruction {
+        instruction(
+            cp_amm::accounts::RemoveLiquidityCtx {
+                pool_authority: pool_authority(),
+                pool: test_pool.pool,
+                position: position.position,
+                token_a_account: get_associated_token_address_with_program_id(
+                    &owner,
+                    &test_pool.token_a.mint,
+                    &test_pool.token_a.token_program,
+                ),
+                token_b_account: get_associated_token_address_with_program_id(
+                    &owner,
+                    &test_pool.token_b.mint,
+                    &test_pool.token_b.token_program,
+                ),
+                token_a_vault: test_pool.token_a_vault,
+                token_b_vault: test_pool.token_b_vault,
+                token_a_mint: test_pool.token_a.mint,
+                token_b_mint: test_pool.token_b.mint,
+                position_nft_account: position.position_nft_account,
+                owner,
+                token_a_program: test_pool.token_a.token_program,
+                token_b_program: test_pool.token_b.token_program,
+                reward_extension: None,
+                position_reward_extension: None,
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            data,
+        )
+    }
+
+    pub async fn remove_liquidity(
+        &mut self,
+        test_pool: &TestPool,
+        position: &TestPosition,
+        owner: &Keypair,
+        params: RemoveLiquidityParameters,
+    ) -> std::result::Result<(), BanksClientError> {
+        let ix = self.remove_liquidity_instruction(
+            test_pool,
+            position,
+            owner.pubkey(),
+            cp_amm::instruction::RemoveLiquidity { params },
+        );
+        self.process(&[ix], &[owner]).await
+    }
+
+    pub async fn claim_position_fee(
+        &mut self,
+        test_pool: &TestPool,
+        position: &TestPosition,
+        owner: &Keypair,
+    ) -> std::result::Result<(), BanksClientError> {
+        let ix = instruction(
+            cp_amm::accounts::ClaimPositionFeeCtx {
+                pool_authority: pool_authority(),
+                pool: test_pool.pool,
+                position: position.position,
+                token_a_account: get_associated_token_address_with_program_id(
+                    &owner.pubkey(),
+                    &test_pool.token_a.mint,
+                    &test_pool.token_a.token_program,
+                ),
+                token_b_account: get_associated_token_address_with_program_id(
+                    &owner.pubkey(),
+                    &test_pool.token_b.mint,
+                    &test_pool.token_b.token_program,
+                ),
+                token_a_vault: test_pool.token_a_vault,
+                token_b_vault: test_pool.token_b_vault,
+                token_a_mint: test_pool.token_a.mint,
+                token_b_mint: test_pool.token_b.mint,
+                position_nft_account: position.position_nft_account,
+                owner: owner.pubkey(),
+                token_a_program: test_pool.token_a.token_program,
+                token_b_program: test_pool.token_b.token_program,
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            cp_amm::instruction::ClaimPositionFee {},
+        );
+        self.process(&[ix], &[owner]).await
+    }
+}
+
+impl TestPosition {
+    pub fn new(position_nft_mint: Pubkey) -> Self {
+        Self {
+            position: derive_position(position_nft_mint),
+            position_nft_mint,
+            position_nft_account: derive_position_nft_account(position_nft_mint),
+        }
+    }
+}
+
+pub fn spl_token_program() -> Pubkey {
+    spl_token::ID
+}
+
+pub fn token_2022_program() -> Pubkey {
+    spl_token_2022::ID
+}
@@ -0,0 +1,14 @@
+//! Integration tests running the compiled program in an in-process SVM with `solana-program-test`.
+//! `cargo test-sbf` builds `cp_amm.so` and enables the `test-sbf` feature, the admin tests need the local build:
+//! `cargo test-sbf --features local`
+#![cfg(feature = "test-sbf")]
+
+mod harness;
+#[cfg(feature = "local")]
+mod test_admin;
+mod test_initialize_pool;
+mod test_liquidity;
+mod test_referral;
+mod test_reward;
+mod test_swap;
+mod test_vesting;
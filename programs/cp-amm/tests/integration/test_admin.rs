@@ -0,0 +1,726 @@
+//! Admin and partner instructions. The local build accepts any signer as admin
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_2022::spl_token_2022::{self, extension::ExtensionType},
+};
+use cp_amm::{
+    constants::{
+        seeds::{CLAIM_FEE_OPERATOR_PREFIX, CONFIG_PREFIX, TOKEN_BADGE_PREFIX},
+        MAX_SQRT_PRICE, MIN_SQRT_PRICE, NUM_REWARDS,
+    },
+    params::fee_parameters::{BaseFeeParameters, ExtraFeeParameters, PoolFeeParameters},
+    state::{
+        ClaimFeeOperator, CollectFeeMode, Config, Oracle, Pool, PoolStatus, RewardExtension,
+        TokenBadge,
+    },
+    ConfigParameters, EvtClaimPartnerFee, EvtClaimProtocolFee, EvtCloseClaimFeeOperator,
+    EvtCloseConfig, EvtCreateClaimFeeOperator, EvtCreateConfig, EvtCreateTokenBadge,
+    EvtInitializeOracle, EvtInitializeReward, EvtInitializeRewardExtension, EvtSetPoolStatus,
+    EvtUpdateConfig, EvtUpdatePoolFees, EvtUpdateProtocolFeeReceiver, EvtUpdateRewardDuration,
+    EvtUpdateRewardFunder, PoolError, SwapParameters,
+};
+use solana_sdk::{
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+
+use crate::harness::*;
+
+fn derive_config(index: u64) -> Pubkey {
+    Pubkey::find_program_address(&[CONFIG_PREFIX, &index.to_le_bytes()], &cp_amm::ID).0
+}
+
+fn derive_token_badge(token_mint: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[TOKEN_BADGE_PREFIX, token_mint.as_ref()], &cp_amm::ID).0
+}
+
+fn derive_claim_fee_operator(operator: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CLAIM_FEE_OPERATOR_PREFIX, operator.as_ref()], &cp_amm::ID).0
+}
+
+/// Parameters of a full range config with the default fees
+fn config_parameters(index: u64, pool_creator_authority: Pubkey) -> ConfigParameters {
+    ConfigParameters {
+        pool_fees: default_pool_fees(),
+        sqrt_min_price: MIN_SQRT_PRICE,
+        sqrt_max_price: MAX_SQRT_PRICE,
+        vault_config_key: Pubkey::default(),
+        pool_creator_authority,
+        activation_type: 1, // timestamp
+        collect_fee_mode: CollectFeeMode::BothToken.into(),
+        index,
+        protocol_fee_receiver: Pubkey::default(),
+    }
+}
+
+fn create_config_accounts(config: Pubkey, admin: Pubkey) -> cp_amm::accounts::CreateConfigCtx {
+    cp_amm::accounts::CreateConfigCtx {
+        config,
+        admin,
+        system_program: anchor_lang::system_program::ID,
+        event_authority: event_authority(),
+        program: cp_amm::ID,
+    }
+}
+
+/// Remaining accounts of claim_protocol_fee_batch claiming the pool to the token accounts of `receiver`
+fn claim_protocol_fee_remaining_accounts(
+    test_pool: &TestPool,
+    receiver: Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(test_pool.pool, false),
+        AccountMeta::new(test_pool.token_a_vault, false),
+        AccountMeta::new(test_pool.token_b_vault, false),
+        AccountMeta::new_readonly(test_pool.token_a.mint, false),
+        AccountMeta::new_readonly(test_pool.token_b.mint, false),
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                &receiver,
+                &test_pool.token_a.mint,
+                &test_pool.token_a.token_program,
+            ),
+            false,
+        ),
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                &receiver,
+                &test_pool.token_b.mint,
+                &test_pool.token_b.token_program,
+            ),
+            false,
+        ),
+        AccountMeta::new_readonly(test_pool.token_a.token_program, false),
+        AccountMeta::new_readonly(test_pool.token_b.token_program, false),
+    ]
+}
+
+/// Trade both ways so the pool accrues protocol and partner fees in both tokens
+async fn swap_both_ways(context: &mut TestContext, test_pool: &TestPool) {
+    let user = context.create_user().await;
+    context
+        .mint_to(user.pubkey(), test_pool.token_a, 10_000_000)
+        .await;
+    context
+        .mint_to(user.pubkey(), test_pool.token_b, 10_000_000)
+        .await;
+    for a_to_b in [true, false] {
+        let params = SwapParameters {
+            amount_in: 1_000_000,
+            minimum_amount_out: 0,
+        };
+        context
+            .swap(test_pool, &user, a_to_b, params)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_config_and_partner_fee() {
+    let mut context = TestContext::new().await;
+    let payer = context.payer();
+    let config = derive_config(1);
+
+    // the payer is the partner, the only one allowed to create pools from the config
+    let mut params = config_parameters(1, payer.pubkey());
+    params.pool_fees.partner_fee_percent = 50;
+    let ix = instruction(
+        create_config_accounts(config, payer.pubkey()),
+        cp_amm::instruction::CreateConfig {
+            config_parameters: params,
+        },
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtCreateConfig>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].config, config);
+    assert_eq!(events[0].pool_creator_authority, payer.pubkey());
+    let config_state: Config = context.get_state(config).await;
+    assert_eq!(config_state.index, 1);
+    assert_eq!(config_state.pool_fees.partner_fee_percent, 50);
+
+    let new_pool_fees = PoolFeeParameters {
+        base_fee: BaseFeeParameters {
+            cliff_fee_numerator: 20_000_000,
+            ..Default::default()
+        },
+        protocol_fee_percent: 20,
+        partner_fee_percent: 50,
+        referral_fee_percent: 20,
+        ..Default::default()
+    };
+    let ix = instruction(
+        cp_amm::accounts::UpdateConfigCtx {
+            config,
+            admin: payer.pubkey(),
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::UpdateConfig {
+            pool_fees: new_pool_fees,
+            extra_fees: ExtraFeeParameters::default(),
+        },
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtUpdateConfig>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].old_pool_fees.base_fee.cliff_fee_numerator,
+        10_000_000
+    );
+    assert_eq!(
+        events[0].new_pool_fees.base_fee.cliff_fee_numerator,
+        20_000_000
+    );
+    let config_state: Config = context.get_state(config).await;
+    assert_eq!(
+        config_state.pool_fees.base_fee.cliff_fee_numerator,
+        20_000_000
+    );
+
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    context.config = config;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.partner, payer.pubkey());
+    assert_eq!(pool.pool_fees.base_fee.cliff_fee_numerator, 20_000_000);
+
+    swap_both_ways(&mut context, &test_pool).await;
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert!(pool.partner_a_fee > 0 && pool.partner_b_fee > 0);
+
+    let payer_token_a = context
+        .get_or_create_token_account(payer.pubkey(), token_a)
+        .await;
+    let payer_token_b = context
+        .get_or_create_token_account(payer.pubkey(), token_b)
+        .await;
+    let payer_a_balance = context.get_token_balance(payer_token_a).await;
+    let payer_b_balance = context.get_token_balance(payer_token_b).await;
+    let ix = instruction(
+        cp_amm::accounts::ClaimPartnerFeesCtx {
+            pool_authority: pool_authority(),
+            pool: test_pool.pool,
+            token_a_account: payer_token_a,
+            token_b_account: payer_token_b,
+            token_a_vault: test_pool.token_a_vault,
+            token_b_vault: test_pool.token_b_vault,
+            token_a_mint: token_a.mint,
+            token_b_mint: token_b.mint,
+            partner: payer.pubkey(),
+            token_a_program: token_a.token_program,
+            token_b_program: token_b.token_program,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::ClaimPartnerFee {
+            max_amount_a: u64::MAX,
+            max_amount_b: u64::MAX,
+        },
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtClaimPartnerFee>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.token_a_amount, pool.partner_a_fee);
+    assert_eq!(event.token_b_amount, pool.partner_b_fee);
+    assert_eq!(
+        context.get_token_balance(payer_token_a).await - payer_a_balance,
+        pool.partner_a_fee
+    );
+    assert_eq!(
+        context.get_token_balance(payer_token_b).await - payer_b_balance,
+        pool.partner_b_fee
+    );
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.partner_a_fee, 0);
+    assert_eq!(pool.partner_b_fee, 0);
+
+    let rent_receiver = Pubkey::new_unique();
+    let ix = instruction(
+        cp_amm::accounts::CloseConfigCtx {
+            config,
+            admin: payer.pubkey(),
+            rent_receiver,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::CloseConfig {},
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtCloseConfig>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].config, config);
+    let config_account = context
+        .context
+        .banks_client
+        .get_account(config)
+        .await
+        .unwrap();
+    assert!(config_account.is_none());
+    assert!(context.get_account(rent_receiver).await.lamports > 0);
+}
+
+#[tokio::test]
+async fn test_create_config_with_extra_fees() {
+    let mut context = TestContext::new().await;
+    let payer = context.payer();
+    let config = derive_config(2);
+
+    let ix = instruction(
+        create_config_accounts(config, payer.pubkey()),
+        cp_amm::instruction::CreateConfigWithExtraFees {
+            config_parameters: config_parameters(2, Pubkey::default()),
+            extra_fees: ExtraFeeParameters {
+                a_to_b_fee_multiplier_bps: Some(20_000),
+                ..Default::default()
+            },
+        },
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    assert_eq!(context.events::<EvtCreateConfig>().len(), 1);
+    let config_state: Config = context.get_state(config).await;
+    assert_eq!(config_state.pool_fees.a_to_b_fee_multiplier_bps, 20_000);
+
+    // pools created from the config inherit the extra fees
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    context.config = config;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.pool_fees.a_to_b_fee_multiplier_bps, 20_000);
+}
+
+#[tokio::test]
+async fn test_create_token_badge() {
+    let mut context = TestContext::new().await;
+    let payer = context.payer();
+    let supported_mint = context.create_mint(token_2022_program(), Some(100)).await;
+
+    // the mint close authority extension isn't supported without a token badge
+    let mint = Keypair::new();
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+        ExtensionType::MintCloseAuthority,
+    ])
+    .unwrap();
+    let rent = context.context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &spl_token_2022::ID,
+        ),
+        spl_token_2022::instruction::initialize_mint_close_authority(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            Some(&payer.pubkey()),
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &payer.pubkey(),
+            None,
+            6,
+        )
+        .unwrap(),
+    ];
+    context.process(&instructions, &[&mint]).await.unwrap();
+
+    let create_token_badge = |token_mint: Pubkey| {
+        instruction(
+            cp_amm::accounts::CreateTokenBadgeCtx {
+                token_badge: derive_token_badge(token_mint),
+                token_mint,
+                admin: payer.pubkey(),
+                system_program: anchor_lang::system_program::ID,
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            cp_amm::instruction::CreateTokenBadge {},
+        )
+    };
+    let result = context
+        .process(&[create_token_badge(supported_mint.mint)], &[])
+        .await;
+    assert_pool_error(result, PoolError::CannotCreateTokenBadgeOnSupportedMint);
+
+    context
+        .process(&[create_token_badge(mint.pubkey())], &[])
+        .await
+        .unwrap();
+    let events = context.events::<EvtCreateTokenBadge>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].token_mint, mint.pubkey());
+    let token_badge: TokenBadge = context.get_state(derive_token_badge(mint.pubkey())).await;
+    assert_eq!(token_badge.token_mint, mint.pubkey());
+}
+
+#[tokio::test]
+async fn test_claim_protocol_fee() {
+    let mut context = TestContext::new().await;
+    let payer = context.payer();
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(token_2022_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+
+    let operator = context.create_user().await;
+    let claim_fee_operator = derive_claim_fee_operator(operator.pubkey());
+    let ix = instruction(
+        cp_amm::accounts::CreateClaimFeeOperatorCtx {
+            claim_fee_operator,
+            operator: operator.pubkey(),
+            admin: payer.pubkey(),
+            system_program: anchor_lang::system_program::ID,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::CreateClaimFeeOperator {},
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtCreateClaimFeeOperator>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].operator, operator.pubkey());
+    let operator_state: ClaimFeeOperator = context.get_state(claim_fee_operator).await;
+    assert_eq!(operator_state.operator, operator.pubkey());
+
+    // the protocol fee goes to the new receiver instead of the treasury
+    let receiver = Pubkey::new_unique();
+    let ix = instruction(
+        cp_amm::accounts::UpdateProtocolFeeReceiverCtx {
+            pool: test_pool.pool,
+            admin: payer.pubkey(),
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::UpdateProtocolFeeReceiver {
+            protocol_fee_receiver: receiver,
+        },
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtUpdateProtocolFeeReceiver>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].old_protocol_fee_receiver, Pubkey::default());
+    assert_eq!(events[0].new_protocol_fee_receiver, receiver);
+    let receiver_token_a = context.get_or_create_token_account(receiver, token_a).await;
+    let receiver_token_b = context.get_or_create_token_account(receiver, token_b).await;
+
+    swap_both_ways(&mut context, &test_pool).await;
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert!(pool.protocol_a_fee > 0 && pool.protocol_b_fee > 0);
+    let ix = instruction(
+        cp_amm::accounts::ClaimProtocolFeesCtx {
+            pool_authority: pool_authority(),
+            pool: test_pool.pool,
+            token_a_vault: test_pool.token_a_vault,
+            token_b_vault: test_pool.token_b_vault,
+            token_a_mint: token_a.mint,
+            token_b_mint: token_b.mint,
+            token_a_account: Some(receiver_token_a),
+            token_b_account: Some(receiver_token_b),
+            claim_fee_operator,
+            operator: operator.pubkey(),
+            token_a_program: token_a.token_program,
+            token_b_program: token_b.token_program,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::ClaimProtocolFee {
+            max_amount_a: u64::MAX,
+            max_amount_b: u64::MAX,
+        },
+    );
+    context.process(&[ix], &[&operator]).await.unwrap();
+    let events = context.events::<EvtClaimProtocolFee>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].token_a_amount, pool.protocol_a_fee);
+    assert_eq!(events[0].token_b_amount, pool.protocol_b_fee);
+    assert_eq!(
+        context.get_token_balance(receiver_token_a).await,
+        pool.protocol_a_fee
+    );
+    assert_eq!(
+        context.get_token_balance(receiver_token_b).await,
+        pool.protocol_b_fee
+    );
+
+    // the batch claims every pool passed in the remaining accounts
+    swap_both_ways(&mut context, &test_pool).await;
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    let receiver_a_balance = context.get_token_balance(receiver_token_a).await;
+    let receiver_b_balance = context.get_token_balance(receiver_token_b).await;
+    let mut ix = instruction(
+        cp_amm::accounts::ClaimProtocolFeesBatchCtx {
+            pool_authority: pool_authority(),
+            claim_fee_operator,
+            operator: operator.pubkey(),
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::ClaimProtocolFeeBatch {},
+    );
+    ix.accounts
+        .extend(claim_protocol_fee_remaining_accounts(&test_pool, receiver));
+    context.process(&[ix], &[&operator]).await.unwrap();
+    let events = context.events::<EvtClaimProtocolFee>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].pool, test_pool.pool);
+    assert_eq!(
+        context.get_token_balance(receiver_token_a).await - receiver_a_balance,
+        pool.protocol_a_fee
+    );
+    assert_eq!(
+        context.get_token_balance(receiver_token_b).await - receiver_b_balance,
+        pool.protocol_b_fee
+    );
+
+    let ix = instruction(
+        cp_amm::accounts::CloseClaimFeeOperatorCtx {
+            claim_fee_operator,
+            rent_receiver: payer.pubkey(),
+            admin: payer.pubkey(),
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::CloseClaimFeeOperator {},
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtCloseClaimFeeOperator>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].claim_fee_operator, claim_fee_operator);
+    let operator_account = context
+        .context
+        .banks_client
+        .get_account(claim_fee_operator)
+        .await
+        .unwrap();
+    assert!(operator_account.is_none());
+}
+
+#[tokio::test]
+async fn test_set_pool_status_and_update_pool_fees() {
+    let mut context = TestContext::new().await;
+    let payer = context.payer();
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+
+    let set_pool_status = |status: PoolStatus| {
+        instruction(
+            cp_amm::accounts::SetPoolStatusCtx {
+                pool: test_pool.pool,
+                admin: payer.pubkey(),
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            cp_amm::instruction::SetPoolStatus {
+                status: status.into(),
+            },
+        )
+    };
+    context
+        .process(&[set_pool_status(PoolStatus::Disable)], &[])
+        .await
+        .unwrap();
+    let events = context.events::<EvtSetPoolStatus>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].status, u8::from(PoolStatus::Disable));
+    let params = SwapParameters {
+        amount_in: 1_000_000,
+        minimum_amount_out: 0,
+    };
+    let result = context.swap(&test_pool, &payer, true, params).await;
+    assert_pool_error(result, PoolError::PoolDisabled);
+
+    let result = context
+        .process(&[set_pool_status(PoolStatus::Disable)], &[])
+        .await;
+    assert_pool_error(result, PoolError::InvalidPoolStatus);
+    context
+        .process(&[set_pool_status(PoolStatus::Enable)], &[])
+        .await
+        .unwrap();
+
+    let mut new_pool_fees = default_pool_fees();
+    new_pool_fees.base_fee.cliff_fee_numerator = 30_000_000;
+    let ix = instruction(
+        cp_amm::accounts::UpdatePoolFeesCtx {
+            pool: test_pool.pool,
+            authority: payer.pubkey(),
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::UpdatePoolFees {
+            pool_fees: new_pool_fees,
+            extra_fees: ExtraFeeParameters::default(),
+        },
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtUpdatePoolFees>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.authority, payer.pubkey());
+    assert_eq!(event.old_pool_fees.base_fee.cliff_fee_numerator, 10_000_000);
+    assert_eq!(event.new_pool_fees.base_fee.cliff_fee_numerator, 30_000_000);
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.pool_fees.base_fee.cliff_fee_numerator, 30_000_000);
+
+    let params = SwapParameters {
+        amount_in: 1_000_000,
+        minimum_amount_out: 0,
+    };
+    context
+        .swap(&test_pool, &payer, true, params)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_initialize_oracle() {
+    let mut context = TestContext::new().await;
+    let payer = context.payer();
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+
+    let oracle = derive_oracle(test_pool.pool);
+    let ix = instruction(
+        cp_amm::accounts::InitializeOracleCtx {
+            pool: test_pool.pool,
+            oracle,
+            admin: payer.pubkey(),
+            system_program: anchor_lang::system_program::ID,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::InitializeOracle {},
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtInitializeOracle>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].oracle, oracle);
+    let oracle_state: Oracle = context.get_state(oracle).await;
+    assert_eq!(oracle_state.pool, test_pool.pool);
+    assert_eq!(oracle_state.observation_count, 1);
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.oracle_flag, 1);
+
+    // swaps have to record the price once the pool has an oracle
+    let params = SwapParameters {
+        amount_in: 1_000_000,
+        minimum_amount_out: 0,
+    };
+    let result = context.swap(&test_pool, &payer, true, params).await;
+    assert_pool_error(result, PoolError::InvalidOracleAccount);
+}
+
+#[tokio::test]
+async fn test_admin_reward() {
+    let mut context = TestContext::new().await;
+    let payer = context.payer();
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let reward_mint = context.create_mint(spl_token_program(), None).await;
+
+    let reward_extension = derive_reward_extension(test_pool.pool);
+    let ix = instruction(
+        cp_amm::accounts::InitializeRewardExtensionCtx {
+            pool: test_pool.pool,
+            reward_extension,
+            admin: payer.pubkey(),
+            system_program: anchor_lang::system_program::ID,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::InitializeRewardExtension {},
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtInitializeRewardExtension>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].reward_extension, reward_extension);
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.reward_extension_flag, 1);
+
+    // one reward in the pool and one in the reward extension
+    let reward_duration = 3_600;
+    for reward_index in [0, NUM_REWARDS as u8] {
+        let ix = instruction(
+            cp_amm::accounts::InitializeRewardCtx {
+                pool_authority: pool_authority(),
+                pool: test_pool.pool,
+                reward_vault: derive_reward_vault(test_pool.pool, reward_index),
+                reward_mint: reward_mint.mint,
+                admin: payer.pubkey(),
+                token_program: reward_mint.token_program,
+                system_program: anchor_lang::system_program::ID,
+                reward_extension: Some(reward_extension),
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            cp_amm::instruction::InitializeReward {
+                reward_index,
+                reward_duration,
+                funder: payer.pubkey(),
+            },
+        );
+        context.process(&[ix], &[]).await.unwrap();
+        let events = context.events::<EvtInitializeReward>();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].reward_index, reward_index);
+    }
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.reward_infos[0].mint, reward_mint.mint);
+    assert_eq!(pool.reward_infos[0].funder, payer.pubkey());
+    let reward_extension_state: RewardExtension = context.get_state(reward_extension).await;
+    assert_eq!(
+        reward_extension_state.reward_infos[0].mint,
+        reward_mint.mint
+    );
+
+    let new_funder = Pubkey::new_unique();
+    let ix = instruction(
+        cp_amm::accounts::UpdateRewardFunderCtx {
+            pool: test_pool.pool,
+            admin: payer.pubkey(),
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::UpdateRewardFunder {
+            reward_index: 0,
+            new_funder,
+        },
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtUpdateRewardFunder>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].old_funder, payer.pubkey());
+    assert_eq!(events[0].new_funder, new_funder);
+
+    let ix = instruction(
+        cp_amm::accounts::UpdateRewardDurationCtx {
+            pool: test_pool.pool,
+            admin: payer.pubkey(),
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::UpdateRewardDuration {
+            reward_index: 0,
+            new_duration: reward_duration * 2,
+        },
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtUpdateRewardDuration>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].old_reward_duration, reward_duration);
+    assert_eq!(events[0].new_reward_duration, reward_duration * 2);
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.reward_infos[0].funder, new_funder);
+    assert_eq!(pool.reward_infos[0].reward_duration, reward_duration * 2);
+}
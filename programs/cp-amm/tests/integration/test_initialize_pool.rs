@@ -0,0 +1,262 @@
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022::spl_token_2022;
+use cp_amm::{
+    constants::{MAX_SQRT_PRICE, MIN_SQRT_PRICE},
+    params::fee_parameters::{BaseFeeParameters, ExtraFeeParameters, PoolFeeParameters},
+    state::{CollectFeeMode, Pool, PoolType, Position},
+    EvtInitializePool, EvtSwap, InitializeCustomizablePoolParameters, PoolError, SwapParameters,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use crate::harness::*;
+
+/// Customizable pool of the token pair, with the initial position of `position_nft_mint`
+fn customizable_test_pool(
+    token_a: TestMint,
+    token_b: TestMint,
+    position_nft_mint: Pubkey,
+) -> TestPool {
+    let pool = derive_customizable_pool(token_a.mint, token_b.mint);
+    TestPool {
+        pool,
+        token_a,
+        token_b,
+        token_a_vault: derive_token_vault(token_a.mint, pool),
+        token_b_vault: derive_token_vault(token_b.mint, pool),
+        position: TestPosition::new(position_nft_mint),
+    }
+}
+
+/// Accounts to initialize a customizable pool created and paid by `payer`
+fn customizable_pool_accounts(
+    test_pool: &TestPool,
+    payer: Pubkey,
+) -> cp_amm::accounts::InitializeCustomizablePoolCtx {
+    let TestPool {
+        pool,
+        token_a,
+        token_b,
+        token_a_vault,
+        token_b_vault,
+        position,
+    } = test_pool;
+    cp_amm::accounts::InitializeCustomizablePoolCtx {
+        creator: payer,
+        position_nft_mint: position.position_nft_mint,
+        position_nft_account: position.position_nft_account,
+        payer,
+        pool_authority: pool_authority(),
+        pool: *pool,
+        position: position.position,
+        token_a_mint: token_a.mint,
+        token_b_mint: token_b.mint,
+        token_a_vault: *token_a_vault,
+        token_b_vault: *token_b_vault,
+        payer_token_a: get_associated_token_address_with_program_id(
+            &payer,
+            &token_a.mint,
+            &token_a.token_program,
+        ),
+        payer_token_b: get_associated_token_address_with_program_id(
+            &payer,
+            &token_b.mint,
+            &token_b.token_program,
+        ),
+        token_a_program: token_a.token_program,
+        token_b_program: token_b.token_program,
+        token_2022_program: spl_token_2022::ID,
+        system_program: anchor_lang::system_program::ID,
+        event_authority: event_authority(),
+        program: cp_amm::ID,
+    }
+}
+
+#[tokio::test]
+async fn test_initialize_pool() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let events = context.events::<EvtInitializePool>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+
+    let vault_a_balance = context.get_token_balance(test_pool.token_a_vault).await;
+    let vault_b_balance = context.get_token_balance(test_pool.token_b_vault).await;
+    assert!(vault_a_balance > 0 && vault_b_balance > 0);
+    assert_eq!(event.pool, test_pool.pool);
+    assert_eq!(event.token_a_mint, token_a.mint);
+    assert_eq!(event.token_b_mint, token_b.mint);
+    assert_eq!(event.liquidity, DEFAULT_LIQUIDITY);
+    assert_eq!(event.sqrt_price, DEFAULT_SQRT_PRICE);
+    assert_eq!(event.token_a_amount, vault_a_balance);
+    assert_eq!(event.token_b_amount, vault_b_balance);
+    assert_eq!(event.pool_type, PoolType::Permissionless as u8);
+
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.liquidity, DEFAULT_LIQUIDITY);
+    assert_eq!(pool.sqrt_price, DEFAULT_SQRT_PRICE);
+    assert_eq!(pool.token_a_mint, token_a.mint);
+    assert_eq!(pool.token_b_mint, token_b.mint);
+
+    let position: Position = context.get_state(test_pool.position.position).await;
+    assert_eq!(position.pool, test_pool.pool);
+    assert_eq!(position.unlocked_liquidity, DEFAULT_LIQUIDITY);
+    let nft_balance = context
+        .get_token_balance(test_pool.position.position_nft_account)
+        .await;
+    assert_eq!(nft_balance, 1);
+}
+
+#[tokio::test]
+async fn test_initialize_customizable_pool() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(token_2022_program(), Some(100)).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let payer = context.payer();
+    let payer_token_a = context
+        .mint_to(payer.pubkey(), token_a, INITIAL_BALANCE)
+        .await;
+    context
+        .mint_to(payer.pubkey(), token_b, INITIAL_BALANCE)
+        .await;
+
+    let position_nft_mint = Keypair::new();
+    let test_pool = customizable_test_pool(token_a, token_b, position_nft_mint.pubkey());
+    let pool = test_pool.pool;
+    // within the max activation duration of the local build too
+    let activation_point = context.get_timestamp().await as u64 + 20;
+
+    let ix = instruction(
+        customizable_pool_accounts(&test_pool, payer.pubkey()),
+        cp_amm::instruction::InitializeCustomizablePool {
+            params: InitializeCustomizablePoolParameters {
+                pool_fees: PoolFeeParameters {
+                    base_fee: BaseFeeParameters {
+                        cliff_fee_numerator: 5_000_000,
+                        ..Default::default()
+                    },
+                    protocol_fee_percent: 20,
+                    referral_fee_percent: 20,
+                    ..Default::default()
+                },
+                sqrt_min_price: MIN_SQRT_PRICE,
+                sqrt_max_price: MAX_SQRT_PRICE,
+                has_alpha_vault: false,
+                liquidity: DEFAULT_LIQUIDITY,
+                sqrt_price: DEFAULT_SQRT_PRICE,
+                activation_type: 1, // timestamp
+                collect_fee_mode: CollectFeeMode::OnlyB.into(),
+                activation_point: Some(activation_point),
+            },
+        },
+    );
+    context.process(&[ix], &[&position_nft_mint]).await.unwrap();
+
+    let events = context.events::<EvtInitializePool>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.pool, pool);
+    assert_eq!(event.pool_type, PoolType::Customizable as u8);
+    assert_eq!(event.activation_point, activation_point);
+    assert_eq!(event.collect_fee_mode, CollectFeeMode::OnlyB as u8);
+    // the payer pays the token A transfer fee on top of what the vault receives
+    let vault_a_balance = context.get_token_balance(test_pool.token_a_vault).await;
+    let payer_a_balance = context.get_token_balance(payer_token_a).await;
+    assert_eq!(event.token_a_amount, vault_a_balance);
+    assert_eq!(event.total_amount_a, INITIAL_BALANCE - payer_a_balance);
+    assert!(event.total_amount_a > event.token_a_amount);
+
+    let pool_state: Pool = context.get_state(pool).await;
+    assert_eq!(pool_state.pool_fees.base_fee.cliff_fee_numerator, 5_000_000);
+    assert_eq!(pool_state.collect_fee_mode, CollectFeeMode::OnlyB as u8);
+    assert_eq!(pool_state.activation_point, activation_point);
+
+    let params = SwapParameters {
+        amount_in: 1_000_000,
+        minimum_amount_out: 0,
+    };
+    let result = context.swap(&test_pool, &payer, true, params).await;
+    assert_pool_error(result, PoolError::PoolDisabled);
+
+    context.set_timestamp(activation_point as i64).await;
+    let params = SwapParameters {
+        amount_in: 2_000_000,
+        minimum_amount_out: 0,
+    };
+    context
+        .swap(&test_pool, &payer, true, params)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_initialize_customizable_pool_with_extra_fees() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let payer = context.payer();
+    context
+        .mint_to(payer.pubkey(), token_a, INITIAL_BALANCE)
+        .await;
+    context
+        .mint_to(payer.pubkey(), token_b, INITIAL_BALANCE)
+        .await;
+    let position_nft_mint = Keypair::new();
+    let test_pool = customizable_test_pool(token_a, token_b, position_nft_mint.pubkey());
+
+    let ix = instruction(
+        customizable_pool_accounts(&test_pool, payer.pubkey()),
+        cp_amm::instruction::InitializeCustomizablePoolWithExtraFees {
+            params: InitializeCustomizablePoolParameters {
+                pool_fees: PoolFeeParameters {
+                    base_fee: BaseFeeParameters {
+                        cliff_fee_numerator: 5_000_000,
+                        ..Default::default()
+                    },
+                    protocol_fee_percent: 20,
+                    referral_fee_percent: 20,
+                    ..Default::default()
+                },
+                sqrt_min_price: MIN_SQRT_PRICE,
+                sqrt_max_price: MAX_SQRT_PRICE,
+                has_alpha_vault: false,
+                liquidity: DEFAULT_LIQUIDITY,
+                sqrt_price: DEFAULT_SQRT_PRICE,
+                activation_type: 1, // timestamp
+                collect_fee_mode: CollectFeeMode::OnlyB.into(),
+                activation_point: None,
+            },
+            // a to b trades pay twice the base fee
+            extra_fees: ExtraFeeParameters {
+                a_to_b_fee_multiplier_bps: Some(20_000),
+                ..Default::default()
+            },
+        },
+    );
+    context.process(&[ix], &[&position_nft_mint]).await.unwrap();
+    let events = context.events::<EvtInitializePool>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].pool_type, PoolType::Customizable as u8);
+    let pool_state: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool_state.pool_fees.a_to_b_fee_multiplier_bps, 20_000);
+
+    let params = SwapParameters {
+        amount_in: 100_000,
+        minimum_amount_out: 0,
+    };
+    context
+        .swap(&test_pool, &payer, true, params)
+        .await
+        .unwrap();
+    // only token B collects fees, so the 1% fee is taken from the output
+    let events = context.events::<EvtSwap>();
+    assert_eq!(events.len(), 1);
+    let swap_result = &events[0].swap_result;
+    let trading_fee = swap_result.lp_fee + swap_result.protocol_fee;
+    assert!(((swap_result.output_amount + trading_fee) / 100).abs_diff(trading_fee) <= 1);
+}
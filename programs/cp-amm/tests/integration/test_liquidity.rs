@@ -0,0 +1,457 @@
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use cp_amm::{
+    state::{Pool, Position},
+    AddLiquidityByAmountsParameters, AddLiquidityParameters, EvtAddLiquidity, EvtClaimPositionFee,
+    EvtMergePositions, EvtRemoveLiquidity, EvtSwap, PoolError, RemoveLiquidityParameters,
+    RemoveLiquiditySingleTokenParameters, SwapParameters, ZapInParameters,
+};
+use solana_sdk::signature::Signer;
+
+use crate::harness::*;
+
+#[tokio::test]
+async fn test_add_liquidity_claim_fee_and_remove_liquidity() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(token_2022_program(), Some(100)).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+
+    let user = context.create_user().await;
+    let user_token_a = context
+        .mint_to(user.pubkey(), token_a, INITIAL_BALANCE)
+        .await;
+    let user_token_b = context
+        .mint_to(user.pubkey(), token_b, INITIAL_BALANCE)
+        .await;
+    let position = context.create_position(test_pool.pool, user.pubkey()).await;
+
+    let params = AddLiquidityParameters {
+        liquidity_delta: DEFAULT_LIQUIDITY,
+        token_a_amount_threshold: u64::MAX,
+        token_b_amount_threshold: u64::MAX,
+    };
+    context
+        .add_liquidity(&test_pool, &position, &user, params)
+        .await
+        .unwrap();
+    let events = context.events::<EvtAddLiquidity>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.position, position.position);
+    assert_eq!(event.params.liquidity_delta, DEFAULT_LIQUIDITY);
+    let user_a_balance = context.get_token_balance(user_token_a).await;
+    let user_b_balance = context.get_token_balance(user_token_b).await;
+    assert_eq!(INITIAL_BALANCE - user_a_balance, event.total_amount_a);
+    assert_eq!(INITIAL_BALANCE - user_b_balance, event.total_amount_b);
+    assert!(event.total_amount_b > event.token_b_amount);
+
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.liquidity, DEFAULT_LIQUIDITY * 2);
+
+    // trade both ways so the position earns fees in both tokens
+    let swapper = context.create_user().await;
+    context
+        .mint_to(swapper.pubkey(), token_a, 100_000_000)
+        .await;
+    context
+        .mint_to(swapper.pubkey(), token_b, 100_000_000)
+        .await;
+    for a_to_b in [true, false] {
+        let params = SwapParameters {
+            amount_in: 50_000_000,
+            minimum_amount_out: 0,
+        };
+        context
+            .swap(&test_pool, &swapper, a_to_b, params)
+            .await
+            .unwrap();
+    }
+
+    context
+        .claim_position_fee(&test_pool, &position, &user)
+        .await
+        .unwrap();
+    let events = context.events::<EvtClaimPositionFee>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.position, position.position);
+    assert_eq!(event.owner, user.pubkey());
+    assert!(event.fee_a_claimed > 0 && event.fee_b_claimed > 0);
+    let claimed_a = context.get_token_balance(user_token_a).await - user_a_balance;
+    let claimed_b = context.get_token_balance(user_token_b).await - user_b_balance;
+    assert_eq!(claimed_a, event.fee_a_claimed);
+    // the claimed token B pays its transfer fee on the way out
+    assert_eq!(
+        claimed_b,
+        event.fee_b_claimed - event.fee_b_claimed.div_ceil(100)
+    );
+
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(position_state.fee_a_pending, 0);
+    assert_eq!(position_state.fee_b_pending, 0);
+    assert_eq!(
+        position_state.metrics.total_claimed_a_fee,
+        event.fee_a_claimed
+    );
+    assert_eq!(
+        position_state.metrics.total_claimed_b_fee,
+        event.fee_b_claimed
+    );
+
+    let user_a_balance = context.get_token_balance(user_token_a).await;
+    let user_b_balance = context.get_token_balance(user_token_b).await;
+    let params = RemoveLiquidityParameters {
+        liquidity_delta: DEFAULT_LIQUIDITY,
+        token_a_amount_threshold: 0,
+        token_b_amount_threshold: 0,
+    };
+    context
+        .remove_liquidity(&test_pool, &position, &user, params)
+        .await
+        .unwrap();
+    let events = context.events::<EvtRemoveLiquidity>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert!(event.token_a_amount > 0 && event.token_b_amount > 0);
+    let removed_a = context.get_token_balance(user_token_a).await - user_a_balance;
+    let removed_b = context.get_token_balance(user_token_b).await - user_b_balance;
+    assert_eq!(removed_a, event.token_a_amount);
+    assert_eq!(
+        removed_b,
+        event.token_b_amount - event.token_b_amount.div_ceil(100)
+    );
+
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(position_state.unlocked_liquidity, 0);
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.liquidity, DEFAULT_LIQUIDITY);
+}
+
+#[tokio::test]
+async fn test_add_liquidity_by_amounts() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(token_2022_program(), Some(100)).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+
+    let user = context.create_user().await;
+    let user_token_a = context.mint_to(user.pubkey(), token_a, 10_000_000).await;
+    let user_token_b = context.mint_to(user.pubkey(), token_b, 10_000_000).await;
+    let position = context.create_position(test_pool.pool, user.pubkey()).await;
+
+    let [exceeded_slippage_ix, ix] = [u128::MAX, 0].map(|minimum_liquidity_delta| {
+        context.add_liquidity_instruction(
+            &test_pool,
+            &position,
+            user.pubkey(),
+            cp_amm::instruction::AddLiquidityByAmounts {
+                params: AddLiquidityByAmountsParameters {
+                    max_amount_token_a: 1_000_000,
+                    max_amount_token_b: 2_000_000,
+                    minimum_liquidity_delta,
+                },
+            },
+        )
+    });
+    let result = context.process(&[exceeded_slippage_ix], &[&user]).await;
+    assert_pool_error(result, PoolError::ExceededSlippage);
+
+    context.process(&[ix], &[&user]).await.unwrap();
+    let events = context.events::<EvtAddLiquidity>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert!(event.params.liquidity_delta > 0);
+    // token A limits the liquidity at a price of 1, token B is only taken in proportion
+    assert!(event.total_amount_a <= 1_000_000);
+    assert!(event.total_amount_b < 2_000_000);
+    assert!(event.token_b_amount <= event.token_a_amount + 1);
+    assert_eq!(
+        10_000_000 - context.get_token_balance(user_token_a).await,
+        event.total_amount_a
+    );
+    assert_eq!(
+        10_000_000 - context.get_token_balance(user_token_b).await,
+        event.total_amount_b
+    );
+
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(
+        position_state.unlocked_liquidity,
+        event.params.liquidity_delta
+    );
+}
+
+#[tokio::test]
+async fn test_zap_in() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+
+    let user = context.create_user().await;
+    let user_token_a = context.mint_to(user.pubkey(), token_a, 10_000_000).await;
+    let user_token_b = context
+        .get_or_create_token_account(user.pubkey(), token_b)
+        .await;
+    let position = context.create_position(test_pool.pool, user.pubkey()).await;
+
+    let amount_in = 1_000_000;
+    let ix = instruction(
+        cp_amm::accounts::ZapInCtx {
+            pool_authority: pool_authority(),
+            pool: test_pool.pool,
+            position: position.position,
+            input_token_account: user_token_a,
+            output_token_account: user_token_b,
+            token_a_vault: test_pool.token_a_vault,
+            token_b_vault: test_pool.token_b_vault,
+            token_a_mint: token_a.mint,
+            token_b_mint: token_b.mint,
+            position_nft_account: position.position_nft_account,
+            owner: user.pubkey(),
+            token_a_program: token_a.token_program,
+            token_b_program: token_b.token_program,
+            oracle: None,
+            reward_extension: None,
+            position_reward_extension: None,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::ZapIn {
+            params: ZapInParameters {
+                amount_in,
+                minimum_liquidity_delta: 0,
+            },
+        },
+    );
+    context.process(&[ix], &[&user]).await.unwrap();
+
+    // part of the input is swapped to token B, then both sides are deposited
+    let swap_events = context.events::<EvtSwap>();
+    assert_eq!(swap_events.len(), 1);
+    let swap_event = &swap_events[0];
+    assert!(swap_event.actual_amount_in > 0 && swap_event.actual_amount_in < amount_in);
+    let events = context.events::<EvtAddLiquidity>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert!(event.params.liquidity_delta > 0);
+
+    let spent_a = 10_000_000 - context.get_token_balance(user_token_a).await;
+    assert!(spent_a <= amount_in);
+    assert_eq!(spent_a, swap_event.actual_amount_in + event.total_amount_a);
+    // only the swap output not deposited comes back to the user
+    assert_eq!(
+        context.get_token_balance(user_token_b).await,
+        swap_event.swap_result.output_amount - event.total_amount_b
+    );
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(
+        position_state.unlocked_liquidity,
+        event.params.liquidity_delta
+    );
+}
+
+#[tokio::test]
+async fn test_remove_all_liquidity() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let payer = context.payer();
+    let position = &test_pool.position;
+
+    let ix = context.remove_liquidity_instruction(
+        &test_pool,
+        position,
+        payer.pubkey(),
+        cp_amm::instruction::RemoveAllLiquidity {
+            token_a_amount_threshold: 0,
+            token_b_amount_threshold: 0,
+        },
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtRemoveLiquidity>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].params.liquidity_delta, DEFAULT_LIQUIDITY);
+
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(position_state.unlocked_liquidity, 0);
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.liquidity, 0);
+}
+
+#[tokio::test]
+async fn test_remove_liquidity_single_token() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+
+    let user = context.create_user().await;
+    context
+        .mint_to(user.pubkey(), token_a, INITIAL_BALANCE)
+        .await;
+    context
+        .mint_to(user.pubkey(), token_b, INITIAL_BALANCE)
+        .await;
+    let user_token_a = get_associated_token_address_with_program_id(
+        &user.pubkey(),
+        &token_a.mint,
+        &token_a.token_program,
+    );
+    let position = context.create_position(test_pool.pool, user.pubkey()).await;
+    let params = AddLiquidityParameters {
+        liquidity_delta: DEFAULT_LIQUIDITY,
+        token_a_amount_threshold: u64::MAX,
+        token_b_amount_threshold: u64::MAX,
+    };
+    context
+        .add_liquidity(&test_pool, &position, &user, params)
+        .await
+        .unwrap();
+    let user_a_balance = context.get_token_balance(user_token_a).await;
+
+    let remove_liquidity_single_token = |minimum_amount_out| {
+        instruction(
+            cp_amm::accounts::RemoveLiquiditySingleTokenCtx {
+                pool_authority: pool_authority(),
+                pool: test_pool.pool,
+                position: position.position,
+                output_token_account: user_token_a,
+                token_a_vault: test_pool.token_a_vault,
+                token_b_vault: test_pool.token_b_vault,
+                token_a_mint: token_a.mint,
+                token_b_mint: token_b.mint,
+                position_nft_account: position.position_nft_account,
+                owner: user.pubkey(),
+                token_a_program: token_a.token_program,
+                token_b_program: token_b.token_program,
+                oracle: None,
+                reward_extension: None,
+                position_reward_extension: None,
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            cp_amm::instruction::RemoveLiquiditySingleToken {
+                params: RemoveLiquiditySingleTokenParameters {
+                    liquidity_delta: DEFAULT_LIQUIDITY / 2,
+                    minimum_amount_out,
+                },
+            },
+        )
+    };
+    let result = context
+        .process(&[remove_liquidity_single_token(u64::MAX)], &[&user])
+        .await;
+    assert_pool_error(result, PoolError::ExceededSlippage);
+
+    context
+        .process(&[remove_liquidity_single_token(0)], &[&user])
+        .await
+        .unwrap();
+    let events = context.events::<EvtRemoveLiquidity>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    // the token B share is swapped to token A in the pool
+    let swap_events = context.events::<EvtSwap>();
+    assert_eq!(swap_events.len(), 1);
+    let swap_event = &swap_events[0];
+    assert_eq!(swap_event.actual_amount_in, event.token_b_amount);
+    let received_a = context.get_token_balance(user_token_a).await - user_a_balance;
+    assert_eq!(
+        received_a,
+        event.token_a_amount + swap_event.swap_result.output_amount
+    );
+
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(position_state.unlocked_liquidity, DEFAULT_LIQUIDITY / 2);
+}
+
+#[tokio::test]
+async fn test_merge_positions() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+
+    let user = context.create_user().await;
+    context
+        .mint_to(user.pubkey(), token_a, INITIAL_BALANCE)
+        .await;
+    context
+        .mint_to(user.pubkey(), token_b, INITIAL_BALANCE)
+        .await;
+    let position = context.create_position(test_pool.pool, user.pubkey()).await;
+    let source_position = context.create_position(test_pool.pool, user.pubkey()).await;
+    for (position, liquidity_delta) in [
+        (&position, DEFAULT_LIQUIDITY),
+        (&source_position, DEFAULT_LIQUIDITY / 2),
+    ] {
+        let params = AddLiquidityParameters {
+            liquidity_delta,
+            token_a_amount_threshold: u64::MAX,
+            token_b_amount_threshold: u64::MAX,
+        };
+        context
+            .add_liquidity(&test_pool, position, &user, params)
+            .await
+            .unwrap();
+    }
+
+    // the fees earned before the merge stay pending on the merged position
+    let params = SwapParameters {
+        amount_in: 100_000,
+        minimum_amount_out: 0,
+    };
+    context.swap(&test_pool, &user, true, params).await.unwrap();
+
+    let payer = context.payer();
+    let ix = instruction(
+        cp_amm::accounts::MergePositionsCtx {
+            pool: test_pool.pool,
+            position: position.position,
+            position_nft_account: position.position_nft_account,
+            source_position: source_position.position,
+            source_position_nft_mint: source_position.position_nft_mint,
+            source_position_nft_account: source_position.position_nft_account,
+            owner: user.pubkey(),
+            rent_receiver: payer.pubkey(),
+            token_program: token_2022_program(),
+            reward_extension: None,
+            position_reward_extension: None,
+            source_position_reward_extension: None,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::MergePositions {},
+    );
+    context.process(&[ix], &[&user]).await.unwrap();
+    let events = context.events::<EvtMergePositions>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].source_position, source_position.position);
+
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(
+        position_state.unlocked_liquidity,
+        DEFAULT_LIQUIDITY + DEFAULT_LIQUIDITY / 2
+    );
+    assert!(position_state.fee_b_pending > 0);
+    // the source position is closed with the account holding its burnt nft
+    for address in [
+        source_position.position,
+        source_position.position_nft_account,
+    ] {
+        let account = context
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap();
+        assert!(account.is_none());
+    }
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(
+        pool.liquidity,
+        DEFAULT_LIQUIDITY * 2 + DEFAULT_LIQUIDITY / 2
+    );
+}
@@ -0,0 +1,151 @@
+use cp_amm::{
+    state::{Pool, ReferralAccount},
+    EvtClaimReferralFee, EvtSwap, PoolError, SwapParameters,
+};
+use solana_program_test::BanksClientError;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use crate::harness::*;
+
+async fn create_referral_account(
+    context: &mut TestContext,
+    referrer: Pubkey,
+    token_mint: Pubkey,
+) -> Pubkey {
+    let referral_account = derive_referral_account(referrer, token_mint);
+    let ix = instruction(
+        cp_amm::accounts::CreateReferralAccountCtx {
+            referral_account,
+            referrer,
+            token_mint,
+            payer: context.payer().pubkey(),
+            system_program: anchor_lang::system_program::ID,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::CreateReferralAccount {},
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    referral_account
+}
+
+async fn claim_referral_fee(
+    context: &mut TestContext,
+    referrer: &Keypair,
+    referral_account: Pubkey,
+    test_pool: &TestPool,
+) -> std::result::Result<(), BanksClientError> {
+    let token_account = context
+        .get_or_create_token_account(referrer.pubkey(), test_pool.token_b)
+        .await;
+    let ix = instruction(
+        cp_amm::accounts::ClaimReferralFeeCtx {
+            pool_authority: pool_authority(),
+            pool: test_pool.pool,
+            referral_account,
+            token_account,
+            token_vault: test_pool.token_b_vault,
+            token_mint: test_pool.token_b.mint,
+            referrer: referrer.pubkey(),
+            token_program: test_pool.token_b.token_program,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::ClaimReferralFee {
+            max_amount: u64::MAX,
+        },
+    );
+    context.process(&[ix], &[referrer]).await
+}
+
+fn swap_with_referral_instruction(
+    context: &TestContext,
+    test_pool: &TestPool,
+    user: Pubkey,
+    referral_account: Pubkey,
+) -> solana_sdk::instruction::Instruction {
+    context.swap_instruction_with_accounts(
+        test_pool,
+        user,
+        true,
+        Some(referral_account),
+        None,
+        cp_amm::instruction::Swap {
+            params: SwapParameters {
+                amount_in: 1_000_000,
+                minimum_amount_out: 0,
+            },
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_referral_fee_is_tracked_per_mint() {
+    let mut context = TestContext::new().await;
+    // both pools hold token B, where a to b swaps charge the referral fee
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let pool_1 = context.initialize_pool(token_a, token_b).await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let pool_2 = context.initialize_pool(token_a, token_b).await;
+
+    // one referral account per referrer and mint, shared by every pool of the mint
+    let referrer = context.create_user().await;
+    let referral_account =
+        create_referral_account(&mut context, referrer.pubkey(), token_b.mint).await;
+    let state: ReferralAccount = context.get_state(referral_account).await;
+    assert_eq!(state.referrer, referrer.pubkey());
+    assert_eq!(state.token_mint, token_b.mint);
+
+    let user = context.create_user().await;
+    context
+        .get_or_create_token_account(user.pubkey(), token_b)
+        .await;
+    let mut referral_fees = vec![];
+    for test_pool in [&pool_1, &pool_2] {
+        context
+            .mint_to(user.pubkey(), test_pool.token_a, 10_000_000)
+            .await;
+        let ix =
+            swap_with_referral_instruction(&context, test_pool, user.pubkey(), referral_account);
+        context.process(&[ix], &[&user]).await.unwrap();
+        let referral_fee = context.events::<EvtSwap>()[0].swap_result.referral_fee;
+        assert!(referral_fee > 0);
+        let pool: Pool = context.get_state(test_pool.pool).await;
+        assert_eq!(pool.referral_b_fee, referral_fee);
+        referral_fees.push(referral_fee);
+    }
+    let state: ReferralAccount = context.get_state(referral_account).await;
+    assert_eq!(state.pending_fee, referral_fees[0] + referral_fees[1]);
+
+    // swaps can't accrue to the referral account of another mint
+    let referral_account_a =
+        create_referral_account(&mut context, referrer.pubkey(), pool_1.token_a.mint).await;
+    let ix = swap_with_referral_instruction(&context, &pool_1, user.pubkey(), referral_account_a);
+    let result = context.process(&[ix], &[&user]).await;
+    assert_pool_error(result, PoolError::InvalidReferralAccount);
+
+    // each claim takes the referral fee held in the vault of its pool
+    for (test_pool, referral_fee) in [(&pool_2, referral_fees[1]), (&pool_1, referral_fees[0])] {
+        claim_referral_fee(&mut context, &referrer, referral_account, test_pool)
+            .await
+            .unwrap();
+        let events = context.events::<EvtClaimReferralFee>();
+        assert_eq!(events[0].amount, referral_fee);
+        let pool: Pool = context.get_state(test_pool.pool).await;
+        assert_eq!(pool.referral_b_fee, 0);
+    }
+    let referrer_token_b = context
+        .get_or_create_token_account(referrer.pubkey(), token_b)
+        .await;
+    assert_eq!(
+        context.get_token_balance(referrer_token_b).await,
+        referral_fees[0] + referral_fees[1]
+    );
+    let state: ReferralAccount = context.get_state(referral_account).await;
+    assert_eq!(state.pending_fee, 0);
+    assert_eq!(state.total_fee, referral_fees[0] + referral_fees[1]);
+}
@@ -0,0 +1,475 @@
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use cp_amm::{
+    constants::NUM_REWARDS,
+    state::{Pool, RewardExtension},
+    token::TokenProgramFlags,
+    AddLiquidityParameters, EvtClaimReward, EvtFundReward, EvtInitializeReward,
+    EvtWithdrawIneligibleReward, PoolError,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+};
+
+use crate::harness::*;
+
+const REWARD_INDEX: u8 = 1;
+const REWARD_DURATION: u64 = 86_400;
+
+/// Initialize reward by creator instruction with the pool token B
+fn initialize_creator_reward_instruction(
+    test_pool: &TestPool,
+    creator: Pubkey,
+    reward_index: u8,
+) -> Instruction {
+    instruction(
+        cp_amm::accounts::InitializeRewardByCreatorCtx {
+            pool_authority: pool_authority(),
+            pool: test_pool.pool,
+            reward_vault: derive_reward_vault(test_pool.pool, reward_index),
+            reward_mint: test_pool.token_b.mint,
+            creator,
+            token_program: test_pool.token_b.token_program,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::InitializeRewardByCreator {
+            reward_index,
+            reward_duration: REWARD_DURATION,
+            funder: creator,
+        },
+    )
+}
+
+/// Fund reward instruction of the payer with the pool token B
+fn fund_reward_instruction(
+    context: &TestContext,
+    test_pool: &TestPool,
+    reward_index: u8,
+    reward_vault: Pubkey,
+    reward_extension: Option<Pubkey>,
+    amount: u64,
+) -> Instruction {
+    let payer = context.payer();
+    instruction(
+        cp_amm::accounts::FundRewardCtx {
+            pool: test_pool.pool,
+            reward_vault,
+            reward_mint: test_pool.token_b.mint,
+            funder_token_account: get_associated_token_address_with_program_id(
+                &payer.pubkey(),
+                &test_pool.token_b.mint,
+                &test_pool.token_b.token_program,
+            ),
+            funder: payer.pubkey(),
+            token_program: test_pool.token_b.token_program,
+            reward_extension,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::FundReward {
+            reward_index,
+            amount,
+            carry_forward: false,
+            reward_start_time: None,
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_creator_reward() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let payer = context.payer();
+    let payer_token_b = context
+        .get_or_create_token_account(payer.pubkey(), token_b)
+        .await;
+    let reward_vault = derive_reward_vault(test_pool.pool, REWARD_INDEX);
+
+    // the payer created the pool, so it can set up the creator reward with the pool token B
+    let ix = initialize_creator_reward_instruction(&test_pool, payer.pubkey(), REWARD_INDEX);
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtInitializeReward>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].reward_mint, token_b.mint);
+    assert_eq!(events[0].reward_index, REWARD_INDEX);
+
+    let amount = REWARD_DURATION * 1_000;
+    let ix = fund_reward_instruction(
+        &context,
+        &test_pool,
+        REWARD_INDEX,
+        reward_vault,
+        None,
+        amount,
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let start_time = context.get_timestamp().await as u64;
+    let events = context.events::<EvtFundReward>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].amount, amount);
+    assert_eq!(events[0].transfer_fee_excluded_amount_in, amount);
+    assert_eq!(events[0].reward_start_time, start_time);
+    assert_eq!(context.get_token_balance(reward_vault).await, amount);
+
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    let reward_info = &pool.reward_infos[usize::from(REWARD_INDEX)];
+    assert_eq!(reward_info.mint, token_b.mint);
+    assert_eq!(
+        reward_info.reward_duration_end,
+        start_time + REWARD_DURATION
+    );
+
+    // the pool position holds all the liquidity, so it earns the whole emission
+    context
+        .set_timestamp((start_time + REWARD_DURATION / 2) as i64)
+        .await;
+    let payer_b_balance = context.get_token_balance(payer_token_b).await;
+    let ix = instruction(
+        cp_amm::accounts::ClaimRewardCtx {
+            pool_authority: pool_authority(),
+            pool: test_pool.pool,
+            position: test_pool.position.position,
+            reward_vault,
+            reward_mint: token_b.mint,
+            user_token_account: payer_token_b,
+            position_nft_account: test_pool.position.position_nft_account,
+            owner: payer.pubkey(),
+            token_program: token_b.token_program,
+            reward_extension: None,
+            position_reward_extension: None,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::ClaimReward {
+            reward_index: REWARD_INDEX,
+        },
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtClaimReward>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.position, test_pool.position.position);
+    assert_eq!(event.mint_reward, token_b.mint);
+    // rounding leaves at most one token in the vault
+    assert!(amount / 2 - event.total_reward <= 1);
+    let claimed = context.get_token_balance(payer_token_b).await - payer_b_balance;
+    assert_eq!(claimed, event.total_reward);
+    assert_eq!(
+        context.get_token_balance(reward_vault).await,
+        amount - event.total_reward
+    );
+}
+
+#[tokio::test]
+async fn test_withdraw_ineligible_reward() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let payer = context.payer();
+    let payer_token_b = context
+        .get_or_create_token_account(payer.pubkey(), token_b)
+        .await;
+    let reward_vault = derive_reward_vault(test_pool.pool, REWARD_INDEX);
+
+    // the pool is emptied, so the whole emission goes to no one
+    let ix = context.remove_liquidity_instruction(
+        &test_pool,
+        &test_pool.position,
+        payer.pubkey(),
+        cp_amm::instruction::RemoveAllLiquidity {
+            token_a_amount_threshold: 0,
+            token_b_amount_threshold: 0,
+        },
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let ix = initialize_creator_reward_instruction(&test_pool, payer.pubkey(), REWARD_INDEX);
+    context.process(&[ix], &[]).await.unwrap();
+    let amount = REWARD_DURATION * 1_000;
+    let ix = fund_reward_instruction(
+        &context,
+        &test_pool,
+        REWARD_INDEX,
+        reward_vault,
+        None,
+        amount,
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let start_time = context.get_timestamp().await as u64;
+
+    let withdraw_ineligible_reward = instruction(
+        cp_amm::accounts::WithdrawIneligibleRewardCtx {
+            pool_authority: pool_authority(),
+            pool: test_pool.pool,
+            reward_vault,
+            reward_mint: token_b.mint,
+            funder_token_account: payer_token_b,
+            funder: payer.pubkey(),
+            token_program: token_b.token_program,
+            reward_extension: None,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::WithdrawIneligibleReward {
+            reward_index: REWARD_INDEX,
+        },
+    );
+    let result = context
+        .process(&[withdraw_ineligible_reward.clone()], &[])
+        .await;
+    assert_pool_error(result, PoolError::RewardNotEnded);
+
+    context
+        .set_timestamp((start_time + REWARD_DURATION + 1) as i64)
+        .await;
+    let payer_b_balance = context.get_token_balance(payer_token_b).await;
+    context
+        .process(&[withdraw_ineligible_reward], &[])
+        .await
+        .unwrap();
+    let events = context.events::<EvtWithdrawIneligibleReward>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].reward_mint, token_b.mint);
+    assert_eq!(events[0].amount, amount);
+    assert_eq!(
+        context.get_token_balance(payer_token_b).await - payer_b_balance,
+        amount
+    );
+    assert_eq!(context.get_token_balance(reward_vault).await, 0);
+}
+
+#[tokio::test]
+async fn test_creator_reward_access() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let payer = context.payer();
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.creator, payer.pubkey());
+
+    // other users can't initialize the creator reward
+    let user = context.create_user().await;
+    let ix = initialize_creator_reward_instruction(&test_pool, user.pubkey(), REWARD_INDEX);
+    let result = context.process(&[ix], &[&user]).await;
+    assert_pool_error(result, PoolError::InvalidRewardCreator);
+
+    // the creator can only take the creator reward index
+    let ix = initialize_creator_reward_instruction(&test_pool, payer.pubkey(), 0);
+    let result = context.process(&[ix], &[]).await;
+    assert_pool_error(result, PoolError::InvalidRewardIndex);
+
+    // nor can it take the creator reward index once it is initialized
+    let mut taken_pool = pool;
+    taken_pool.reward_infos[usize::from(REWARD_INDEX)].init_reward(
+        token_b.mint,
+        Pubkey::new_unique(),
+        payer.pubkey(),
+        REWARD_DURATION,
+        TokenProgramFlags::TokenProgram.into(),
+    );
+    context.set_state(test_pool.pool, &taken_pool);
+    let ix = initialize_creator_reward_instruction(&test_pool, payer.pubkey(), REWARD_INDEX);
+    let result = context.process(&[ix], &[]).await;
+    assert_pool_error(result, PoolError::RewardInitialized);
+
+    context.set_state(test_pool.pool, &pool);
+    let ix = initialize_creator_reward_instruction(&test_pool, payer.pubkey(), REWARD_INDEX);
+    context.process(&[ix], &[]).await.unwrap();
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    let reward_info = &pool.reward_infos[usize::from(REWARD_INDEX)];
+    assert!(reward_info.initialized());
+    assert_eq!(reward_info.funder, payer.pubkey());
+}
+
+#[tokio::test]
+async fn test_creator_is_only_recorded_when_signing() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let creator = context.create_user().await;
+    let test_pool = context
+        .initialize_pool_with_creator(token_a, token_b, creator.pubkey())
+        .await;
+
+    // the creator didn't sign the pool initialization, so it can't claim the creator reward
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.creator, Pubkey::default());
+    let ix = initialize_creator_reward_instruction(&test_pool, creator.pubkey(), REWARD_INDEX);
+    let result = context.process(&[ix], &[&creator]).await;
+    assert_pool_error(result, PoolError::InvalidRewardCreator);
+}
+
+#[tokio::test]
+async fn test_claim_all_rewards_with_reward_extension() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let payer = context.payer();
+    let payer_token_b = context
+        .get_or_create_token_account(payer.pubkey(), token_b)
+        .await;
+
+    let reward_vault = derive_reward_vault(test_pool.pool, REWARD_INDEX);
+    let ix = initialize_creator_reward_instruction(&test_pool, payer.pubkey(), REWARD_INDEX);
+    context.process(&[ix], &[]).await.unwrap();
+
+    // only the admin can set up the reward extension, so its state is written directly
+    let reward_extension = derive_reward_extension(test_pool.pool);
+    let extension_reward_index = NUM_REWARDS as u8;
+    let extension_reward_vault = context
+        .get_or_create_token_account(pool_authority(), token_b)
+        .await;
+    let mut pool: Pool = context.get_state(test_pool.pool).await;
+    pool.reward_extension_flag = 1;
+    context.set_state(test_pool.pool, &pool);
+    let mut reward_extension_state = RewardExtension::default();
+    reward_extension_state.initialize(test_pool.pool);
+    reward_extension_state.reward_infos[0].init_reward(
+        token_b.mint,
+        extension_reward_vault,
+        payer.pubkey(),
+        REWARD_DURATION,
+        TokenProgramFlags::TokenProgram.into(),
+    );
+    context.set_state(reward_extension, &reward_extension_state);
+
+    // a second position of the payer with the same liquidity as the pool position, positions
+    // without a position reward extension keep working once the pool has a reward extension
+    let position = context
+        .create_position(test_pool.pool, payer.pubkey())
+        .await;
+    let params = AddLiquidityParameters {
+        liquidity_delta: DEFAULT_LIQUIDITY,
+        token_a_amount_threshold: u64::MAX,
+        token_b_amount_threshold: u64::MAX,
+    };
+    context
+        .add_liquidity(&test_pool, &position, &payer, params)
+        .await
+        .unwrap();
+    let positions = [&test_pool.position, &position];
+
+    for position in positions {
+        let ix = instruction(
+            cp_amm::accounts::CreatePositionRewardExtensionCtx {
+                pool: test_pool.pool,
+                position: position.position,
+                position_nft_account: position.position_nft_account,
+                owner: payer.pubkey(),
+                reward_extension,
+                position_reward_extension: derive_position_reward_extension(position.position),
+                payer: payer.pubkey(),
+                system_program: anchor_lang::system_program::ID,
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            cp_amm::instruction::CreatePositionRewardExtension {},
+        );
+        context.process(&[ix], &[]).await.unwrap();
+    }
+    let reward_extension_state: RewardExtension = context.get_state(reward_extension).await;
+    assert_eq!(reward_extension_state.liquidity, 2 * DEFAULT_LIQUIDITY);
+
+    let amount = REWARD_DURATION * 1_000;
+    let ixs = [
+        fund_reward_instruction(
+            &context,
+            &test_pool,
+            REWARD_INDEX,
+            reward_vault,
+            Some(reward_extension),
+            amount,
+        ),
+        fund_reward_instruction(
+            &context,
+            &test_pool,
+            extension_reward_index,
+            extension_reward_vault,
+            Some(reward_extension),
+            amount,
+        ),
+    ];
+    context.process(&ixs, &[]).await.unwrap();
+    let start_time = context.get_timestamp().await as u64;
+    context
+        .set_timestamp((start_time + REWARD_DURATION / 2) as i64)
+        .await;
+
+    // the reward accounts of the claimed reward indexes, then each position with its reward extension
+    let claim_all_rewards_instruction = |reward_extension: Option<Pubkey>| {
+        let mut remaining_accounts = vec![];
+        let reward_vaults = match reward_extension {
+            Some(_) => vec![reward_vault, extension_reward_vault],
+            None => vec![reward_vault],
+        };
+        for vault in reward_vaults {
+            remaining_accounts.extend([
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(token_b.mint, false),
+                AccountMeta::new(payer_token_b, false),
+                AccountMeta::new_readonly(token_b.token_program, false),
+            ]);
+        }
+        for position in positions {
+            remaining_accounts.extend([
+                AccountMeta::new(position.position, false),
+                AccountMeta::new_readonly(position.position_nft_account, false),
+                AccountMeta::new(derive_position_reward_extension(position.position), false),
+            ]);
+        }
+        let mut ix = instruction(
+            cp_amm::accounts::ClaimAllRewardsCtx {
+                pool_authority: pool_authority(),
+                pool: test_pool.pool,
+                owner: payer.pubkey(),
+                reward_extension,
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            cp_amm::instruction::ClaimAllRewards { claim_fee: false },
+        );
+        ix.accounts.extend(remaining_accounts);
+        ix
+    };
+
+    // positions having a position reward extension can't skip the reward extension
+    let ix = claim_all_rewards_instruction(None);
+    let result = context.process(&[ix], &[]).await;
+    assert_pool_error(result, PoolError::InvalidRewardExtension);
+
+    let payer_b_balance = context.get_token_balance(payer_token_b).await;
+    let ix = claim_all_rewards_instruction(Some(reward_extension));
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtClaimReward>();
+    assert_eq!(events.len(), 4);
+    for reward_index in [REWARD_INDEX, extension_reward_index] {
+        let rewards: Vec<u64> = events
+            .iter()
+            .filter(|event| event.reward_index == reward_index)
+            .map(|event| event.total_reward)
+            .collect();
+        // both positions hold half of the liquidity
+        assert_eq!(rewards.len(), 2);
+        assert!(rewards[0].abs_diff(rewards[1]) <= 1);
+        assert!(amount / 2 - (rewards[0] + rewards[1]) <= 2);
+    }
+    let total_reward: u64 = events.iter().map(|event| event.total_reward).sum();
+    let claimed = context.get_token_balance(payer_token_b).await - payer_b_balance;
+    assert_eq!(claimed, total_reward);
+    let extension_reward: u64 = events
+        .iter()
+        .filter(|event| event.reward_index == extension_reward_index)
+        .map(|event| event.total_reward)
+        .sum();
+    assert_eq!(
+        context.get_token_balance(extension_reward_vault).await,
+        amount - extension_reward
+    );
+}
@@ -0,0 +1,298 @@
+use bytemuck::Zeroable;
+use cp_amm::{
+    state::{Oracle, Pool},
+    EvtSwap, EvtSwapExactOut, EvtSwapWithPriceLimit, PoolError, SwapExactOutParameters,
+    SwapParameters, SwapWithPriceLimitParameters,
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+use crate::harness::*;
+
+#[tokio::test]
+async fn test_swap_with_transfer_fee() {
+    let mut context = TestContext::new().await;
+    // 1% transfer fee on token A, 0.5% on token B
+    let token_a = context.create_mint(token_2022_program(), Some(100)).await;
+    let token_b = context.create_mint(token_2022_program(), Some(50)).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+
+    let user = context.create_user().await;
+    let user_token_a = context.mint_to(user.pubkey(), token_a, 10_000_000).await;
+    let user_token_b = context
+        .get_or_create_token_account(user.pubkey(), token_b)
+        .await;
+    let vault_a_before = context.get_token_balance(test_pool.token_a_vault).await;
+    let vault_b_before = context.get_token_balance(test_pool.token_b_vault).await;
+
+    let amount_in = 1_000_000;
+    let params = SwapParameters {
+        amount_in,
+        minimum_amount_out: 0,
+    };
+    context.swap(&test_pool, &user, true, params).await.unwrap();
+
+    let events = context.events::<EvtSwap>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.pool, test_pool.pool);
+    assert_eq!(event.params.amount_in, amount_in);
+    // the input transfer fee is taken before the pool swaps
+    assert_eq!(event.actual_amount_in, amount_in - amount_in / 100);
+
+    let vault_a_after = context.get_token_balance(test_pool.token_a_vault).await;
+    let vault_b_after = context.get_token_balance(test_pool.token_b_vault).await;
+    assert_eq!(vault_a_after - vault_a_before, event.actual_amount_in);
+    let output_amount = event.swap_result.output_amount;
+    assert!(output_amount > 0);
+    assert_eq!(vault_b_before - vault_b_after, output_amount);
+
+    // the output transfer fee is taken from what the user receives
+    let user_a_balance = context.get_token_balance(user_token_a).await;
+    let user_b_balance = context.get_token_balance(user_token_b).await;
+    assert_eq!(user_a_balance, 10_000_000 - amount_in);
+    assert_eq!(user_b_balance, output_amount - output_amount.div_ceil(200));
+
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.sqrt_price, event.swap_result.next_sqrt_price);
+    assert_eq!(pool.protocol_b_fee, event.swap_result.protocol_fee);
+}
+
+#[tokio::test]
+async fn test_swap_exceeded_slippage() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+
+    let user = context.create_user().await;
+    context.mint_to(user.pubkey(), token_b, 10_000_000).await;
+    context
+        .get_or_create_token_account(user.pubkey(), token_a)
+        .await;
+
+    let params = SwapParameters {
+        amount_in: 1_000_000,
+        minimum_amount_out: 1_000_000,
+    };
+    let result = context.swap(&test_pool, &user, false, params).await;
+    assert_pool_error(result, PoolError::ExceededSlippage);
+}
+
+#[tokio::test]
+async fn test_swap_with_price_limit() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+
+    let user = context.create_user().await;
+    let user_token_a = context.mint_to(user.pubkey(), token_a, u64::MAX / 2).await;
+    context
+        .get_or_create_token_account(user.pubkey(), token_b)
+        .await;
+
+    // far more input than the pool can take before reaching the limit
+    let amount_in = u64::MAX / 4;
+    let sqrt_price_limit = DEFAULT_SQRT_PRICE / 2;
+    let ix = context.swap_instruction(
+        &test_pool,
+        user.pubkey(),
+        true,
+        cp_amm::instruction::SwapWithPriceLimit {
+            params: SwapWithPriceLimitParameters {
+                amount_in,
+                minimum_amount_out: 0,
+                sqrt_price_limit: Some(sqrt_price_limit),
+            },
+        },
+    );
+    context.process(&[ix], &[&user]).await.unwrap();
+
+    let swap_events = context.events::<EvtSwap>();
+    let events = context.events::<EvtSwapWithPriceLimit>();
+    assert_eq!(swap_events.len(), 1);
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.sqrt_price_limit, sqrt_price_limit);
+    assert!(event.consumed_amount_in < amount_in);
+    assert_eq!(event.actual_amount_in, swap_events[0].actual_amount_in);
+
+    // only the consumed input leaves the user
+    let user_a_balance = context.get_token_balance(user_token_a).await;
+    assert_eq!(user_a_balance, u64::MAX / 2 - event.consumed_amount_in);
+
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.sqrt_price, sqrt_price_limit);
+}
+
+#[tokio::test]
+async fn test_swap_exact_out() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    // 1% transfer fee on the output
+    let token_b = context.create_mint(token_2022_program(), Some(100)).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+
+    let user = context.create_user().await;
+    let user_token_a = context.mint_to(user.pubkey(), token_a, 10_000_000).await;
+    let user_token_b = context
+        .get_or_create_token_account(user.pubkey(), token_b)
+        .await;
+
+    let amount_out = 100_000;
+    let [exceeded_slippage_ix, ix] = [amount_out, u64::MAX].map(|maximum_amount_in| {
+        context.swap_instruction(
+            &test_pool,
+            user.pubkey(),
+            true,
+            cp_amm::instruction::SwapExactOut {
+                params: SwapExactOutParameters {
+                    amount_out,
+                    maximum_amount_in,
+                },
+            },
+        )
+    });
+    // the trading fee makes the input larger than the output at a price of 1
+    let result = context.process(&[exceeded_slippage_ix], &[&user]).await;
+    assert_pool_error(result, PoolError::ExceededSlippage);
+
+    context.process(&[ix], &[&user]).await.unwrap();
+    let events = context.events::<EvtSwapExactOut>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.params.amount_out, amount_out);
+    assert!(event.actual_amount_in > amount_out);
+
+    // the user receives exactly the requested amount, the pool covers the output transfer fee
+    assert_eq!(context.get_token_balance(user_token_b).await, amount_out);
+    assert_eq!(
+        context.get_token_balance(user_token_a).await,
+        10_000_000 - event.actual_amount_in
+    );
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.sqrt_price, event.swap_result.next_sqrt_price);
+}
+
+#[tokio::test]
+async fn test_swap_requires_oracle_once_enabled() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let start_time = context.get_timestamp().await as u64;
+
+    // only the admin can initialize the oracle, so its state is written directly
+    let oracle = derive_oracle(test_pool.pool);
+    let mut oracle_state = Oracle::zeroed();
+    oracle_state.initialize(test_pool.pool, start_time);
+    context.set_state(oracle, &oracle_state);
+    let mut pool: Pool = context.get_state(test_pool.pool).await;
+    pool.oracle_flag = 1;
+    context.set_state(test_pool.pool, &pool);
+    // an oracle account at another address can't stand in for the pool oracle
+    let fake_oracle = Pubkey::new_unique();
+    context.set_state(fake_oracle, &oracle_state);
+
+    let user = context.create_user().await;
+    context.mint_to(user.pubkey(), token_a, 10_000_000).await;
+    context
+        .get_or_create_token_account(user.pubkey(), token_b)
+        .await;
+    context.set_timestamp((start_time + 100) as i64).await;
+    let swap_data = || cp_amm::instruction::Swap {
+        params: SwapParameters {
+            amount_in: 1_000_000,
+            minimum_amount_out: 0,
+        },
+    };
+
+    // swaps leaving the oracle out can't move the price unrecorded
+    for oracle in [None, Some(fake_oracle)] {
+        let ix = context.swap_instruction_with_accounts(
+            &test_pool,
+            user.pubkey(),
+            true,
+            None,
+            oracle,
+            swap_data(),
+        );
+        let result = context.process(&[ix], &[&user]).await;
+        assert_pool_error(result, PoolError::InvalidOracleAccount);
+    }
+
+    let ix = context.swap_instruction_with_accounts(
+        &test_pool,
+        user.pubkey(),
+        true,
+        None,
+        Some(oracle),
+        swap_data(),
+    );
+    context.process(&[ix], &[&user]).await.unwrap();
+    let oracle_state: Oracle = context.get_state(oracle).await;
+    assert_eq!(oracle_state.observation_count, 2);
+    let observation = &oracle_state.observations[oracle_state.observation_index as usize];
+    assert_eq!(observation.timestamp, start_time + 100);
+    // the price before the swap is recorded for the elapsed time
+    assert_eq!(observation.sqrt_price_cumulative, pool.sqrt_price * 100);
+}
+
+#[tokio::test]
+async fn test_observe() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(spl_token_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let start_time = context.get_timestamp().await as u64;
+
+    let oracle = derive_oracle(test_pool.pool);
+    let mut oracle_state = Oracle::zeroed();
+    oracle_state.initialize(test_pool.pool, start_time);
+    context.set_state(oracle, &oracle_state);
+    let mut pool: Pool = context.get_state(test_pool.pool).await;
+    pool.oracle_flag = 1;
+    context.set_state(test_pool.pool, &pool);
+    let sqrt_price_before = pool.sqrt_price;
+
+    let user = context.create_user().await;
+    context.mint_to(user.pubkey(), token_a, 10_000_000).await;
+    context
+        .get_or_create_token_account(user.pubkey(), token_b)
+        .await;
+    context.set_timestamp((start_time + 100) as i64).await;
+    let ix = context.swap_instruction_with_accounts(
+        &test_pool,
+        user.pubkey(),
+        true,
+        None,
+        Some(oracle),
+        cp_amm::instruction::Swap {
+            params: SwapParameters {
+                amount_in: 1_000_000,
+                minimum_amount_out: 0,
+            },
+        },
+    );
+    context.process(&[ix], &[&user]).await.unwrap();
+    let sqrt_price_after: u128 = context.get_state::<Pool>(test_pool.pool).await.sqrt_price;
+
+    context.set_timestamp((start_time + 200) as i64).await;
+    let observe = |window| {
+        instruction(
+            cp_amm::accounts::ObserveCtx {
+                pool: test_pool.pool,
+                oracle,
+            },
+            cp_amm::instruction::Observe { window },
+        )
+    };
+    // no observation is old enough for the window
+    let result = context.process(&[observe(300)], &[]).await;
+    assert_pool_error(result, PoolError::InvalidOracleWindow);
+
+    // the window starts at the first observation, each price held for 100 seconds
+    context.process(&[observe(150)], &[]).await.unwrap();
+    let twap: u128 = context.return_data();
+    assert_eq!(twap, (sqrt_price_before + sqrt_price_after) / 2);
+}
@@ -0,0 +1,269 @@
+use cp_amm::{
+    state::{Pool, Position, Vesting},
+    EvtLockPosition, EvtPermanentLockPosition, EvtSplitPosition, PoolError,
+    SplitPositionParameters, VestingParameters,
+};
+use solana_sdk::{
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use crate::harness::*;
+
+#[tokio::test]
+async fn test_lock_position_and_refresh_vesting() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(token_2022_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let payer = context.payer();
+    let position = &test_pool.position;
+
+    // a quarter unlocks at the cliff, then a quarter in each of the two periods
+    let quarter = DEFAULT_LIQUIDITY / 4;
+    let period_frequency = 100;
+    let vesting = Keypair::new();
+    let ix = instruction(
+        cp_amm::accounts::LockPositionCtx {
+            pool: test_pool.pool,
+            position: position.position,
+            vesting: vesting.pubkey(),
+            position_nft_account: position.position_nft_account,
+            owner: payer.pubkey(),
+            payer: payer.pubkey(),
+            system_program: anchor_lang::system_program::ID,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::LockPosition {
+            params: VestingParameters {
+                cliff_point: None,
+                period_frequency,
+                cliff_unlock_liquidity: quarter,
+                liquidity_per_period: quarter,
+                number_of_period: 2,
+            },
+        },
+    );
+    context.process(&[ix], &[&vesting]).await.unwrap();
+    let lock_time = context.get_timestamp().await as u64;
+
+    let events = context.events::<EvtLockPosition>();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.position, position.position);
+    assert_eq!(event.vesting, vesting.pubkey());
+    assert_eq!(event.cliff_point, lock_time);
+    assert_eq!(event.number_of_period, 2);
+
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(position_state.vested_liquidity, quarter * 3);
+    assert_eq!(position_state.unlocked_liquidity, quarter);
+    let vesting_state: Vesting = context.get_state(vesting.pubkey()).await;
+    assert_eq!(vesting_state.position, position.position);
+
+    let refresh_vesting = |ctx: &TestContext| {
+        let mut ix = instruction(
+            cp_amm::accounts::RefreshVesting {
+                pool: test_pool.pool,
+                position: position.position,
+                position_nft_account: position.position_nft_account,
+                owner: ctx.payer().pubkey(),
+            },
+            cp_amm::instruction::RefreshVesting {},
+        );
+        ix.accounts.push(AccountMeta::new(vesting.pubkey(), false));
+        ix
+    };
+
+    // the cliff and the first period are released
+    context
+        .set_timestamp((lock_time + period_frequency) as i64)
+        .await;
+    let ix = refresh_vesting(&context);
+    context.process(&[ix], &[]).await.unwrap();
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(position_state.vested_liquidity, quarter);
+    assert_eq!(position_state.unlocked_liquidity, quarter * 3);
+    let vesting_state: Vesting = context.get_state(vesting.pubkey()).await;
+    assert_eq!(vesting_state.total_released_liquidity, quarter * 2);
+
+    // once everything is released the vesting account is closed
+    context
+        .set_timestamp((lock_time + period_frequency * 2) as i64)
+        .await;
+    let ix = refresh_vesting(&context);
+    context.process(&[ix], &[]).await.unwrap();
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(position_state.vested_liquidity, 0);
+    assert_eq!(position_state.unlocked_liquidity, DEFAULT_LIQUIDITY);
+    let vesting_account = context
+        .context
+        .banks_client
+        .get_account(vesting.pubkey())
+        .await
+        .unwrap();
+    assert!(vesting_account.is_none());
+
+    let ix = instruction(
+        cp_amm::accounts::PermanentLockPositionCtx {
+            pool: test_pool.pool,
+            position: position.position,
+            position_nft_account: position.position_nft_account,
+            owner: payer.pubkey(),
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::PermanentLockPosition {
+            permanent_lock_liquidity: quarter,
+        },
+    );
+    context.process(&[ix], &[]).await.unwrap();
+    let events = context.events::<EvtPermanentLockPosition>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].lock_liquidity_amount, quarter);
+    assert_eq!(events[0].total_permanent_locked_liquidity, quarter);
+
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(position_state.permanent_locked_liquidity, quarter);
+    assert_eq!(position_state.unlocked_liquidity, quarter * 3);
+    let pool: Pool = context.get_state(test_pool.pool).await;
+    assert_eq!(pool.permanent_lock_liquidity, quarter);
+}
+
+#[tokio::test]
+async fn test_split_position_with_vesting() {
+    let mut context = TestContext::new().await;
+    let token_a = context.create_mint(spl_token_program(), None).await;
+    let token_b = context.create_mint(token_2022_program(), None).await;
+    let test_pool = context.initialize_pool(token_a, token_b).await;
+    let payer = context.payer();
+    let position = &test_pool.position;
+
+    let quarter = DEFAULT_LIQUIDITY / 4;
+    let period_frequency = 100;
+    let vesting = Keypair::new();
+    let ix = instruction(
+        cp_amm::accounts::LockPositionCtx {
+            pool: test_pool.pool,
+            position: position.position,
+            vesting: vesting.pubkey(),
+            position_nft_account: position.position_nft_account,
+            owner: payer.pubkey(),
+            payer: payer.pubkey(),
+            system_program: anchor_lang::system_program::ID,
+            event_authority: event_authority(),
+            program: cp_amm::ID,
+        },
+        cp_amm::instruction::LockPosition {
+            params: VestingParameters {
+                cliff_point: None,
+                period_frequency,
+                cliff_unlock_liquidity: quarter,
+                liquidity_per_period: quarter,
+                number_of_period: 2,
+            },
+        },
+    );
+    context.process(&[ix], &[&vesting]).await.unwrap();
+    let lock_time = context.get_timestamp().await as u64;
+
+    let new_position_nft_mint = Keypair::new();
+    let new_position = TestPosition::new(new_position_nft_mint.pubkey());
+    let new_vesting = Keypair::new();
+    let split_position = |vested_liquidity_percentage: u8, vestings: &[(Pubkey, Pubkey)]| {
+        let mut ix = instruction(
+            cp_amm::accounts::SplitPositionCtx {
+                pool: test_pool.pool,
+                position: position.position,
+                position_nft_account: position.position_nft_account,
+                owner: payer.pubkey(),
+                new_owner: payer.pubkey(),
+                new_position_nft_mint: new_position.position_nft_mint,
+                new_position_nft_account: new_position.position_nft_account,
+                new_position: new_position.position,
+                pool_authority: pool_authority(),
+                payer: payer.pubkey(),
+                token_program: token_2022_program(),
+                system_program: anchor_lang::system_program::ID,
+                reward_extension: None,
+                position_reward_extension: None,
+                new_position_reward_extension: None,
+                event_authority: event_authority(),
+                program: cp_amm::ID,
+            },
+            cp_amm::instruction::SplitPosition {
+                params: SplitPositionParameters {
+                    vested_liquidity_percentage,
+                    ..Default::default()
+                },
+            },
+        );
+        for &(vesting, new_vesting) in vestings {
+            ix.accounts.push(AccountMeta::new(vesting, false));
+            ix.accounts.push(AccountMeta::new(new_vesting, true));
+        }
+        ix
+    };
+
+    // every vesting of the position must be split
+    let ix = split_position(50, &[]);
+    let result = context.process(&[ix], &[&new_position_nft_mint]).await;
+    assert_pool_error(result, PoolError::InvalidVestingAccount);
+
+    // the cliff and the first period are released to the position, half of the last period moves
+    context
+        .set_timestamp((lock_time + period_frequency) as i64)
+        .await;
+    let ix = split_position(50, &[(vesting.pubkey(), new_vesting.pubkey())]);
+    context
+        .process(&[ix], &[&new_position_nft_mint, &new_vesting])
+        .await
+        .unwrap();
+
+    let events = context.events::<EvtSplitPosition>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].split_amount.vested_liquidity, quarter / 2);
+
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(position_state.unlocked_liquidity, quarter * 3);
+    assert_eq!(position_state.vested_liquidity, quarter / 2);
+    let new_position_state: Position = context.get_state(new_position.position).await;
+    assert_eq!(new_position_state.unlocked_liquidity, 0);
+    assert_eq!(new_position_state.vested_liquidity, quarter / 2);
+
+    let vesting_state: Vesting = context.get_state(vesting.pubkey()).await;
+    assert_eq!(vesting_state.position, position.position);
+    assert_eq!(vesting_state.liquidity_per_period, quarter / 2);
+    assert_eq!(vesting_state.total_released_liquidity, quarter);
+    let new_vesting_state: Vesting = context.get_state(new_vesting.pubkey()).await;
+    assert_eq!(new_vesting_state.position, new_position.position);
+    assert_eq!(new_vesting_state.cliff_point, vesting_state.cliff_point);
+    assert_eq!(new_vesting_state.liquidity_per_period, quarter / 2);
+    assert_eq!(new_vesting_state.total_released_liquidity, quarter);
+
+    // each position releases the rest of its own vesting
+    context
+        .set_timestamp((lock_time + period_frequency * 2) as i64)
+        .await;
+    for (test_position, vesting) in [(position, &vesting), (&new_position, &new_vesting)] {
+        let mut ix = instruction(
+            cp_amm::accounts::RefreshVesting {
+                pool: test_pool.pool,
+                position: test_position.position,
+                position_nft_account: test_position.position_nft_account,
+                owner: payer.pubkey(),
+            },
+            cp_amm::instruction::RefreshVesting {},
+        );
+        ix.accounts.push(AccountMeta::new(vesting.pubkey(), false));
+        context.process(&[ix], &[]).await.unwrap();
+    }
+    let position_state: Position = context.get_state(position.position).await;
+    assert_eq!(position_state.vested_liquidity, 0);
+    assert_eq!(position_state.unlocked_liquidity, quarter * 3 + quarter / 2);
+    let new_position_state: Position = context.get_state(new_position.position).await;
+    assert_eq!(new_position_state.vested_liquidity, 0);
+    assert_eq!(new_position_state.unlocked_liquidity, quarter / 2);
+}
@@ -1,10 +1,58 @@
-use anyhow::{Context, Ok, Result, ensure};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        BaseStateWithExtensions, StateWithExtensions,
+        transfer_fee::{TransferFee, TransferFeeConfig},
+    },
+    state::Mint,
+};
+use anyhow::{Context, Ok, Result, bail, ensure};
 use cp_amm::{
-    ActivationType,
+    ActivationType, PoolError,
     params::swap::TradeDirection,
-    state::{Pool, SwapResult, fee::FeeMode},
+    state::{Pool, SwapExactOutResult, SwapResult, fee::FeeMode},
+    token::{
+        calculate_transfer_fee_excluded_amount_with_epoch_fee,
+        calculate_transfer_fee_included_amount_with_epoch_fee,
+    },
 };
 
+/// Token-2022 transfer fee configs of the pool mints, `None` for mints without the extension
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TransferFeeInfo<'a> {
+    pub token_a_transfer_fee_config: Option<&'a TransferFeeConfig>,
+    pub token_b_transfer_fee_config: Option<&'a TransferFeeConfig>,
+    pub current_epoch: u64,
+}
+
+impl TransferFeeInfo<'_> {
+    fn get_epoch_transfer_fee(&self, is_token_a: bool) -> Option<TransferFee> {
+        let transfer_fee_config = if is_token_a {
+            self.token_a_transfer_fee_config
+        } else {
+            self.token_b_transfer_fee_config
+        };
+        transfer_fee_config.map(|config| *config.get_epoch_fee(self.current_epoch))
+    }
+}
+
+/// Amounts actually moved by the program when the mints charge a transfer fee
+#[derive(Debug)]
+pub struct TransferFeeQuote {
+    /// amount sent by the user, transfer fee included
+    pub amount_in: u64,
+    /// amount received by the pool, transfer fee excluded
+    pub actual_amount_in: u64,
+    /// amount received by the user, transfer fee excluded
+    pub amount_out: u64,
+    pub swap_result: SwapResult,
+}
+
+/// Reads the transfer fee config from raw mint account data, works for both SPL Token and Token-2022 mints
+pub fn get_transfer_fee_config(mint_data: &[u8]) -> Result<Option<TransferFeeConfig>> {
+    let mint = StateWithExtensions::<Mint>::unpack(mint_data).context("invalid mint data")?;
+    Ok(mint.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
 pub fn get_quote(
     pool: &Pool,
     current_timestamp: u64,
@@ -69,3 +117,148 @@ fn get_internal_quote(
 
     Ok(swap_result)
 }
+
+pub fn get_quote_exact_out(
+    pool: &Pool,
+    current_timestamp: u64,
+    current_slot: u64,
+    amount_out: u64,
+    a_to_b: bool,
+    has_referral: bool,
+) -> Result<SwapExactOutResult> {
+    ensure!(amount_out > 0, "amount is zero");
+
+    if pool.pool_fees.dynamic_fee.is_dynamic_fee_enable() {
+        let mut pool = *pool;
+        pool.update_pre_swap(current_timestamp)?;
+        get_internal_quote_exact_out(
+            &pool,
+            current_timestamp,
+            current_slot,
+            amount_out,
+            a_to_b,
+            has_referral,
+        )
+    } else {
+        get_internal_quote_exact_out(
+            pool,
+            current_timestamp,
+            current_slot,
+            amount_out,
+            a_to_b,
+            has_referral,
+        )
+    }
+}
+
+fn get_internal_quote_exact_out(
+    pool: &Pool,
+    current_timestamp: u64,
+    current_slot: u64,
+    amount_out: u64,
+    a_to_b: bool,
+    has_referral: bool,
+) -> Result<SwapExactOutResult> {
+    let activation_type =
+        ActivationType::try_from(pool.activation_type).context("invalid activation type")?;
+
+    let current_point = match activation_type {
+        ActivationType::Slot => current_slot,
+        ActivationType::Timestamp => current_timestamp,
+    };
+
+    let trade_direction = if a_to_b {
+        TradeDirection::AtoB
+    } else {
+        TradeDirection::BtoA
+    };
+
+    let fee_mode = &FeeMode::get_fee_mode(pool.collect_fee_mode, trade_direction, has_referral)?;
+
+    match pool.get_swap_result_exact_out(amount_out, fee_mode, trade_direction, current_point) {
+        Err(err) if err == PoolError::PriceRangeViolation.into() => {
+            bail!("amount out exceeds what the pool can deliver within its price range")
+        }
+        result => Ok(result?),
+    }
+}
+
+pub fn get_quote_with_transfer_fee(
+    pool: &Pool,
+    current_timestamp: u64,
+    current_slot: u64,
+    amount_in: u64,
+    a_to_b: bool,
+    has_referral: bool,
+    transfer_fee_info: TransferFeeInfo,
+) -> Result<TransferFeeQuote> {
+    let actual_amount_in = calculate_transfer_fee_excluded_amount_with_epoch_fee(
+        transfer_fee_info.get_epoch_transfer_fee(a_to_b).as_ref(),
+        amount_in,
+    )?
+    .amount;
+
+    let swap_result = get_quote(
+        pool,
+        current_timestamp,
+        current_slot,
+        actual_amount_in,
+        a_to_b,
+        has_referral,
+    )?;
+
+    let amount_out = calculate_transfer_fee_excluded_amount_with_epoch_fee(
+        transfer_fee_info.get_epoch_transfer_fee(!a_to_b).as_ref(),
+        swap_result.output_amount,
+    )?
+    .amount;
+
+    Ok(TransferFeeQuote {
+        amount_in,
+        actual_amount_in,
+        amount_out,
+        swap_result,
+    })
+}
+
+pub fn get_quote_exact_out_with_transfer_fee(
+    pool: &Pool,
+    current_timestamp: u64,
+    current_slot: u64,
+    amount_out: u64,
+    a_to_b: bool,
+    has_referral: bool,
+    transfer_fee_info: TransferFeeInfo,
+) -> Result<TransferFeeQuote> {
+    // the pool has to send out the transfer fee on top of what user receives
+    let included_amount_out = calculate_transfer_fee_included_amount_with_epoch_fee(
+        transfer_fee_info.get_epoch_transfer_fee(!a_to_b).as_ref(),
+        amount_out,
+    )?
+    .amount;
+
+    let SwapExactOutResult {
+        input_amount,
+        swap_result,
+    } = get_quote_exact_out(
+        pool,
+        current_timestamp,
+        current_slot,
+        included_amount_out,
+        a_to_b,
+        has_referral,
+    )?;
+
+    let amount_in = calculate_transfer_fee_included_amount_with_epoch_fee(
+        transfer_fee_info.get_epoch_transfer_fee(a_to_b).as_ref(),
+        input_amount,
+    )?
+    .amount;
+
+    Ok(TransferFeeQuote {
+        amount_in,
+        actual_amount_in: input_amount,
+        amount_out,
+        swap_result,
+    })
+}
@@ -5,6 +5,8 @@ import { Keypair, PublicKey } from "@solana/web3.js";
 import {
   addLiquidity,
   AddLiquidityParams,
+  addLiquidityByAmounts,
+  AddLiquidityByAmountsParams,
   createConfigIx,
   CreateConfigParams,
   createPosition,
@@ -14,9 +16,11 @@ import {
   MAX_SQRT_PRICE,
   MIN_SQRT_PRICE,
   getPool,
+  getPosition,
   U64_MAX,
   createToken,
   mintSplTokenTo,
+  zapIn,
 } from "./bankrun-utils";
 import BN from "bn.js";
 import { AccountLayout, ExtensionType } from "@solana/spl-token";
@@ -107,6 +111,7 @@ describe("Add liquidity", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
@@ -250,6 +255,126 @@ describe("Add liquidity", () => {
 
       expect(preTokenAVaultBalance).eq(postTokenAVaultBalance);
     });
+
+    it("Add liquidity by token amounts", async () => {
+      const initPoolParams: InitializePoolParams = {
+        payer: creator,
+        creator: creator.publicKey,
+        config,
+        tokenAMint: tokenAMint,
+        tokenBMint: tokenBMint,
+        liquidity: MIN_LP_AMOUNT,
+        sqrtPrice: MIN_SQRT_PRICE.muln(2),
+        activationPoint: null,
+      };
+
+      const result = await initializePool(context.banksClient, initPoolParams);
+
+      pool = result.pool;
+      position = await createPosition(
+        context.banksClient,
+        user,
+        user.publicKey,
+        pool
+      );
+
+      const poolState = await getPool(context.banksClient, pool);
+
+      const preTokenAVaultBalance = new BN(
+        AccountLayout.decode(
+          (await context.banksClient.getAccount(poolState.tokenAVault)).data
+        ).amount.toString()
+      );
+
+      const preTokenBVaultBalance = new BN(
+        AccountLayout.decode(
+          (await context.banksClient.getAccount(poolState.tokenBVault)).data
+        ).amount.toString()
+      );
+
+      const maxAmountTokenA = new BN(1_000_000);
+      const maxAmountTokenB = new BN(1_000_000);
+      const addLiquidityByAmountsParams: AddLiquidityByAmountsParams = {
+        owner: user,
+        pool,
+        position,
+        maxAmountTokenA,
+        maxAmountTokenB,
+        minimumLiquidityDelta: new BN(1),
+      };
+      await addLiquidityByAmounts(
+        context.banksClient,
+        addLiquidityByAmountsParams
+      );
+
+      const postTokenAVaultBalance = new BN(
+        AccountLayout.decode(
+          (await context.banksClient.getAccount(poolState.tokenAVault)).data
+        ).amount.toString()
+      );
+
+      const postTokenBVaultBalance = new BN(
+        AccountLayout.decode(
+          (await context.banksClient.getAccount(poolState.tokenBVault)).data
+        ).amount.toString()
+      );
+
+      const positionState = await getPosition(context.banksClient, position);
+      expect(positionState.unlockedLiquidity.gtn(0)).to.be.true;
+      expect(
+        postTokenAVaultBalance.sub(preTokenAVaultBalance).lte(maxAmountTokenA)
+      ).to.be.true;
+      expect(
+        postTokenBVaultBalance.sub(preTokenBVaultBalance).lte(maxAmountTokenB)
+      ).to.be.true;
+    });
+
+    it("Zap in with token a only", async () => {
+      const initPoolParams: InitializePoolParams = {
+        payer: creator,
+        creator: creator.publicKey,
+        config,
+        tokenAMint: tokenAMint,
+        tokenBMint: tokenBMint,
+        liquidity: MIN_LP_AMOUNT.muln(1_000_000),
+        sqrtPrice: new BN(1).shln(64),
+        activationPoint: null,
+      };
+
+      const result = await initializePool(context.banksClient, initPoolParams);
+
+      pool = result.pool;
+      position = await createPosition(
+        context.banksClient,
+        user,
+        user.publicKey,
+        pool
+      );
+
+      const beforePoolState = await getPool(context.banksClient, pool);
+
+      await zapIn(context.banksClient, {
+        owner: user,
+        pool,
+        position,
+        inputTokenMint: tokenAMint,
+        outputTokenMint: tokenBMint,
+        amountIn: new BN(1_000_000),
+        minimumLiquidityDelta: new BN(1),
+      });
+
+      const afterPoolState = await getPool(context.banksClient, pool);
+      const positionState = await getPosition(context.banksClient, position);
+
+      expect(positionState.unlockedLiquidity.gtn(0)).to.be.true;
+      expect(
+        afterPoolState.liquidity
+          .sub(beforePoolState.liquidity)
+          .eq(positionState.unlockedLiquidity)
+      ).to.be.true;
+      // part of token a is swapped to token b
+      expect(afterPoolState.sqrtPrice.lt(beforePoolState.sqrtPrice)).to.be.true;
+    });
   });
 
   describe("Token 2022", () => {
@@ -336,6 +461,7 @@ describe("Add liquidity", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
@@ -88,6 +88,39 @@ export function deriveClaimFeeOperatorAddress(operator: PublicKey): PublicKey {
   )[0];
 }
 
+export function deriveReferralAccountAddress(
+  referrer: PublicKey,
+  tokenMint: PublicKey
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("referral"), referrer.toBuffer(), tokenMint.toBuffer()],
+    CP_AMM_PROGRAM_ID
+  )[0];
+}
+
+export function deriveOracleAddress(pool: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("oracle"), pool.toBuffer()],
+    CP_AMM_PROGRAM_ID
+  )[0];
+}
+
+export function deriveRewardExtensionAddress(pool: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("reward_extension"), pool.toBuffer()],
+    CP_AMM_PROGRAM_ID
+  )[0];
+}
+
+export function derivePositionRewardExtensionAddress(
+  position: PublicKey
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("position_reward_extension"), position.toBuffer()],
+    CP_AMM_PROGRAM_ID
+  )[0];
+}
+
 export function derivePositionNftAccount(
   positionNftMint: PublicKey
 ): PublicKey {
@@ -42,7 +42,11 @@ import {
   derivePoolAddress,
   derivePoolAuthority,
   derivePositionAddress,
+  deriveOracleAddress,
   derivePositionNftAccount,
+  derivePositionRewardExtensionAddress,
+  deriveReferralAccountAddress,
+  deriveRewardExtensionAddress,
   deriveRewardVaultAddress,
   deriveTokenBadgeAddress,
   deriveTokenVaultAddress,
@@ -57,6 +61,14 @@ export type Vesting = IdlAccounts<CpAmm>["vesting"];
 export type Config = IdlAccounts<CpAmm>["config"];
 export type LockPositionParams = IdlTypes<CpAmm>["vestingParameters"];
 export type TokenBadge = IdlAccounts<CpAmm>["tokenBadge"];
+export type ReferralAccount = IdlAccounts<CpAmm>["referralAccount"];
+export type Oracle = IdlAccounts<CpAmm>["oracle"];
+export type RewardExtension = IdlAccounts<CpAmm>["rewardExtension"];
+export type PositionRewardExtension =
+  IdlAccounts<CpAmm>["positionRewardExtension"];
+export type RewardInfo = Pool["rewardInfos"][number];
+
+const NUM_REWARDS = 2;
 
 export function getSecondKey(key1: PublicKey, key2: PublicKey) {
   const buf1 = key1.toBuffer();
@@ -100,6 +112,15 @@ export type DynamicFee = {
   variableFeeControl: number;
 };
 
+export type FeeCurvePoint = {
+  pointOffset: number;
+  feeNumerator: number;
+};
+
+export type FeeCurve = {
+  points: FeeCurvePoint[];
+};
+
 export type BaseFee = {
   cliffFeeNumerator: BN;
   numberOfPeriod: number;
@@ -116,6 +137,16 @@ export type PoolFees = {
   dynamicFee: DynamicFee | null;
 };
 
+export type ExtraFees = {
+  feeCurve: FeeCurve | null;
+  aToBFeeMultiplierBps: number | null;
+};
+
+export const DEFAULT_EXTRA_FEES: ExtraFees = {
+  feeCurve: null,
+  aToBFeeMultiplierBps: null,
+};
+
 export type CreateConfigParams = {
   index: BN;
   poolFees: PoolFees;
@@ -125,18 +156,22 @@ export type CreateConfigParams = {
   poolCreatorAuthority: PublicKey;
   activationType: number; // 0: slot, 1: timestamp
   collectFeeMode: number; // 0: BothToken, 1: OnlyTokenB
+  protocolFeeReceiver: PublicKey; // PublicKey.default: treasury
 };
 
 export async function createConfigIx(
   banksClient: BanksClient,
   admin: Keypair,
-  params: CreateConfigParams
+  params: CreateConfigParams,
+  extraFees?: ExtraFees
 ): Promise<PublicKey> {
   const program = createCpAmmProgram();
 
   const config = deriveConfigAddress(params.index);
-  const transaction = await program.methods
-    .createConfig(params)
+  const methodBuilder = extraFees
+    ? program.methods.createConfigWithExtraFees(params, extraFees)
+    : program.methods.createConfig(params);
+  const transaction = await methodBuilder
     .accounts({
       config,
       admin: admin.publicKey,
@@ -159,6 +194,9 @@ export async function createConfigIx(
   );
   expect(configState.activationType).eq(params.activationType);
   expect(configState.collectFeeMode).eq(params.collectFeeMode);
+  expect(configState.protocolFeeReceiver.toString()).eq(
+    params.protocolFeeReceiver.toString()
+  );
   expect(configState.sqrtMinPrice.toNumber()).eq(
     params.sqrtMinPrice.toNumber()
   );
@@ -193,6 +231,41 @@ export async function createConfigIx(
   return config;
 }
 
+export async function updateConfigIx(
+  banksClient: BanksClient,
+  admin: Keypair,
+  config: PublicKey,
+  poolFees: PoolFeesParams,
+  extraFees: ExtraFees = DEFAULT_EXTRA_FEES
+) {
+  const program = createCpAmmProgram();
+  const transaction = await program.methods
+    .updateConfig(poolFees, extraFees)
+    .accounts({
+      config,
+      admin: admin.publicKey,
+    })
+    .transaction();
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(admin);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+
+  const configState = await getConfig(banksClient, config);
+  expect(configState.poolFees.baseFee.cliffFeeNumerator.toNumber()).eq(
+    poolFees.baseFee.cliffFeeNumerator.toNumber()
+  );
+  expect(configState.poolFees.protocolFeePercent).eq(
+    poolFees.protocolFeePercent
+  );
+  expect(configState.poolFees.partnerFeePercent).eq(
+    poolFees.partnerFeePercent
+  );
+  expect(configState.poolFees.referralFeePercent).eq(
+    poolFees.referralFeePercent
+  );
+}
+
 export async function closeConfigIx(
   banksClient: BanksClient,
   admin: Keypair,
@@ -311,13 +384,15 @@ export type ClaimProtocolFeeParams = {
   operator: Keypair;
   pool: PublicKey;
   treasury: PublicKey;
+  maxAmountA: BN;
+  maxAmountB: BN;
 };
 export async function claimProtocolFee(
   banksClient: BanksClient,
   params: ClaimProtocolFeeParams
 ) {
   const program = createCpAmmProgram();
-  const { operator, pool, treasury } = params;
+  const { operator, pool, treasury, maxAmountA, maxAmountB } = params;
   const poolAuthority = derivePoolAuthority();
   const claimFeeOperator = deriveClaimFeeOperatorAddress(operator.publicKey);
   const poolState = await getPool(banksClient, pool);
@@ -327,24 +402,29 @@ export async function claimProtocolFee(
   const tokenBProgram = (await banksClient.getAccount(poolState.tokenBMint))
     .owner;
 
-  const tokenAAccount = await getOrCreateAssociatedTokenAccount(
-    banksClient,
-    operator,
-    poolState.tokenAMint,
-    treasury,
-    tokenAProgram
-  );
-
-  const tokenBAccount = await getOrCreateAssociatedTokenAccount(
-    banksClient,
-    operator,
-    poolState.tokenBMint,
-    treasury,
-    tokenBProgram
-  );
+  // skip the token account of the side that is not claimed
+  const tokenAAccount = maxAmountA.isZero()
+    ? null
+    : await getOrCreateAssociatedTokenAccount(
+        banksClient,
+        operator,
+        poolState.tokenAMint,
+        treasury,
+        tokenAProgram
+      );
+
+  const tokenBAccount = maxAmountB.isZero()
+    ? null
+    : await getOrCreateAssociatedTokenAccount(
+        banksClient,
+        operator,
+        poolState.tokenBMint,
+        treasury,
+        tokenBProgram
+      );
 
   const transaction = await program.methods
-    .claimProtocolFee()
+    .claimProtocolFee(maxAmountA, maxAmountB)
     .accounts({
       poolAuthority,
       pool,
@@ -367,6 +447,73 @@ export async function claimProtocolFee(
   await processTransactionMaybeThrow(banksClient, transaction);
 }
 
+export type ClaimProtocolFeeBatchParams = {
+  operator: Keypair;
+  pools: PublicKey[];
+  treasury: PublicKey;
+};
+export async function claimProtocolFeeBatch(
+  banksClient: BanksClient,
+  params: ClaimProtocolFeeBatchParams
+) {
+  const program = createCpAmmProgram();
+  const { operator, pools, treasury } = params;
+  const poolAuthority = derivePoolAuthority();
+  const claimFeeOperator = deriveClaimFeeOperatorAddress(operator.publicKey);
+
+  const remainingAccounts = [];
+  for (const pool of pools) {
+    const poolState = await getPool(banksClient, pool);
+    const tokenAProgram = (await banksClient.getAccount(poolState.tokenAMint))
+      .owner;
+    const tokenBProgram = (await banksClient.getAccount(poolState.tokenBMint))
+      .owner;
+
+    const tokenAAccount = await getOrCreateAssociatedTokenAccount(
+      banksClient,
+      operator,
+      poolState.tokenAMint,
+      treasury,
+      tokenAProgram
+    );
+
+    const tokenBAccount = await getOrCreateAssociatedTokenAccount(
+      banksClient,
+      operator,
+      poolState.tokenBMint,
+      treasury,
+      tokenBProgram
+    );
+
+    remainingAccounts.push(
+      { isSigner: false, isWritable: true, pubkey: pool },
+      { isSigner: false, isWritable: true, pubkey: poolState.tokenAVault },
+      { isSigner: false, isWritable: true, pubkey: poolState.tokenBVault },
+      { isSigner: false, isWritable: false, pubkey: poolState.tokenAMint },
+      { isSigner: false, isWritable: false, pubkey: poolState.tokenBMint },
+      { isSigner: false, isWritable: true, pubkey: tokenAAccount },
+      { isSigner: false, isWritable: true, pubkey: tokenBAccount },
+      { isSigner: false, isWritable: false, pubkey: tokenAProgram },
+      { isSigner: false, isWritable: false, pubkey: tokenBProgram }
+    );
+  }
+
+  const transaction = await program.methods
+    .claimProtocolFeeBatch()
+    .accounts({
+      poolAuthority,
+      claimFeeOperator,
+      operator: operator.publicKey,
+    })
+    .remainingAccounts(remainingAccounts)
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(operator);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+}
+
 export type ClaimPartnerFeeParams = {
   partner: Keypair;
   pool: PublicKey;
@@ -555,6 +702,189 @@ export async function setPoolStatus(
   await processTransactionMaybeThrow(banksClient, transaction);
 }
 
+export type InitializeOracleParams = {
+  admin: Keypair;
+  pool: PublicKey;
+};
+
+export async function initializeOracle(
+  banksClient: BanksClient,
+  params: InitializeOracleParams
+): Promise<PublicKey> {
+  const { admin, pool } = params;
+  const program = createCpAmmProgram();
+  const oracle = deriveOracleAddress(pool);
+  const transaction = await program.methods
+    .initializeOracle()
+    .accounts({
+      pool,
+      oracle,
+      admin: admin.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(admin);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+
+  return oracle;
+}
+
+export async function getOracle(
+  banksClient: BanksClient,
+  oracle: PublicKey
+): Promise<Oracle> {
+  const program = createCpAmmProgram();
+  const account = await banksClient.getAccount(oracle);
+  return program.coder.accounts.decode("oracle", Buffer.from(account.data));
+}
+
+export type InitializeRewardExtensionParams = {
+  admin: Keypair;
+  pool: PublicKey;
+};
+
+export async function initializeRewardExtension(
+  banksClient: BanksClient,
+  params: InitializeRewardExtensionParams
+): Promise<PublicKey> {
+  const { admin, pool } = params;
+  const program = createCpAmmProgram();
+  const rewardExtension = deriveRewardExtensionAddress(pool);
+  const transaction = await program.methods
+    .initializeRewardExtension()
+    .accounts({
+      pool,
+      rewardExtension,
+      admin: admin.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(admin);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+
+  return rewardExtension;
+}
+
+export type CreatePositionRewardExtensionParams = {
+  payer: Keypair;
+  owner: Keypair;
+  pool: PublicKey;
+  position: PublicKey;
+};
+
+export async function createPositionRewardExtension(
+  banksClient: BanksClient,
+  params: CreatePositionRewardExtensionParams
+): Promise<PublicKey> {
+  const { payer, owner, pool, position } = params;
+  const program = createCpAmmProgram();
+  const positionState = await getPosition(banksClient, position);
+  const positionRewardExtension =
+    derivePositionRewardExtensionAddress(position);
+  const transaction = await program.methods
+    .createPositionRewardExtension()
+    .accounts({
+      pool,
+      position,
+      positionNftAccount: derivePositionNftAccount(positionState.nftMint),
+      owner: owner.publicKey,
+      rewardExtension: deriveRewardExtensionAddress(pool),
+      positionRewardExtension,
+      payer: payer.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(payer, owner);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+
+  return positionRewardExtension;
+}
+
+export async function getRewardExtension(
+  banksClient: BanksClient,
+  rewardExtension: PublicKey
+): Promise<RewardExtension> {
+  const program = createCpAmmProgram();
+  const account = await banksClient.getAccount(rewardExtension);
+  return program.coder.accounts.decode(
+    "rewardExtension",
+    Buffer.from(account.data)
+  );
+}
+
+export async function getPositionRewardExtension(
+  banksClient: BanksClient,
+  positionRewardExtension: PublicKey
+): Promise<PositionRewardExtension> {
+  const program = createCpAmmProgram();
+  const account = await banksClient.getAccount(positionRewardExtension);
+  return program.coder.accounts.decode(
+    "positionRewardExtension",
+    Buffer.from(account.data)
+  );
+}
+
+// Reward indexes from NUM_REWARDS onwards are stored in the reward extension
+export async function getRewardInfo(
+  banksClient: BanksClient,
+  pool: PublicKey,
+  index: number
+): Promise<RewardInfo> {
+  if (index < NUM_REWARDS) {
+    const poolState = await getPool(banksClient, pool);
+    return poolState.rewardInfos[index];
+  }
+  const rewardExtensionState = await getRewardExtension(
+    banksClient,
+    deriveRewardExtensionAddress(pool)
+  );
+  return rewardExtensionState.rewardInfos[index - NUM_REWARDS];
+}
+
+export function getRewardExtensionAddress(
+  poolState: Pool,
+  pool: PublicKey
+): PublicKey | null {
+  return poolState.rewardExtensionFlag == 1
+    ? deriveRewardExtensionAddress(pool)
+    : null;
+}
+
+// Positions having a position reward extension need the extension accounts
+// to update their rewards
+export function getRewardExtensionAccounts(
+  poolState: Pool,
+  pool: PublicKey,
+  positionState: Position,
+  position: PublicKey
+) {
+  return {
+    rewardExtension: getRewardExtensionAddress(poolState, pool),
+    positionRewardExtension: getPositionRewardExtensionAddress(
+      positionState,
+      position
+    ),
+  };
+}
+
+export function getPositionRewardExtensionAddress(
+  positionState: Position,
+  position: PublicKey
+): PublicKey | null {
+  return positionState.rewardExtensionFlag == 1
+    ? derivePositionRewardExtensionAddress(position)
+    : null;
+}
+
 export type PoolFeesParams = {
   baseFee: BaseFee;
   protocolFeePercent: number;
@@ -563,6 +893,64 @@ export type PoolFeesParams = {
   dynamicFee: DynamicFee | null;
 };
 
+export type UpdateProtocolFeeReceiverParams = {
+  admin: Keypair;
+  pool: PublicKey;
+  protocolFeeReceiver: PublicKey;
+};
+
+export async function updateProtocolFeeReceiver(
+  banksClient: BanksClient,
+  params: UpdateProtocolFeeReceiverParams
+) {
+  const { admin, pool, protocolFeeReceiver } = params;
+  const program = createCpAmmProgram();
+  const transaction = await program.methods
+    .updateProtocolFeeReceiver(protocolFeeReceiver)
+    .accounts({
+      pool,
+      admin: admin.publicKey,
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(admin);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+
+  const poolState = await getPool(banksClient, pool);
+  expect(poolState.protocolFeeReceiver.toString()).eq(
+    protocolFeeReceiver.toString()
+  );
+}
+
+export type UpdatePoolFeesParams = {
+  authority: Keypair;
+  pool: PublicKey;
+  poolFees: PoolFeesParams;
+  extraFees?: ExtraFees;
+};
+
+export async function updatePoolFees(
+  banksClient: BanksClient,
+  params: UpdatePoolFeesParams
+) {
+  const { authority, pool, poolFees, extraFees } = params;
+  const program = createCpAmmProgram();
+  const transaction = await program.methods
+    .updatePoolFees(poolFees, extraFees ?? DEFAULT_EXTRA_FEES)
+    .accounts({
+      pool,
+      authority: authority.publicKey,
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(authority);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+}
+
 export type InitializeCustomizeablePoolParams = {
   payer: Keypair;
   creator: PublicKey;
@@ -703,6 +1091,7 @@ export async function initializeReward(
   const rewardVault = deriveRewardVaultAddress(pool, index);
 
   const tokenProgram = (await banksClient.getAccount(rewardMint)).owner;
+  const poolState = await getPool(banksClient, pool);
 
   const transaction = await program.methods
     .initializeReward(index, rewardDuration, payer.publicKey)
@@ -714,6 +1103,7 @@ export async function initializeReward(
       admin: payer.publicKey,
       tokenProgram,
       systemProgram: SystemProgram.programId,
+      rewardExtension: getRewardExtensionAddress(poolState, pool),
     })
     .transaction();
   transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
@@ -722,48 +1112,89 @@ export async function initializeReward(
   await processTransactionMaybeThrow(banksClient, transaction);
 
   // validate reward data
-  const poolState = await getPool(banksClient, pool);
-  expect(poolState.rewardInfos[index].initialized).eq(1);
-  expect(poolState.rewardInfos[index].vault.toString()).eq(
-    rewardVault.toString()
-  );
-  expect(poolState.rewardInfos[index].mint.toString()).eq(
-    rewardMint.toString()
-  );
+  const rewardInfo = await getRewardInfo(banksClient, pool, index);
+  expect(rewardInfo.initialized).eq(1);
+  expect(rewardInfo.vault.toString()).eq(rewardVault.toString());
+  expect(rewardInfo.mint.toString()).eq(rewardMint.toString());
 }
 
-export type UpdateRewardDurationParams = {
+export type InitializeRewardByCreatorParams = {
+  creator: Keypair;
   index: number;
-  admin: Keypair;
+  rewardDuration: BN;
   pool: PublicKey;
-  newDuration: BN;
+  rewardMint: PublicKey;
+  funder: PublicKey;
 };
 
-export async function updateRewardDuration(
+export async function initializeRewardByCreator(
   banksClient: BanksClient,
-  params: UpdateRewardDurationParams
+  params: InitializeRewardByCreatorParams
 ): Promise<void> {
-  const { pool, admin, index, newDuration } = params;
+  const { index, rewardDuration, pool, rewardMint, creator, funder } = params;
   const program = createCpAmmProgram();
+
+  const poolAuthority = derivePoolAuthority();
+  const rewardVault = deriveRewardVaultAddress(pool, index);
+
+  const tokenProgram = (await banksClient.getAccount(rewardMint)).owner;
+
   const transaction = await program.methods
-    .updateRewardDuration(index, newDuration)
+    .initializeRewardByCreator(index, rewardDuration, funder)
     .accounts({
       pool,
-      admin: admin.publicKey,
+      poolAuthority,
+      rewardVault,
+      rewardMint,
+      creator: creator.publicKey,
+      tokenProgram,
+      systemProgram: SystemProgram.programId,
     })
     .transaction();
   transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
-  transaction.sign(admin);
+  transaction.sign(creator);
 
   await processTransactionMaybeThrow(banksClient, transaction);
 
   const poolState = await getPool(banksClient, pool);
-  expect(poolState.rewardInfos[index].rewardDuration.toNumber()).eq(
-    newDuration.toNumber()
+  expect(poolState.rewardInfos[index].initialized).eq(1);
+  expect(poolState.rewardInfos[index].funder.toString()).eq(
+    funder.toString()
   );
 }
 
-export type UpdateRewardFunderParams = {
+export type UpdateRewardDurationParams = {
+  index: number;
+  admin: Keypair;
+  pool: PublicKey;
+  newDuration: BN;
+};
+
+export async function updateRewardDuration(
+  banksClient: BanksClient,
+  params: UpdateRewardDurationParams
+): Promise<void> {
+  const { pool, admin, index, newDuration } = params;
+  const program = createCpAmmProgram();
+  const transaction = await program.methods
+    .updateRewardDuration(index, newDuration)
+    .accounts({
+      pool,
+      admin: admin.publicKey,
+    })
+    .transaction();
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(admin);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+
+  const poolState = await getPool(banksClient, pool);
+  expect(poolState.rewardInfos[index].rewardDuration.toNumber()).eq(
+    newDuration.toNumber()
+  );
+}
+
+export type UpdateRewardFunderParams = {
   index: number;
   admin: Keypair;
   pool: PublicKey;
@@ -800,22 +1231,23 @@ export type FundRewardParams = {
   pool: PublicKey;
   carryForward: boolean;
   amount: BN;
+  rewardStartTime?: BN;
 };
 
 export async function fundReward(
   banksClient: BanksClient,
   params: FundRewardParams
 ): Promise<void> {
-  const { index, carryForward, pool, funder, amount } = params;
+  const { index, carryForward, pool, funder, amount, rewardStartTime } =
+    params;
   const program = createCpAmmProgram();
 
   const poolState = await getPool(banksClient, pool);
-  const rewardVault = poolState.rewardInfos[index].vault;
-  const tokenProgram = (
-    await banksClient.getAccount(poolState.rewardInfos[index].mint)
-  ).owner;
+  const rewardInfo = await getRewardInfo(banksClient, pool, index);
+  const rewardVault = rewardInfo.vault;
+  const tokenProgram = (await banksClient.getAccount(rewardInfo.mint)).owner;
   const funderTokenAccount = getAssociatedTokenAddressSync(
-    poolState.rewardInfos[index].mint,
+    rewardInfo.mint,
     funder.publicKey,
     true,
     tokenProgram
@@ -832,14 +1264,15 @@ export async function fundReward(
   );
 
   const transaction = await program.methods
-    .fundReward(index, amount, carryForward)
+    .fundReward(index, amount, carryForward, rewardStartTime ?? null)
     .accounts({
       pool,
-      rewardVault: poolState.rewardInfos[index].vault,
-      rewardMint: poolState.rewardInfos[index].mint,
+      rewardVault,
+      rewardMint: rewardInfo.mint,
       funderTokenAccount,
       funder: funder.publicKey,
       tokenProgram,
+      rewardExtension: getRewardExtensionAddress(poolState, pool),
     })
     .transaction();
   transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
@@ -877,19 +1310,18 @@ export async function claimReward(
   const program = createCpAmmProgram();
 
   const poolState = await getPool(banksClient, pool);
+  const rewardInfo = await getRewardInfo(banksClient, pool, index);
   const positionState = await getPosition(banksClient, position);
   const poolAuthority = derivePoolAuthority();
   const positionNftAccount = derivePositionNftAccount(positionState.nftMint);
 
   // TODO should use token flag in pool state to get token program ID
-  const tokenProgram = (
-    await banksClient.getAccount(poolState.rewardInfos[index].mint)
-  ).owner;
+  const tokenProgram = (await banksClient.getAccount(rewardInfo.mint)).owner;
 
   const userTokenAccount = await getOrCreateAssociatedTokenAccount(
     banksClient,
     user,
-    poolState.rewardInfos[index].mint,
+    rewardInfo.mint,
     user.publicKey,
     tokenProgram
   );
@@ -899,14 +1331,131 @@ export async function claimReward(
     .accounts({
       pool,
       positionNftAccount,
-      rewardVault: poolState.rewardInfos[index].vault,
-      rewardMint: poolState.rewardInfos[index].mint,
+      rewardVault: rewardInfo.vault,
+      rewardMint: rewardInfo.mint,
       poolAuthority,
       position,
       userTokenAccount,
       owner: user.publicKey,
       tokenProgram,
+      ...getRewardExtensionAccounts(poolState, pool, positionState, position),
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(user);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+}
+
+export type ClaimAllRewardsParams = {
+  user: Keypair;
+  pool: PublicKey;
+  positions: PublicKey[];
+  claimFee: boolean;
+};
+
+export async function claimAllRewards(
+  banksClient: BanksClient,
+  params: ClaimAllRewardsParams
+): Promise<void> {
+  const { user, pool, positions, claimFee } = params;
+  const program = createCpAmmProgram();
+
+  const poolState = await getPool(banksClient, pool);
+  const poolAuthority = derivePoolAuthority();
+
+  const remainingAccounts = [];
+  if (claimFee) {
+    const tokenAProgram = (await banksClient.getAccount(poolState.tokenAMint))
+      .owner;
+    const tokenBProgram = (await banksClient.getAccount(poolState.tokenBMint))
+      .owner;
+    const tokenAAccount = await getOrCreateAssociatedTokenAccount(
+      banksClient,
+      user,
+      poolState.tokenAMint,
+      user.publicKey,
+      tokenAProgram
+    );
+    const tokenBAccount = await getOrCreateAssociatedTokenAccount(
+      banksClient,
+      user,
+      poolState.tokenBMint,
+      user.publicKey,
+      tokenBProgram
+    );
+    remainingAccounts.push(
+      { isSigner: false, isWritable: true, pubkey: tokenAAccount },
+      { isSigner: false, isWritable: true, pubkey: tokenBAccount },
+      { isSigner: false, isWritable: true, pubkey: poolState.tokenAVault },
+      { isSigner: false, isWritable: true, pubkey: poolState.tokenBVault },
+      { isSigner: false, isWritable: false, pubkey: poolState.tokenAMint },
+      { isSigner: false, isWritable: false, pubkey: poolState.tokenBMint },
+      { isSigner: false, isWritable: false, pubkey: tokenAProgram },
+      { isSigner: false, isWritable: false, pubkey: tokenBProgram }
+    );
+  }
+
+  // reward indexes from NUM_REWARDS onwards are stored in the reward extension
+  const rewardExtension = getRewardExtensionAddress(poolState, pool);
+  const rewardInfos = [...poolState.rewardInfos];
+  if (rewardExtension) {
+    const rewardExtensionState = await getRewardExtension(
+      banksClient,
+      rewardExtension
+    );
+    rewardInfos.push(...rewardExtensionState.rewardInfos);
+  }
+
+  for (const rewardInfo of rewardInfos) {
+    if (rewardInfo.initialized == 0) {
+      continue;
+    }
+    const tokenProgram = (await banksClient.getAccount(rewardInfo.mint)).owner;
+    const userTokenAccount = await getOrCreateAssociatedTokenAccount(
+      banksClient,
+      user,
+      rewardInfo.mint,
+      user.publicKey,
+      tokenProgram
+    );
+    remainingAccounts.push(
+      { isSigner: false, isWritable: true, pubkey: rewardInfo.vault },
+      { isSigner: false, isWritable: false, pubkey: rewardInfo.mint },
+      { isSigner: false, isWritable: true, pubkey: userTokenAccount },
+      { isSigner: false, isWritable: false, pubkey: tokenProgram }
+    );
+  }
+
+  for (const position of positions) {
+    const positionState = await getPosition(banksClient, position);
+    remainingAccounts.push(
+      { isSigner: false, isWritable: true, pubkey: position },
+      {
+        isSigner: false,
+        isWritable: false,
+        pubkey: derivePositionNftAccount(positionState.nftMint),
+      }
+    );
+    if (positionState.rewardExtensionFlag == 1) {
+      remainingAccounts.push({
+        isSigner: false,
+        isWritable: true,
+        pubkey: derivePositionRewardExtensionAddress(position),
+      });
+    }
+  }
+
+  const transaction = await program.methods
+    .claimAllRewards(claimFee)
+    .accounts({
+      poolAuthority,
+      pool,
+      owner: user.publicKey,
+      rewardExtension,
     })
+    .remainingAccounts(remainingAccounts)
     .transaction();
 
   transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
@@ -929,12 +1478,11 @@ export async function withdrawIneligibleReward(
   const program = createCpAmmProgram();
 
   const poolState = await getPool(banksClient, pool);
+  const rewardInfo = await getRewardInfo(banksClient, pool, index);
   const poolAuthority = derivePoolAuthority();
-  const tokenProgram = (
-    await banksClient.getAccount(poolState.rewardInfos[index].mint)
-  ).owner;
+  const tokenProgram = (await banksClient.getAccount(rewardInfo.mint)).owner;
   const funderTokenAccount = getAssociatedTokenAddressSync(
-    poolState.rewardInfos[index].mint,
+    rewardInfo.mint,
     funder.publicKey,
     true,
     tokenProgram
@@ -944,12 +1492,13 @@ export async function withdrawIneligibleReward(
     .withdrawIneligibleReward(index)
     .accounts({
       pool,
-      rewardVault: poolState.rewardInfos[index].vault,
-      rewardMint: poolState.rewardInfos[index].mint,
+      rewardVault: rewardInfo.vault,
+      rewardMint: rewardInfo.mint,
       poolAuthority,
       funderTokenAccount,
       funder: funder.publicKey,
       tokenProgram,
+      rewardExtension: getRewardExtensionAddress(poolState, pool),
     })
     .transaction();
 
@@ -1022,6 +1571,136 @@ export async function permanentLockPosition(
   await processTransactionMaybeThrow(banksClient, transaction);
 }
 
+export type SplitPositionParams = {
+  unlockedLiquidityPercentage: number;
+  vestedLiquidityPercentage: number;
+  permanentLockedLiquidityPercentage: number;
+  feeAPercentage: number;
+  feeBPercentage: number;
+  reward0Percentage: number;
+  reward1Percentage: number;
+  reward2Percentage: number;
+  reward3Percentage: number;
+};
+
+export async function splitPosition(
+  banksClient: BanksClient,
+  position: PublicKey,
+  owner: Keypair,
+  newOwner: PublicKey,
+  payer: Keypair,
+  params: SplitPositionParams,
+  // every vesting of the position when splitting vested liquidity
+  vestings: { vesting: PublicKey; newVesting: Keypair }[] = []
+): Promise<PublicKey> {
+  const program = createCpAmmProgram();
+
+  const positionState = await getPosition(banksClient, position);
+  const positionNftAccount = derivePositionNftAccount(positionState.nftMint);
+  const poolState = await getPool(banksClient, positionState.pool);
+
+  const newPositionNftKP = Keypair.generate();
+  const newPosition = derivePositionAddress(newPositionNftKP.publicKey);
+  const newPositionNftAccount = derivePositionNftAccount(
+    newPositionNftKP.publicKey
+  );
+  const poolAuthority = derivePoolAuthority();
+
+  const transaction = await program.methods
+    .splitPosition(params)
+    .accounts({
+      pool: positionState.pool,
+      position,
+      positionNftAccount,
+      owner: owner.publicKey,
+      newOwner,
+      newPositionNftMint: newPositionNftKP.publicKey,
+      newPositionNftAccount,
+      newPosition,
+      poolAuthority,
+      payer: payer.publicKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      ...getRewardExtensionAccounts(
+        poolState,
+        positionState.pool,
+        positionState,
+        position
+      ),
+      newPositionRewardExtension:
+        positionState.rewardExtensionFlag == 1
+          ? derivePositionRewardExtensionAddress(newPosition)
+          : null,
+    })
+    .remainingAccounts(
+      vestings.flatMap(({ vesting, newVesting }) => [
+        { isSigner: false, isWritable: true, pubkey: vesting },
+        { isSigner: true, isWritable: true, pubkey: newVesting.publicKey },
+      ])
+    )
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(
+    payer,
+    owner,
+    newPositionNftKP,
+    ...vestings.map(({ newVesting }) => newVesting)
+  );
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+
+  return newPosition;
+}
+
+export async function mergePositions(
+  banksClient: BanksClient,
+  position: PublicKey,
+  sourcePosition: PublicKey,
+  owner: Keypair,
+  payer: Keypair
+) {
+  const program = createCpAmmProgram();
+
+  const positionState = await getPosition(banksClient, position);
+  const positionNftAccount = derivePositionNftAccount(positionState.nftMint);
+  const sourcePositionState = await getPosition(banksClient, sourcePosition);
+  const sourcePositionNftAccount = derivePositionNftAccount(
+    sourcePositionState.nftMint
+  );
+  const poolState = await getPool(banksClient, positionState.pool);
+
+  const transaction = await program.methods
+    .mergePositions()
+    .accounts({
+      pool: positionState.pool,
+      position,
+      positionNftAccount,
+      sourcePosition,
+      sourcePositionNftMint: sourcePositionState.nftMint,
+      sourcePositionNftAccount,
+      owner: owner.publicKey,
+      rentReceiver: owner.publicKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      ...getRewardExtensionAccounts(
+        poolState,
+        positionState.pool,
+        positionState,
+        position
+      ),
+      sourcePositionRewardExtension: getPositionRewardExtensionAddress(
+        sourcePositionState,
+        sourcePosition
+      ),
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(payer, owner);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+}
+
 export async function lockPosition(
   banksClient: BanksClient,
   position: PublicKey,
@@ -1194,6 +1873,7 @@ export async function addLiquidity(
       tokenBProgram,
       tokenAMint,
       tokenBMint,
+      ...getRewardExtensionAccounts(poolState, pool, positionState, position),
     })
     .transaction();
 
@@ -1203,19 +1883,26 @@ export async function addLiquidity(
   await processTransactionMaybeThrow(banksClient, transaction);
 }
 
-export type RemoveLiquidityParams = AddLiquidityParams;
+export type AddLiquidityByAmountsParams = {
+  owner: Keypair;
+  pool: PublicKey;
+  position: PublicKey;
+  maxAmountTokenA: BN;
+  maxAmountTokenB: BN;
+  minimumLiquidityDelta: BN;
+};
 
-export async function removeLiquidity(
+export async function addLiquidityByAmounts(
   banksClient: BanksClient,
-  params: RemoveLiquidityParams
+  params: AddLiquidityByAmountsParams
 ) {
   const {
     owner,
     pool,
     position,
-    liquidityDelta,
-    tokenAAmountThreshold,
-    tokenBAmountThreshold,
+    maxAmountTokenA,
+    maxAmountTokenB,
+    minimumLiquidityDelta,
   } = params;
 
   const program = createCpAmmProgram();
@@ -1223,7 +1910,6 @@ export async function removeLiquidity(
   const positionState = await getPosition(banksClient, position);
   const positionNftAccount = derivePositionNftAccount(positionState.nftMint);
 
-  const poolAuthority = derivePoolAuthority();
   const tokenAProgram = (await banksClient.getAccount(poolState.tokenAMint))
     .owner;
   const tokenBProgram = (await banksClient.getAccount(poolState.tokenBMint))
@@ -1247,13 +1933,12 @@ export async function removeLiquidity(
   const tokenBMint = poolState.tokenBMint;
 
   const transaction = await program.methods
-    .removeLiquidity({
-      liquidityDelta,
-      tokenAAmountThreshold,
-      tokenBAmountThreshold,
+    .addLiquidityByAmounts({
+      maxAmountTokenA,
+      maxAmountTokenB,
+      minimumLiquidityDelta,
     })
     .accounts({
-      poolAuthority,
       pool,
       position,
       positionNftAccount,
@@ -1266,6 +1951,7 @@ export async function removeLiquidity(
       tokenBProgram,
       tokenAMint,
       tokenBMint,
+      ...getRewardExtensionAccounts(poolState, pool, positionState, position),
     })
     .transaction();
 
@@ -1275,23 +1961,99 @@ export async function removeLiquidity(
   await processTransactionMaybeThrow(banksClient, transaction);
 }
 
-
-export type RemoveAllLiquidityParams = {
+export type ZapInParams = {
   owner: Keypair;
   pool: PublicKey;
   position: PublicKey;
-  tokenAAmountThreshold: BN;
-  tokenBAmountThreshold: BN;
+  inputTokenMint: PublicKey;
+  outputTokenMint: PublicKey;
+  amountIn: BN;
+  minimumLiquidityDelta: BN;
 };
 
-export async function removeAllLiquidity(
+export async function zapIn(banksClient: BanksClient, params: ZapInParams) {
+  const {
+    owner,
+    pool,
+    position,
+    inputTokenMint,
+    outputTokenMint,
+    amountIn,
+    minimumLiquidityDelta,
+  } = params;
+
+  const program = createCpAmmProgram();
+  const poolState = await getPool(banksClient, pool);
+  const positionState = await getPosition(banksClient, position);
+  const positionNftAccount = derivePositionNftAccount(positionState.nftMint);
+
+  const poolAuthority = derivePoolAuthority();
+  const inputTokenProgram = (await banksClient.getAccount(inputTokenMint))
+    .owner;
+  const outputTokenProgram = (await banksClient.getAccount(outputTokenMint))
+    .owner;
+  const inputTokenAccount = getAssociatedTokenAddressSync(
+    inputTokenMint,
+    owner.publicKey,
+    true,
+    inputTokenProgram
+  );
+  const outputTokenAccount = getAssociatedTokenAddressSync(
+    outputTokenMint,
+    owner.publicKey,
+    true,
+    outputTokenProgram
+  );
+  const tokenAProgram = (await banksClient.getAccount(poolState.tokenAMint))
+    .owner;
+  const tokenBProgram = (await banksClient.getAccount(poolState.tokenBMint))
+    .owner;
+  const tokenAVault = poolState.tokenAVault;
+  const tokenBVault = poolState.tokenBVault;
+  const tokenAMint = poolState.tokenAMint;
+  const tokenBMint = poolState.tokenBMint;
+
+  const transaction = await program.methods
+    .zapIn({
+      amountIn,
+      minimumLiquidityDelta,
+    })
+    .accounts({
+      poolAuthority,
+      pool,
+      position,
+      inputTokenAccount,
+      outputTokenAccount,
+      tokenAVault,
+      tokenBVault,
+      tokenAMint,
+      tokenBMint,
+      positionNftAccount,
+      owner: owner.publicKey,
+      tokenAProgram,
+      tokenBProgram,
+      oracle: poolState.oracleFlag == 1 ? deriveOracleAddress(pool) : null,
+      ...getRewardExtensionAccounts(poolState, pool, positionState, position),
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(owner);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+}
+
+export type RemoveLiquidityParams = AddLiquidityParams;
+
+export async function removeLiquidity(
   banksClient: BanksClient,
-  params: RemoveAllLiquidityParams,
+  params: RemoveLiquidityParams
 ) {
   const {
     owner,
     pool,
     position,
+    liquidityDelta,
     tokenAAmountThreshold,
     tokenBAmountThreshold,
   } = params;
@@ -1325,10 +2087,11 @@ export async function removeAllLiquidity(
   const tokenBMint = poolState.tokenBMint;
 
   const transaction = await program.methods
-    .removeAllLiquidity(
+    .removeLiquidity({
+      liquidityDelta,
       tokenAAmountThreshold,
       tokenBAmountThreshold,
-    )
+    })
     .accounts({
       poolAuthority,
       pool,
@@ -1343,6 +2106,7 @@ export async function removeAllLiquidity(
       tokenBProgram,
       tokenAMint,
       tokenBMint,
+      ...getRewardExtensionAccounts(poolState, pool, positionState, position),
     })
     .transaction();
 
@@ -1352,42 +2116,195 @@ export async function removeAllLiquidity(
   await processTransactionMaybeThrow(banksClient, transaction);
 }
 
-export type SwapParams = {
-  payer: Keypair;
+export type RemoveLiquiditySingleTokenParams = {
+  owner: Keypair;
   pool: PublicKey;
-  inputTokenMint: PublicKey;
+  position: PublicKey;
   outputTokenMint: PublicKey;
-  amountIn: BN;
+  liquidityDelta: BN;
   minimumAmountOut: BN;
-  referralTokenAccount: PublicKey | null;
 };
 
-export async function swap(banksClient: BanksClient, params: SwapParams) {
+export async function removeLiquiditySingleToken(
+  banksClient: BanksClient,
+  params: RemoveLiquiditySingleTokenParams
+) {
   const {
-    payer,
+    owner,
     pool,
-    inputTokenMint,
+    position,
     outputTokenMint,
-    amountIn,
+    liquidityDelta,
     minimumAmountOut,
-    referralTokenAccount,
   } = params;
 
   const program = createCpAmmProgram();
   const poolState = await getPool(banksClient, pool);
+  const positionState = await getPosition(banksClient, position);
+  const positionNftAccount = derivePositionNftAccount(positionState.nftMint);
 
   const poolAuthority = derivePoolAuthority();
   const tokenAProgram = (await banksClient.getAccount(poolState.tokenAMint))
     .owner;
-
   const tokenBProgram = (await banksClient.getAccount(poolState.tokenBMint))
     .owner;
-  const inputTokenAccount = getAssociatedTokenAddressSync(
-    inputTokenMint,
-    payer.publicKey,
-    true,
-    tokenAProgram
-  );
+
+  const outputTokenAccount = getAssociatedTokenAddressSync(
+    outputTokenMint,
+    owner.publicKey,
+    true,
+    (await banksClient.getAccount(outputTokenMint)).owner
+  );
+  const tokenAVault = poolState.tokenAVault;
+  const tokenBVault = poolState.tokenBVault;
+  const tokenAMint = poolState.tokenAMint;
+  const tokenBMint = poolState.tokenBMint;
+
+  const transaction = await program.methods
+    .removeLiquiditySingleToken({
+      liquidityDelta,
+      minimumAmountOut,
+    })
+    .accounts({
+      poolAuthority,
+      pool,
+      position,
+      positionNftAccount,
+      owner: owner.publicKey,
+      outputTokenAccount,
+      tokenAVault,
+      tokenBVault,
+      tokenAProgram,
+      tokenBProgram,
+      tokenAMint,
+      tokenBMint,
+      oracle: poolState.oracleFlag == 1 ? deriveOracleAddress(pool) : null,
+      ...getRewardExtensionAccounts(poolState, pool, positionState, position),
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(owner);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+}
+
+
+export type RemoveAllLiquidityParams = {
+  owner: Keypair;
+  pool: PublicKey;
+  position: PublicKey;
+  tokenAAmountThreshold: BN;
+  tokenBAmountThreshold: BN;
+};
+
+export async function removeAllLiquidity(
+  banksClient: BanksClient,
+  params: RemoveAllLiquidityParams,
+) {
+  const {
+    owner,
+    pool,
+    position,
+    tokenAAmountThreshold,
+    tokenBAmountThreshold,
+  } = params;
+
+  const program = createCpAmmProgram();
+  const poolState = await getPool(banksClient, pool);
+  const positionState = await getPosition(banksClient, position);
+  const positionNftAccount = derivePositionNftAccount(positionState.nftMint);
+
+  const poolAuthority = derivePoolAuthority();
+  const tokenAProgram = (await banksClient.getAccount(poolState.tokenAMint))
+    .owner;
+  const tokenBProgram = (await banksClient.getAccount(poolState.tokenBMint))
+    .owner;
+
+  const tokenAAccount = getAssociatedTokenAddressSync(
+    poolState.tokenAMint,
+    owner.publicKey,
+    true,
+    tokenAProgram
+  );
+  const tokenBAccount = getAssociatedTokenAddressSync(
+    poolState.tokenBMint,
+    owner.publicKey,
+    true,
+    tokenBProgram
+  );
+  const tokenAVault = poolState.tokenAVault;
+  const tokenBVault = poolState.tokenBVault;
+  const tokenAMint = poolState.tokenAMint;
+  const tokenBMint = poolState.tokenBMint;
+
+  const transaction = await program.methods
+    .removeAllLiquidity(
+      tokenAAmountThreshold,
+      tokenBAmountThreshold,
+    )
+    .accounts({
+      poolAuthority,
+      pool,
+      position,
+      positionNftAccount,
+      owner: owner.publicKey,
+      tokenAAccount,
+      tokenBAccount,
+      tokenAVault,
+      tokenBVault,
+      tokenAProgram,
+      tokenBProgram,
+      tokenAMint,
+      tokenBMint,
+      ...getRewardExtensionAccounts(poolState, pool, positionState, position),
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(owner);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+}
+
+export type SwapParams = {
+  payer: Keypair;
+  pool: PublicKey;
+  inputTokenMint: PublicKey;
+  outputTokenMint: PublicKey;
+  amountIn: BN;
+  minimumAmountOut: BN;
+  referralTokenAccount: PublicKey | null;
+  referralAccount?: PublicKey;
+};
+
+export async function swap(banksClient: BanksClient, params: SwapParams) {
+  const {
+    payer,
+    pool,
+    inputTokenMint,
+    outputTokenMint,
+    amountIn,
+    minimumAmountOut,
+    referralTokenAccount,
+    referralAccount,
+  } = params;
+
+  const program = createCpAmmProgram();
+  const poolState = await getPool(banksClient, pool);
+
+  const poolAuthority = derivePoolAuthority();
+  const tokenAProgram = (await banksClient.getAccount(poolState.tokenAMint))
+    .owner;
+
+  const tokenBProgram = (await banksClient.getAccount(poolState.tokenBMint))
+    .owner;
+  const inputTokenAccount = getAssociatedTokenAddressSync(
+    inputTokenMint,
+    payer.publicKey,
+    true,
+    tokenAProgram
+  );
   const outputTokenAccount = getAssociatedTokenAddressSync(
     outputTokenMint,
     payer.publicKey,
@@ -1417,6 +2334,212 @@ export async function swap(banksClient: BanksClient, params: SwapParams) {
       tokenAMint,
       tokenBMint,
       referralTokenAccount,
+      referralAccount: referralAccount ?? null,
+      oracle: poolState.oracleFlag == 1 ? deriveOracleAddress(pool) : null,
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(payer);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+}
+
+export type SwapWithPriceLimitParams = {
+  payer: Keypair;
+  pool: PublicKey;
+  inputTokenMint: PublicKey;
+  outputTokenMint: PublicKey;
+  amountIn: BN;
+  minimumAmountOut: BN;
+  sqrtPriceLimit: BN | null;
+  referralTokenAccount: PublicKey | null;
+  referralAccount?: PublicKey;
+};
+
+export async function swapWithPriceLimit(
+  banksClient: BanksClient,
+  params: SwapWithPriceLimitParams
+) {
+  const {
+    payer,
+    pool,
+    inputTokenMint,
+    outputTokenMint,
+    amountIn,
+    minimumAmountOut,
+    sqrtPriceLimit,
+    referralTokenAccount,
+    referralAccount,
+  } = params;
+
+  const program = createCpAmmProgram();
+  const poolState = await getPool(banksClient, pool);
+
+  const poolAuthority = derivePoolAuthority();
+  const tokenAProgram = (await banksClient.getAccount(poolState.tokenAMint))
+    .owner;
+
+  const tokenBProgram = (await banksClient.getAccount(poolState.tokenBMint))
+    .owner;
+  const inputTokenProgram = (await banksClient.getAccount(inputTokenMint))
+    .owner;
+  const outputTokenProgram = (await banksClient.getAccount(outputTokenMint))
+    .owner;
+  const inputTokenAccount = getAssociatedTokenAddressSync(
+    inputTokenMint,
+    payer.publicKey,
+    true,
+    inputTokenProgram
+  );
+  const outputTokenAccount = getAssociatedTokenAddressSync(
+    outputTokenMint,
+    payer.publicKey,
+    true,
+    outputTokenProgram
+  );
+  const tokenAVault = poolState.tokenAVault;
+  const tokenBVault = poolState.tokenBVault;
+  const tokenAMint = poolState.tokenAMint;
+  const tokenBMint = poolState.tokenBMint;
+
+  const transaction = await program.methods
+    .swapWithPriceLimit({
+      amountIn,
+      minimumAmountOut,
+      sqrtPriceLimit,
+    })
+    .accounts({
+      poolAuthority,
+      pool,
+      payer: payer.publicKey,
+      inputTokenAccount,
+      outputTokenAccount,
+      tokenAVault,
+      tokenBVault,
+      tokenAProgram,
+      tokenBProgram,
+      tokenAMint,
+      tokenBMint,
+      referralTokenAccount,
+      referralAccount: referralAccount ?? null,
+      oracle: poolState.oracleFlag == 1 ? deriveOracleAddress(pool) : null,
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(payer);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+}
+
+export type SwapExactOutParams = {
+  payer: Keypair;
+  pool: PublicKey;
+  inputTokenMint: PublicKey;
+  outputTokenMint: PublicKey;
+  amountOut: BN;
+  maximumAmountIn: BN;
+  referralTokenAccount: PublicKey | null;
+  referralAccount?: PublicKey;
+};
+
+export async function swapExactOut(
+  banksClient: BanksClient,
+  params: SwapExactOutParams
+) {
+  const {
+    payer,
+    pool,
+    inputTokenMint,
+    outputTokenMint,
+    amountOut,
+    maximumAmountIn,
+    referralTokenAccount,
+    referralAccount,
+  } = params;
+
+  const program = createCpAmmProgram();
+  const poolState = await getPool(banksClient, pool);
+
+  const poolAuthority = derivePoolAuthority();
+  const tokenAProgram = (await banksClient.getAccount(poolState.tokenAMint))
+    .owner;
+
+  const tokenBProgram = (await banksClient.getAccount(poolState.tokenBMint))
+    .owner;
+  const inputTokenProgram = (await banksClient.getAccount(inputTokenMint))
+    .owner;
+  const outputTokenProgram = (await banksClient.getAccount(outputTokenMint))
+    .owner;
+  const inputTokenAccount = getAssociatedTokenAddressSync(
+    inputTokenMint,
+    payer.publicKey,
+    true,
+    inputTokenProgram
+  );
+  const outputTokenAccount = getAssociatedTokenAddressSync(
+    outputTokenMint,
+    payer.publicKey,
+    true,
+    outputTokenProgram
+  );
+  const tokenAVault = poolState.tokenAVault;
+  const tokenBVault = poolState.tokenBVault;
+  const tokenAMint = poolState.tokenAMint;
+  const tokenBMint = poolState.tokenBMint;
+
+  const transaction = await program.methods
+    .swapExactOut({
+      amountOut,
+      maximumAmountIn,
+    })
+    .accounts({
+      poolAuthority,
+      pool,
+      payer: payer.publicKey,
+      inputTokenAccount,
+      outputTokenAccount,
+      tokenAVault,
+      tokenBVault,
+      tokenAProgram,
+      tokenBProgram,
+      tokenAMint,
+      tokenBMint,
+      referralTokenAccount,
+      referralAccount: referralAccount ?? null,
+      oracle: poolState.oracleFlag == 1 ? deriveOracleAddress(pool) : null,
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(payer);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
+}
+
+export type CreateReferralAccountParams = {
+  payer: Keypair;
+  referrer: PublicKey;
+  tokenMint: PublicKey;
+};
+
+export async function createReferralAccount(
+  banksClient: BanksClient,
+  params: CreateReferralAccountParams
+): Promise<PublicKey> {
+  const program = createCpAmmProgram();
+  const { payer, referrer, tokenMint } = params;
+
+  const referralAccount = deriveReferralAccountAddress(referrer, tokenMint);
+  const transaction = await program.methods
+    .createReferralAccount()
+    .accounts({
+      referralAccount,
+      referrer,
+      tokenMint,
+      payer: payer.publicKey,
+      systemProgram: SystemProgram.programId,
     })
     .transaction();
 
@@ -1424,6 +2547,58 @@ export async function swap(banksClient: BanksClient, params: SwapParams) {
   transaction.sign(payer);
 
   await processTransactionMaybeThrow(banksClient, transaction);
+
+  return referralAccount;
+}
+
+export type ClaimReferralFeeParams = {
+  referrer: Keypair;
+  pool: PublicKey;
+  tokenMint: PublicKey;
+  maxAmount: BN;
+};
+
+export async function claimReferralFee(
+  banksClient: BanksClient,
+  params: ClaimReferralFeeParams
+) {
+  const program = createCpAmmProgram();
+  const { referrer, pool, tokenMint, maxAmount } = params;
+  const poolAuthority = derivePoolAuthority();
+  const poolState = await getPool(banksClient, pool);
+  const tokenProgram = (await banksClient.getAccount(tokenMint)).owner;
+  const tokenVault = poolState.tokenAMint.equals(tokenMint)
+    ? poolState.tokenAVault
+    : poolState.tokenBVault;
+  const tokenAccount = await getOrCreateAssociatedTokenAccount(
+    banksClient,
+    referrer,
+    tokenMint,
+    referrer.publicKey,
+    tokenProgram
+  );
+
+  const transaction = await program.methods
+    .claimReferralFee(maxAmount)
+    .accounts({
+      poolAuthority,
+      pool,
+      referralAccount: deriveReferralAccountAddress(
+        referrer.publicKey,
+        tokenMint
+      ),
+      tokenAccount,
+      tokenVault,
+      tokenMint,
+      referrer: referrer.publicKey,
+      tokenProgram,
+    })
+    .transaction();
+
+  transaction.recentBlockhash = (await banksClient.getLatestBlockhash())[0];
+  transaction.sign(referrer);
+
+  await processTransactionMaybeThrow(banksClient, transaction);
 }
 
 export type ClaimpositionFeeParams = {
@@ -1549,3 +2724,15 @@ export async function getTokenBadge(
   const account = await banksClient.getAccount(tokenBadge);
   return program.coder.accounts.decode("tokenBadge", Buffer.from(account.data));
 }
+
+export async function getReferralAccount(
+  banksClient: BanksClient,
+  referralAccount: PublicKey
+): Promise<ReferralAccount> {
+  const program = createCpAmmProgram();
+  const account = await banksClient.getAccount(referralAccount);
+  return program.coder.accounts.decode(
+    "referralAccount",
+    Buffer.from(account.data)
+  );
+}
@@ -1,5 +1,11 @@
+import { expect } from "chai";
 import { ProgramTestContext } from "solana-bankrun";
-import { generateKpAndFund, randomID, startTest } from "./bankrun-utils/common";
+import {
+  expectThrowsAsync,
+  generateKpAndFund,
+  randomID,
+  startTest,
+} from "./bankrun-utils/common";
 import { Keypair, PublicKey } from "@solana/web3.js";
 import {
   addLiquidity,
@@ -16,14 +22,24 @@ import {
   SwapParams,
   createClaimFeeOperator,
   claimProtocolFee,
+  claimProtocolFeeBatch,
   TREASURY,
   claimPartnerFee,
   closeClaimFeeOperator,
   mintSplTokenTo,
   createToken,
+  updateProtocolFeeReceiver,
+  getPool,
+  createReferralAccount,
+  claimReferralFee,
+  getReferralAccount,
+  getTokenAccount,
 } from "./bankrun-utils";
 import BN from "bn.js";
-import { ExtensionType } from "@solana/spl-token";
+import {
+  ExtensionType,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
 import { createToken2022, mintToToken2022 } from "./bankrun-utils/token2022";
 
 describe("Claim fee", () => {
@@ -106,7 +122,7 @@ describe("Claim fee", () => {
           },
           protocolFeePercent: 10,
           partnerFeePercent: 0,
-          referralFeePercent: 0,
+          referralFeePercent: 20,
           dynamicFee: null,
         },
         sqrtMinPrice: new BN(MIN_SQRT_PRICE),
@@ -115,6 +131,7 @@ describe("Claim fee", () => {
         poolCreatorAuthority: partner.publicKey,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
@@ -181,6 +198,8 @@ describe("Claim fee", () => {
         operator,
         pool,
         treasury: TREASURY,
+        maxAmountA: new BN(100000000000000),
+        maxAmountB: new BN(100000000000000),
       });
 
       // claim partner fee
@@ -200,6 +219,205 @@ describe("Claim fee", () => {
         rentReceiver: operator.publicKey,
       });
     });
+
+    it("Claim protocol fee in batch", async () => {
+      const addLiquidityParams: AddLiquidityParams = {
+        owner: user,
+        pool,
+        position,
+        liquidityDelta: MIN_SQRT_PRICE,
+        tokenAAmountThreshold: new BN(2_000_000_000),
+        tokenBAmountThreshold: new BN(2_000_000_000),
+      };
+      await addLiquidity(context.banksClient, addLiquidityParams);
+
+      await swap(context.banksClient, {
+        payer: user,
+        pool,
+        inputTokenMint,
+        outputTokenMint,
+        amountIn: new BN(1_000_000),
+        minimumAmountOut: new BN(0),
+        referralTokenAccount: null,
+      });
+
+      // the second entry has no protocol fee left and is skipped
+      await claimProtocolFeeBatch(context.banksClient, {
+        operator,
+        pools: [pool, pool],
+        treasury: TREASURY,
+      });
+      const poolState = await getPool(context.banksClient, pool);
+      expect(poolState.protocolAFee.toNumber()).eq(0);
+      expect(poolState.protocolBFee.toNumber()).eq(0);
+    });
+
+    it("Claim protocol fee of token b only", async () => {
+      const addLiquidityParams: AddLiquidityParams = {
+        owner: user,
+        pool,
+        position,
+        liquidityDelta: MIN_SQRT_PRICE,
+        tokenAAmountThreshold: new BN(2_000_000_000),
+        tokenBAmountThreshold: new BN(2_000_000_000),
+      };
+      await addLiquidity(context.banksClient, addLiquidityParams);
+
+      // collect fee in both tokens
+      for (const [inputMint, outputMint] of [
+        [inputTokenMint, outputTokenMint],
+        [outputTokenMint, inputTokenMint],
+      ]) {
+        await swap(context.banksClient, {
+          payer: user,
+          pool,
+          inputTokenMint: inputMint,
+          outputTokenMint: outputMint,
+          amountIn: new BN(1_000_000),
+          minimumAmountOut: new BN(0),
+          referralTokenAccount: null,
+        });
+      }
+      const beforePoolState = await getPool(context.banksClient, pool);
+      expect(beforePoolState.protocolAFee.toNumber()).gt(0);
+
+      // token a account of treasury is not passed
+      await claimProtocolFee(context.banksClient, {
+        operator,
+        pool,
+        treasury: TREASURY,
+        maxAmountA: new BN(0),
+        maxAmountB: new BN(100000000000000),
+      });
+      const poolState = await getPool(context.banksClient, pool);
+      expect(poolState.protocolAFee.toString()).eq(
+        beforePoolState.protocolAFee.toString()
+      );
+      expect(poolState.protocolBFee.toNumber()).eq(0);
+    });
+
+    it("Claim protocol fee to custom receiver", async () => {
+      const addLiquidityParams: AddLiquidityParams = {
+        owner: user,
+        pool,
+        position,
+        liquidityDelta: MIN_SQRT_PRICE,
+        tokenAAmountThreshold: new BN(2_000_000_000),
+        tokenBAmountThreshold: new BN(2_000_000_000),
+      };
+      await addLiquidity(context.banksClient, addLiquidityParams);
+
+      await swap(context.banksClient, {
+        payer: user,
+        pool,
+        inputTokenMint,
+        outputTokenMint,
+        amountIn: new BN(1_000_000),
+        minimumAmountOut: new BN(0),
+        referralTokenAccount: null,
+      });
+
+      const protocolFeeReceiver = Keypair.generate().publicKey;
+      await updateProtocolFeeReceiver(context.banksClient, {
+        admin,
+        pool,
+        protocolFeeReceiver,
+      });
+
+      // treasury is not the receiver anymore, ConstraintTokenOwner
+      await expectThrowsAsync(async () => {
+        await claimProtocolFee(context.banksClient, {
+          operator,
+          pool,
+          treasury: TREASURY,
+          maxAmountA: new BN(100000000000000),
+          maxAmountB: new BN(100000000000000),
+        });
+      }, "0x7df");
+
+      await claimProtocolFee(context.banksClient, {
+        operator,
+        pool,
+        treasury: protocolFeeReceiver,
+        maxAmountA: new BN(100000000000000),
+        maxAmountB: new BN(100000000000000),
+      });
+      const poolState = await getPool(context.banksClient, pool);
+      expect(poolState.protocolAFee.toNumber()).eq(0);
+      expect(poolState.protocolBFee.toNumber()).eq(0);
+    });
+
+    it("Accrue referral fee and claim", async () => {
+      const addLiquidityParams: AddLiquidityParams = {
+        owner: user,
+        pool,
+        position,
+        liquidityDelta: MIN_SQRT_PRICE,
+        tokenAAmountThreshold: new BN(2_000_000_000),
+        tokenBAmountThreshold: new BN(2_000_000_000),
+      };
+      await addLiquidity(context.banksClient, addLiquidityParams);
+
+      const referrer = await generateKpAndFund(
+        context.banksClient,
+        context.payer
+      );
+      // fee is collected in output token
+      const referralAccount = await createReferralAccount(
+        context.banksClient,
+        {
+          payer: user,
+          referrer: referrer.publicKey,
+          tokenMint: outputTokenMint,
+        }
+      );
+
+      await swap(context.banksClient, {
+        payer: user,
+        pool,
+        inputTokenMint,
+        outputTokenMint,
+        amountIn: new BN(1_000_000),
+        minimumAmountOut: new BN(0),
+        referralTokenAccount: null,
+        referralAccount,
+      });
+
+      let referralAccountState = await getReferralAccount(
+        context.banksClient,
+        referralAccount
+      );
+      let poolState = await getPool(context.banksClient, pool);
+      const pendingFee = referralAccountState.pendingFee;
+      expect(referralAccountState.tokenMint.toString()).eq(
+        outputTokenMint.toString()
+      );
+      expect(pendingFee.toString()).eq(poolState.referralBFee.toString());
+      expect(referralAccountState.totalFee.toString()).eq(
+        pendingFee.toString()
+      );
+
+      await claimReferralFee(context.banksClient, {
+        referrer,
+        pool,
+        tokenMint: outputTokenMint,
+        maxAmount: new BN(100000000000000),
+      });
+
+      const referrerTokenAccount = await getTokenAccount(
+        context.banksClient,
+        getAssociatedTokenAddressSync(outputTokenMint, referrer.publicKey)
+      );
+      expect(referrerTokenAccount.amount.toString()).eq(pendingFee.toString());
+
+      referralAccountState = await getReferralAccount(
+        context.banksClient,
+        referralAccount
+      );
+      poolState = await getPool(context.banksClient, pool);
+      expect(referralAccountState.pendingFee.toNumber()).eq(0);
+      expect(poolState.referralBFee.toNumber()).eq(0);
+    });
   });
 
   describe("Token 2022", () => {
@@ -290,6 +508,7 @@ describe("Claim fee", () => {
         poolCreatorAuthority: partner.publicKey,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
@@ -356,6 +575,8 @@ describe("Claim fee", () => {
         operator,
         pool,
         treasury: TREASURY,
+        maxAmountA: new BN(100000000000000),
+        maxAmountB: new BN(100000000000000),
       });
 
       // claim partner fee
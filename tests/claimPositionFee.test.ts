@@ -105,6 +105,7 @@ describe("Claim position fee", () => {
       poolCreatorAuthority: PublicKey.default,
       activationType: 0,
       collectFeeMode: 0,
+      protocolFeeReceiver: PublicKey.default,
     };
 
     config = await createConfigIx(
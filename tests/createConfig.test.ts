@@ -8,9 +8,11 @@ import {
   closeConfigIx,
   createConfigIx,
   CreateConfigParams,
+  DEFAULT_EXTRA_FEES,
   MAX_SQRT_PRICE,
   MIN_SQRT_PRICE,
   OFFSET,
+  updateConfigIx,
 } from "./bankrun-utils";
 import { shlDiv } from "./bankrun-utils/math";
 
@@ -45,6 +47,7 @@ describe("Admin function: Create config", () => {
       poolCreatorAuthority: PublicKey.default,
       activationType: 0,
       collectFeeMode: 0,
+      protocolFeeReceiver: PublicKey.default,
     };
   });
 
@@ -61,9 +64,39 @@ describe("Admin function: Create config", () => {
     await closeConfigIx(context.banksClient, admin, config);
   });
 
+  it("Admin update config", async () => {
+    const config = await createConfigIx(
+      context.banksClient,
+      admin,
+      createConfigParams
+    );
+    await updateConfigIx(context.banksClient, admin, config, {
+      ...createConfigParams.poolFees,
+      baseFee: {
+        ...createConfigParams.poolFees.baseFee,
+        cliffFeeNumerator: new BN(5_000_000),
+      },
+      protocolFeePercent: 20,
+      referralFeePercent: 20,
+      dynamicFee: {
+        binStep: 10,
+        binStepU128: shlDiv(
+          new BN(10),
+          new BN(BASIS_POINT_MAX),
+          OFFSET
+        ),
+        filterPeriod: 10,
+        decayPeriod: 120,
+        reductionFactor: 5000,
+        maxVolatilityAccumulator: 14460000,
+        variableFeeControl: 5,
+      },
+    });
+  });
+
   it("Admin create config with dynamic fee", async () => {
     // params
-    const binStep = new BN(1);
+    const binStep = new BN(10);
     const binStepU128 = shlDiv(binStep, new BN(BASIS_POINT_MAX), OFFSET);
     const decayPeriod = 5_000;
     const filterPeriod = 2_000;
@@ -101,8 +134,53 @@ describe("Admin function: Create config", () => {
       poolCreatorAuthority: PublicKey.default,
       activationType: 0,
       collectFeeMode: 0,
+      protocolFeeReceiver: PublicKey.default,
     };
 
     await createConfigIx(context.banksClient, admin, createConfigParams);
   });
+
+  it("Admin create config with fee curve", async () => {
+    // 50% drops to 5% in 10 minutes, then slowly to 1% in 1 day
+    const createConfigParams: CreateConfigParams = {
+      index: new BN(randomID()),
+      poolFees: {
+        baseFee: {
+          cliffFeeNumerator: new BN(500_000_000),
+          numberOfPeriod: 0,
+          reductionFactor: new BN(0),
+          periodFrequency: new BN(0),
+          feeSchedulerMode: 3,
+        },
+        protocolFeePercent: 10,
+        partnerFeePercent: 0,
+        referralFeePercent: 0,
+        dynamicFee: null,
+      },
+      sqrtMinPrice: new BN(MIN_SQRT_PRICE),
+      sqrtMaxPrice: new BN(MAX_SQRT_PRICE),
+      vaultConfigKey: PublicKey.default,
+      poolCreatorAuthority: PublicKey.default,
+      activationType: 0,
+      collectFeeMode: 0,
+      protocolFeeReceiver: PublicKey.default,
+    };
+
+    await createConfigIx(context.banksClient, admin, createConfigParams, {
+      ...DEFAULT_EXTRA_FEES,
+      feeCurve: {
+        points: [
+          { pointOffset: 1500, feeNumerator: 50_000_000 },
+          { pointOffset: 216_000, feeNumerator: 10_000_000 },
+        ],
+      },
+    });
+  });
+
+  it("Admin create config with higher fee on a to b", async () => {
+    await createConfigIx(context.banksClient, admin, createConfigParams, {
+      ...DEFAULT_EXTRA_FEES,
+      aToBFeeMultiplierBps: 20_000,
+    });
+  });
 });
@@ -3,6 +3,7 @@ import { ProgramTestContext } from "solana-bankrun";
 import { generateKpAndFund, startTest } from "./bankrun-utils/common";
 import { Keypair, PublicKey } from "@solana/web3.js";
 import {
+  BASIS_POINT_MAX,
   createConfigIx,
   CreateConfigParams,
   getPool,
@@ -12,9 +13,12 @@ import {
   MAX_SQRT_PRICE,
   MIN_SQRT_PRICE,
   setPoolStatus,
+  updatePoolFees,
   createToken,
   mintSplTokenTo,
+  OFFSET,
 } from "./bankrun-utils";
+import { shlDiv } from "./bankrun-utils/math";
 import BN from "bn.js";
 import { ExtensionType } from "@solana/spl-token";
 import { createToken2022, mintToToken2022 } from "./bankrun-utils/token2022";
@@ -85,6 +89,7 @@ describe("Initialize pool", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
@@ -123,6 +128,69 @@ describe("Initialize pool", () => {
       const poolState = await getPool(context.banksClient, pool);
       expect(poolState.poolStatus).eq(newStatus);
     });
+
+    it("Initialize pool & update pool fees", async () => {
+      liquidity = new BN(MIN_LP_AMOUNT);
+      sqrtPrice = new BN(MIN_SQRT_PRICE);
+
+      const initPoolParams: InitializePoolParams = {
+        payer: creator,
+        creator: creator.publicKey,
+        config,
+        tokenAMint,
+        tokenBMint,
+        liquidity,
+        sqrtPrice,
+        activationPoint: null,
+      };
+
+      const { pool } = await initializePool(
+        context.banksClient,
+        initPoolParams
+      );
+
+      await updatePoolFees(context.banksClient, {
+        authority: admin,
+        pool,
+        poolFees: {
+          baseFee: {
+            cliffFeeNumerator: new BN(5_000_000),
+            numberOfPeriod: 0,
+            reductionFactor: new BN(0),
+            periodFrequency: new BN(0),
+            feeSchedulerMode: 0,
+          },
+          protocolFeePercent: 20,
+          partnerFeePercent: 0,
+          referralFeePercent: 20,
+          dynamicFee: {
+            binStep: 10,
+            binStepU128: shlDiv(
+              new BN(10),
+              new BN(BASIS_POINT_MAX),
+              OFFSET
+            ),
+            filterPeriod: 10,
+            decayPeriod: 120,
+            reductionFactor: 5000,
+            maxVolatilityAccumulator: 14460000,
+            variableFeeControl: 5,
+          },
+        },
+      });
+
+      const poolState = await getPool(context.banksClient, pool);
+      const { poolFees } = poolState;
+      expect(poolFees.baseFee.cliffFeeNumerator.toNumber()).eq(5_000_000);
+      expect(poolFees.protocolFeePercent).eq(20);
+      expect(poolFees.referralFeePercent).eq(20);
+      expect(poolFees.dynamicFee.initialized).eq(1);
+      expect(poolFees.dynamicFee.binStep).eq(10);
+      expect(poolFees.dynamicFee.sqrtPriceReference.toString()).eq(
+        poolState.sqrtPrice.toString()
+      );
+      expect(poolFees.dynamicFee.volatilityAccumulator.toNumber()).eq(0);
+    });
   });
 
   describe("Token 2022", () => {
@@ -191,6 +259,7 @@ describe("Initialize pool", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
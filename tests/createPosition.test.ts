@@ -87,6 +87,7 @@ describe("Create position", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       const config = await createConfigIx(
@@ -186,6 +187,7 @@ describe("Create position", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       const config = await createConfigIx(
@@ -17,10 +17,12 @@ import {
   MIN_LP_AMOUNT,
   lockPosition,
   LockPositionParams,
+  mergePositions,
   MAX_SQRT_PRICE,
   MIN_SQRT_PRICE,
   permanentLockPosition,
   refreshVestings,
+  splitPosition,
   swap,
   SwapParams,
   mintSplTokenTo,
@@ -124,6 +126,7 @@ describe("Lock position", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
@@ -348,6 +351,92 @@ describe("Lock position", () => {
         expect(positionState.unlockedLiquidity.isZero()).to.be.true;
         expect(!positionState.permanentLockedLiquidity.isZero()).to.be.true;
       });
+
+      it("Split permanent locked position", async () => {
+        await permanentLockPosition(context.banksClient, position, user, user);
+
+        const beforePositionState = await getPosition(
+          context.banksClient,
+          position
+        );
+
+        const newPosition = await splitPosition(
+          context.banksClient,
+          position,
+          user,
+          admin.publicKey,
+          user,
+          {
+            unlockedLiquidityPercentage: 0,
+            vestedLiquidityPercentage: 0,
+            permanentLockedLiquidityPercentage: 50,
+            feeAPercentage: 50,
+            feeBPercentage: 50,
+            reward0Percentage: 0,
+            reward1Percentage: 0,
+            reward2Percentage: 0,
+            reward3Percentage: 0,
+          }
+        );
+
+        const positionState = await getPosition(context.banksClient, position);
+        const newPositionState = await getPosition(
+          context.banksClient,
+          newPosition
+        );
+        expect(
+          positionState.permanentLockedLiquidity
+            .add(newPositionState.permanentLockedLiquidity)
+            .eq(beforePositionState.permanentLockedLiquidity)
+        ).to.be.true;
+        expect(
+          newPositionState.permanentLockedLiquidity.eq(
+            beforePositionState.permanentLockedLiquidity.divn(2)
+          )
+        ).to.be.true;
+      });
+
+      it("Merge split positions", async () => {
+        const beforePositionState = await getPosition(
+          context.banksClient,
+          position
+        );
+
+        const newPosition = await splitPosition(
+          context.banksClient,
+          position,
+          user,
+          user.publicKey,
+          user,
+          {
+            unlockedLiquidityPercentage: 50,
+            vestedLiquidityPercentage: 0,
+            permanentLockedLiquidityPercentage: 0,
+            feeAPercentage: 0,
+            feeBPercentage: 0,
+            reward0Percentage: 0,
+            reward1Percentage: 0,
+            reward2Percentage: 0,
+            reward3Percentage: 0,
+          }
+        );
+
+        await mergePositions(
+          context.banksClient,
+          position,
+          newPosition,
+          user,
+          user
+        );
+
+        const positionState = await getPosition(context.banksClient, position);
+        expect(
+          positionState.unlockedLiquidity.eq(
+            beforePositionState.unlockedLiquidity
+          )
+        ).to.be.true;
+        expect(await context.banksClient.getAccount(newPosition)).to.be.null;
+      });
     });
   });
 
@@ -441,6 +530,7 @@ describe("Lock position", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
@@ -1,3 +1,4 @@
+import { expect } from "chai";
 import { ProgramTestContext } from "solana-bankrun";
 import { generateKpAndFund, randomID, startTest } from "./bankrun-utils/common";
 import { Keypair, PublicKey } from "@solana/web3.js";
@@ -14,6 +15,9 @@ import {
   mintSplTokenTo,
   createToken,
   removeAllLiquidity,
+  removeLiquiditySingleToken,
+  swap,
+  getPosition,
 } from "./bankrun-utils";
 import BN from "bn.js";
 import { ExtensionType } from "@solana/spl-token";
@@ -104,6 +108,7 @@ describe("Remove liquidity", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
@@ -169,6 +174,47 @@ describe("Remove liquidity", () => {
       };
       await removeAllLiquidity(context.banksClient, removeAllLiquidityParams);
     });
+
+    it("User remove liquidity to a single token", async () => {
+      const position = await createPosition(
+        context.banksClient,
+        user,
+        user.publicKey,
+        pool
+      );
+
+      await addLiquidity(context.banksClient, {
+        owner: user,
+        pool,
+        position,
+        liquidityDelta: new BN(MIN_LP_AMOUNT),
+        tokenAAmountThreshold: U64_MAX,
+        tokenBAmountThreshold: U64_MAX,
+      });
+
+      // move the price up so that the position holds both tokens
+      await swap(context.banksClient, {
+        payer: user,
+        pool,
+        inputTokenMint: tokenBMint,
+        outputTokenMint: tokenAMint,
+        amountIn: new BN(1_000),
+        minimumAmountOut: new BN(0),
+        referralTokenAccount: null,
+      });
+
+      await removeLiquiditySingleToken(context.banksClient, {
+        owner: user,
+        pool,
+        position,
+        outputTokenMint: tokenAMint,
+        liquidityDelta: new BN(MIN_LP_AMOUNT),
+        minimumAmountOut: new BN(1),
+      });
+
+      const positionState = await getPosition(context.banksClient, position);
+      expect(positionState.unlockedLiquidity.isZero()).to.be.true;
+    });
   });
 
   describe("Token 2022", () => {
@@ -255,6 +301,7 @@ describe("Remove liquidity", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
@@ -1,19 +1,30 @@
+import { expect } from "chai";
 import { Clock, ProgramTestContext } from "solana-bankrun";
-import { generateKpAndFund, startTest } from "./bankrun-utils/common";
+import {
+  expectThrowsAsync,
+  generateKpAndFund,
+  startTest,
+} from "./bankrun-utils/common";
 import { Keypair, PublicKey } from "@solana/web3.js";
 import {
   addLiquidity,
   AddLiquidityParams,
   claimReward,
+  claimAllRewards,
   createConfigIx,
   CreateConfigParams,
   createPosition,
+  createPositionRewardExtension,
+  derivePositionRewardExtensionAddress,
   fundReward,
   getPool,
   initializePool,
   InitializePoolParams,
   initializeReward,
   InitializeRewardParams,
+  initializeRewardExtension,
+  initializeRewardByCreator,
+  getStakeProgramErrorCodeHexString,
   MIN_LP_AMOUNT,
   MAX_SQRT_PRICE,
   MIN_SQRT_PRICE,
@@ -22,10 +33,16 @@ import {
   withdrawIneligibleReward,
   createToken,
   mintSplTokenTo,
+  getPosition,
+  getPositionRewardExtension,
+  getTokenAccount,
 } from "./bankrun-utils";
 import BN from "bn.js";
 import { describe } from "mocha";
-import { ExtensionType } from "@solana/spl-token";
+import {
+  ExtensionType,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
 import { createToken2022, mintToToken2022 } from "./bankrun-utils/token2022";
 
 describe("Reward unit-testing", () => {
@@ -138,6 +155,7 @@ describe("Reward unit-testing", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
@@ -251,6 +269,477 @@ describe("Reward unit-testing", () => {
         pool,
       });
     });
+
+    it("Claim all rewards of many positions", async () => {
+      const initPoolParams: InitializePoolParams = {
+        payer: creator,
+        creator: creator.publicKey,
+        config,
+        tokenAMint,
+        tokenBMint,
+        liquidity: new BN(MIN_LP_AMOUNT),
+        sqrtPrice: new BN(MIN_SQRT_PRICE),
+        activationPoint: null,
+      };
+
+      const { pool } = await initializePool(
+        context.banksClient,
+        initPoolParams
+      );
+
+      const positions = [];
+      for (let i = 0; i < 2; i++) {
+        const position = await createPosition(
+          context.banksClient,
+          user,
+          user.publicKey,
+          pool
+        );
+        await addLiquidity(context.banksClient, {
+          owner: user,
+          pool,
+          position,
+          liquidityDelta: new BN(100),
+          tokenAAmountThreshold: new BN(200),
+          tokenBAmountThreshold: new BN(200),
+        });
+        positions.push(position);
+      }
+
+      const index = 0;
+      await initializeReward(context.banksClient, {
+        index,
+        payer: admin,
+        rewardDuration: new BN(24 * 60 * 60),
+        pool,
+        rewardMint,
+      });
+      await fundReward(context.banksClient, {
+        index,
+        funder: admin,
+        pool,
+        carryForward: true,
+        amount: new BN("1000000"),
+      });
+
+      const currentClock = await context.banksClient.getClock();
+      context.setClock(
+        new Clock(
+          currentClock.slot,
+          currentClock.epochStartTimestamp,
+          currentClock.epoch,
+          currentClock.leaderScheduleEpoch,
+          currentClock.unixTimestamp + BigInt(3600)
+        )
+      );
+
+      await claimAllRewards(context.banksClient, {
+        user,
+        pool,
+        positions,
+        claimFee: true,
+      });
+
+      let totalClaimedRewards = new BN(0);
+      for (const position of positions) {
+        const positionState = await getPosition(context.banksClient, position);
+        expect(positionState.rewardInfos[index].rewardPendings.toNumber()).eq(
+          0
+        );
+        totalClaimedRewards = totalClaimedRewards.add(
+          positionState.rewardInfos[index].totalClaimedRewards
+        );
+      }
+
+      const userRewardAccount = await getTokenAccount(
+        context.banksClient,
+        getAssociatedTokenAddressSync(rewardMint, user.publicKey)
+      );
+      expect(userRewardAccount.amount.toString()).eq(
+        totalClaimedRewards.toString()
+      );
+    });
+
+    it("Creator initializes and funds the creator reward", async () => {
+      const initPoolParams: InitializePoolParams = {
+        payer: creator,
+        creator: creator.publicKey,
+        config,
+        tokenAMint,
+        tokenBMint,
+        liquidity: new BN(MIN_LP_AMOUNT),
+        sqrtPrice: new BN(MIN_SQRT_PRICE),
+        activationPoint: null,
+      };
+
+      const { pool } = await initializePool(
+        context.banksClient,
+        initPoolParams
+      );
+
+      // the creator can only take the creator reward index
+      await expectThrowsAsync(async () => {
+        await initializeRewardByCreator(context.banksClient, {
+          index: 0,
+          creator,
+          rewardDuration: new BN(24 * 60 * 60),
+          pool,
+          rewardMint: tokenBMint,
+          funder: creator.publicKey,
+        });
+      }, getStakeProgramErrorCodeHexString("InvalidRewardIndex"));
+
+      // other users can't initialize the creator reward
+      await expectThrowsAsync(async () => {
+        await initializeRewardByCreator(context.banksClient, {
+          index: 1,
+          creator: user,
+          rewardDuration: new BN(24 * 60 * 60),
+          pool,
+          rewardMint: tokenBMint,
+          funder: user.publicKey,
+        });
+      }, getStakeProgramErrorCodeHexString("InvalidRewardCreator"));
+
+      const index = 1;
+      await initializeRewardByCreator(context.banksClient, {
+        index,
+        creator,
+        rewardDuration: new BN(24 * 60 * 60),
+        pool,
+        rewardMint: tokenBMint,
+        funder: creator.publicKey,
+      });
+
+      await fundReward(context.banksClient, {
+        index,
+        funder: creator,
+        pool,
+        carryForward: true,
+        amount: new BN("1000000"),
+      });
+
+      const poolState = await getPool(context.banksClient, pool);
+      expect(poolState.creator.toString()).eq(creator.publicKey.toString());
+      expect(poolState.rewardInfos[index].rewardRate.isZero()).eq(false);
+    });
+
+    it("Fund reward scheduled to start in the future", async () => {
+      const initPoolParams: InitializePoolParams = {
+        payer: creator,
+        creator: creator.publicKey,
+        config,
+        tokenAMint,
+        tokenBMint,
+        liquidity: new BN(MIN_LP_AMOUNT),
+        sqrtPrice: new BN(MIN_SQRT_PRICE),
+        activationPoint: null,
+      };
+
+      const { pool, position } = await initializePool(
+        context.banksClient,
+        initPoolParams
+      );
+
+      const index = 0;
+      const rewardDuration = new BN(24 * 60 * 60);
+      await initializeReward(context.banksClient, {
+        index,
+        payer: admin,
+        rewardDuration,
+        pool,
+        rewardMint,
+      });
+      await updateRewardFunder(context.banksClient, {
+        index,
+        admin: admin,
+        pool,
+        newFunder: funder.publicKey,
+      });
+
+      const currentClock = await context.banksClient.getClock();
+      const currentTime = new BN(currentClock.unixTimestamp.toString());
+
+      // the reward can't start in the past
+      await expectThrowsAsync(async () => {
+        await fundReward(context.banksClient, {
+          index,
+          funder,
+          pool,
+          carryForward: true,
+          amount: new BN("1000000"),
+          rewardStartTime: currentTime.subn(1),
+        });
+      }, getStakeProgramErrorCodeHexString("InvalidRewardStartTime"));
+
+      const rewardStartTime = currentTime.addn(3600);
+      await fundReward(context.banksClient, {
+        index,
+        funder,
+        pool,
+        carryForward: true,
+        amount: new BN("1000000"),
+        rewardStartTime,
+      });
+
+      let poolState = await getPool(context.banksClient, pool);
+      let rewardInfo = poolState.rewardInfos[index];
+      expect(rewardInfo.rewardStartTime.toString()).eq(
+        rewardStartTime.toString()
+      );
+      expect(rewardInfo.rewardDurationEnd.toString()).eq(
+        rewardStartTime.add(rewardDuration).toString()
+      );
+
+      // no reward accrues before the start
+      for (const timestamp of [rewardStartTime.subn(1), rewardStartTime]) {
+        context.setClock(
+          new Clock(
+            currentClock.slot,
+            currentClock.epochStartTimestamp,
+            currentClock.epoch,
+            currentClock.leaderScheduleEpoch,
+            BigInt(timestamp.toString())
+          )
+        );
+        await claimReward(context.banksClient, {
+          index,
+          user: creator,
+          pool,
+          position,
+        });
+      }
+      poolState = await getPool(context.banksClient, pool);
+      rewardInfo = poolState.rewardInfos[index];
+      expect(new BN(rewardInfo.rewardPerTokenStored, "le").isZero()).eq(true);
+
+      context.setClock(
+        new Clock(
+          currentClock.slot,
+          currentClock.epochStartTimestamp,
+          currentClock.epoch,
+          currentClock.leaderScheduleEpoch,
+          BigInt(rewardStartTime.addn(3600).toString())
+        )
+      );
+      await claimReward(context.banksClient, {
+        index,
+        user: creator,
+        pool,
+        position,
+      });
+      poolState = await getPool(context.banksClient, pool);
+      rewardInfo = poolState.rewardInfos[index];
+      expect(new BN(rewardInfo.rewardPerTokenStored, "le").isZero()).eq(false);
+    });
+
+    it("Claim reward of the reward extension", async () => {
+      const initPoolParams: InitializePoolParams = {
+        payer: creator,
+        creator: creator.publicKey,
+        config,
+        tokenAMint,
+        tokenBMint,
+        liquidity: new BN(MIN_LP_AMOUNT),
+        sqrtPrice: new BN(MIN_SQRT_PRICE),
+        activationPoint: null,
+      };
+
+      const { pool } = await initializePool(
+        context.banksClient,
+        initPoolParams
+      );
+
+      const position = await createPosition(
+        context.banksClient,
+        user,
+        user.publicKey,
+        pool
+      );
+
+      await initializeRewardExtension(context.banksClient, {
+        admin,
+        pool,
+      });
+      await createPositionRewardExtension(context.banksClient, {
+        payer: user,
+        owner: user,
+        pool,
+        position,
+      });
+
+      await addLiquidity(context.banksClient, {
+        owner: user,
+        pool,
+        position,
+        liquidityDelta: new BN(100),
+        tokenAAmountThreshold: new BN(200),
+        tokenBAmountThreshold: new BN(200),
+      });
+
+      // the first reward index of the reward extension
+      const index = 2;
+      await initializeReward(context.banksClient, {
+        index,
+        payer: admin,
+        rewardDuration: new BN(24 * 60 * 60),
+        pool,
+        rewardMint,
+      });
+      await fundReward(context.banksClient, {
+        index,
+        funder: admin,
+        pool,
+        carryForward: true,
+        amount: new BN("1000000"),
+      });
+
+      const currentClock = await context.banksClient.getClock();
+      context.setClock(
+        new Clock(
+          currentClock.slot,
+          currentClock.epochStartTimestamp,
+          currentClock.epoch,
+          currentClock.leaderScheduleEpoch,
+          currentClock.unixTimestamp + BigInt(3600)
+        )
+      );
+
+      await claimReward(context.banksClient, {
+        index,
+        user,
+        pool,
+        position,
+      });
+
+      const positionRewardExtensionState = await getPositionRewardExtension(
+        context.banksClient,
+        derivePositionRewardExtensionAddress(position)
+      );
+      const rewardInfo = positionRewardExtensionState.rewardInfos[0];
+      expect(rewardInfo.rewardPendings.toNumber()).eq(0);
+      expect(rewardInfo.totalClaimedRewards.toNumber()).greaterThan(0);
+
+      const userRewardAccount = await getTokenAccount(
+        context.banksClient,
+        getAssociatedTokenAddressSync(rewardMint, user.publicKey)
+      );
+      expect(userRewardAccount.amount.toString()).eq(
+        rewardInfo.totalClaimedRewards.toString()
+      );
+    });
+
+    it("Claim all rewards of the reward extension", async () => {
+      const initPoolParams: InitializePoolParams = {
+        payer: creator,
+        creator: creator.publicKey,
+        config,
+        tokenAMint,
+        tokenBMint,
+        liquidity: new BN(MIN_LP_AMOUNT),
+        sqrtPrice: new BN(MIN_SQRT_PRICE),
+        activationPoint: null,
+      };
+
+      const { pool } = await initializePool(
+        context.banksClient,
+        initPoolParams
+      );
+
+      await initializeRewardExtension(context.banksClient, {
+        admin,
+        pool,
+      });
+
+      const positions = [];
+      for (let i = 0; i < 2; i++) {
+        const position = await createPosition(
+          context.banksClient,
+          user,
+          user.publicKey,
+          pool
+        );
+        await createPositionRewardExtension(context.banksClient, {
+          payer: user,
+          owner: user,
+          pool,
+          position,
+        });
+        await addLiquidity(context.banksClient, {
+          owner: user,
+          pool,
+          position,
+          liquidityDelta: new BN(100),
+          tokenAAmountThreshold: new BN(200),
+          tokenBAmountThreshold: new BN(200),
+        });
+        positions.push(position);
+      }
+
+      // the first reward index of the pool and of the reward extension
+      for (const index of [0, 2]) {
+        await initializeReward(context.banksClient, {
+          index,
+          payer: admin,
+          rewardDuration: new BN(24 * 60 * 60),
+          pool,
+          rewardMint,
+        });
+        await fundReward(context.banksClient, {
+          index,
+          funder: admin,
+          pool,
+          carryForward: true,
+          amount: new BN("1000000"),
+        });
+      }
+
+      const currentClock = await context.banksClient.getClock();
+      context.setClock(
+        new Clock(
+          currentClock.slot,
+          currentClock.epochStartTimestamp,
+          currentClock.epoch,
+          currentClock.leaderScheduleEpoch,
+          currentClock.unixTimestamp + BigInt(3600)
+        )
+      );
+
+      await claimAllRewards(context.banksClient, {
+        user,
+        pool,
+        positions,
+        claimFee: false,
+      });
+
+      let totalClaimedRewards = new BN(0);
+      for (const position of positions) {
+        const positionState = await getPosition(context.banksClient, position);
+        const positionRewardExtensionState = await getPositionRewardExtension(
+          context.banksClient,
+          derivePositionRewardExtensionAddress(position)
+        );
+        const rewardInfos = [
+          positionState.rewardInfos[0],
+          positionRewardExtensionState.rewardInfos[0],
+        ];
+        for (const rewardInfo of rewardInfos) {
+          expect(rewardInfo.rewardPendings.toNumber()).eq(0);
+          expect(rewardInfo.totalClaimedRewards.toNumber()).greaterThan(0);
+          totalClaimedRewards = totalClaimedRewards.add(
+            rewardInfo.totalClaimedRewards
+          );
+        }
+      }
+
+      const userRewardAccount = await getTokenAccount(
+        context.banksClient,
+        getAssociatedTokenAddressSync(rewardMint, user.publicKey)
+      );
+      expect(userRewardAccount.amount.toString()).eq(
+        totalClaimedRewards.toString()
+      );
+    });
   });
 
   // SPL-Token2022
@@ -365,6 +854,7 @@ describe("Reward unit-testing", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
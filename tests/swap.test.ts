@@ -1,4 +1,5 @@
-import { ProgramTestContext } from "solana-bankrun";
+import { expect } from "chai";
+import { Clock, ProgramTestContext } from "solana-bankrun";
 import { generateKpAndFund, randomID, startTest } from "./bankrun-utils/common";
 import { Keypair, PublicKey } from "@solana/web3.js";
 import {
@@ -14,11 +15,22 @@ import {
   MIN_SQRT_PRICE,
   swap,
   SwapParams,
+  swapExactOut,
+  SwapExactOutParams,
+  swapWithPriceLimit,
+  SwapWithPriceLimitParams,
+  getPool,
   createToken,
   mintSplTokenTo,
+  getTokenAccount,
+  initializeOracle,
+  getOracle,
 } from "./bankrun-utils";
 import BN from "bn.js";
-import { ExtensionType } from "@solana/spl-token";
+import {
+  ExtensionType,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
 import { createToken2022, mintToToken2022 } from "./bankrun-utils/token2022";
 
 describe("Swap token", () => {
@@ -108,6 +120,7 @@ describe("Swap token", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(
@@ -163,6 +176,153 @@ describe("Swap token", () => {
 
       await swap(context.banksClient, swapParams);
     });
+
+    it("User swap A->B records pool oracle", async () => {
+      const addLiquidityParams: AddLiquidityParams = {
+        owner: user,
+        pool,
+        position,
+        liquidityDelta: new BN(MIN_SQRT_PRICE.muln(30)),
+        tokenAAmountThreshold: new BN(200),
+        tokenBAmountThreshold: new BN(200),
+      };
+      await addLiquidity(context.banksClient, addLiquidityParams);
+
+      const oracle = await initializeOracle(context.banksClient, {
+        admin,
+        pool,
+      });
+      const poolState = await getPool(context.banksClient, pool);
+      expect(poolState.oracleFlag).eq(1);
+
+      const currentClock = await context.banksClient.getClock();
+      const timestamp = currentClock.unixTimestamp + BigInt(100);
+      context.setClock(
+        new Clock(
+          currentClock.slot,
+          currentClock.epochStartTimestamp,
+          currentClock.epoch,
+          currentClock.leaderScheduleEpoch,
+          timestamp
+        )
+      );
+
+      const swapParams: SwapParams = {
+        payer: user,
+        pool,
+        inputTokenMint,
+        outputTokenMint,
+        amountIn: new BN(10),
+        minimumAmountOut: new BN(0),
+        referralTokenAccount: null,
+      };
+      await swap(context.banksClient, swapParams);
+
+      const oracleState = await getOracle(context.banksClient, oracle);
+      expect(oracleState.observationCount.toNumber()).eq(2);
+      expect(oracleState.observations[1].timestamp.toString()).eq(
+        timestamp.toString()
+      );
+      // price before the swap is held for 100 seconds
+      expect(oracleState.observations[1].sqrtPriceCumulative.toString()).eq(
+        poolState.sqrtPrice.muln(100).toString()
+      );
+    });
+
+    it("User swap exact out A->B", async () => {
+      const addLiquidityParams: AddLiquidityParams = {
+        owner: user,
+        pool,
+        position,
+        liquidityDelta: new BN(MIN_SQRT_PRICE.muln(30)),
+        tokenAAmountThreshold: new BN(200),
+        tokenBAmountThreshold: new BN(200),
+      };
+      await addLiquidity(context.banksClient, addLiquidityParams);
+
+      const userOutputTokenAccount = getAssociatedTokenAddressSync(
+        outputTokenMint,
+        user.publicKey
+      );
+      const outputBalanceBefore = await getTokenAccount(
+        context.banksClient,
+        userOutputTokenAccount
+      );
+
+      const swapExactOutParams: SwapExactOutParams = {
+        payer: user,
+        pool,
+        inputTokenMint,
+        outputTokenMint,
+        amountOut: new BN(10),
+        maximumAmountIn: new BN(1_000),
+        referralTokenAccount: null,
+      };
+
+      await swapExactOut(context.banksClient, swapExactOutParams);
+
+      const outputBalanceAfter = await getTokenAccount(
+        context.banksClient,
+        userOutputTokenAccount
+      );
+      expect(
+        new BN(outputBalanceAfter.amount.toString())
+          .sub(new BN(outputBalanceBefore.amount.toString()))
+          .toNumber()
+      ).eq(10);
+    });
+
+    it("User swap A->B with price limit fills partially", async () => {
+      const addLiquidityParams: AddLiquidityParams = {
+        owner: user,
+        pool,
+        position,
+        liquidityDelta: new BN(MIN_SQRT_PRICE.muln(30)),
+        tokenAAmountThreshold: new BN(200),
+        tokenBAmountThreshold: new BN(200),
+      };
+      await addLiquidity(context.banksClient, addLiquidityParams);
+
+      const poolState = await getPool(context.banksClient, pool);
+      const sqrtPriceLimit = poolState.sqrtPrice.sub(
+        poolState.sqrtPrice.divn(100)
+      );
+
+      const userInputTokenAccount = getAssociatedTokenAddressSync(
+        inputTokenMint,
+        user.publicKey
+      );
+      const inputBalanceBefore = await getTokenAccount(
+        context.banksClient,
+        userInputTokenAccount
+      );
+
+      const amountIn = new BN(1_000_000_000);
+      const swapParams: SwapWithPriceLimitParams = {
+        payer: user,
+        pool,
+        inputTokenMint,
+        outputTokenMint,
+        amountIn,
+        minimumAmountOut: new BN(0),
+        sqrtPriceLimit,
+        referralTokenAccount: null,
+      };
+
+      await swapWithPriceLimit(context.banksClient, swapParams);
+
+      const inputBalanceAfter = await getTokenAccount(
+        context.banksClient,
+        userInputTokenAccount
+      );
+      const consumedAmount = new BN(inputBalanceBefore.amount.toString()).sub(
+        new BN(inputBalanceAfter.amount.toString())
+      );
+      expect(consumedAmount.lt(amountIn)).to.be.true;
+
+      const poolStateAfter = await getPool(context.banksClient, pool);
+      expect(poolStateAfter.sqrtPrice.eq(sqrtPriceLimit)).to.be.true;
+    });
   });
 
   describe("Token 2022", () => {
@@ -251,6 +411,7 @@ describe("Swap token", () => {
         poolCreatorAuthority: PublicKey.default,
         activationType: 0,
         collectFeeMode: 0,
+        protocolFeeReceiver: PublicKey.default,
       };
 
       config = await createConfigIx(